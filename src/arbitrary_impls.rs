@@ -0,0 +1,28 @@
+//! Generating arenas for fuzzing, behind the `arbitrary` feature.
+use std::vec::Vec;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{Arena, Index};
+
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Arena<T> {
+    /// Build an arena by interleaving insertions and removals, so it has
+    /// vacant slots with advanced generations, like a long-lived arena.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut arena = Arena::new();
+        let mut live: Vec<Index> = Vec::new();
+
+        // Choosing between insertion and removal consumes data, and only
+        // an insertion into an empty arena skips it, so this stops.
+        while !u.is_empty() {
+            if !live.is_empty() && u.ratio(1, 3)? {
+                let index = live.swap_remove(u.choose_index(live.len())?);
+                arena.remove(index);
+            } else {
+                live.push(arena.insert(T::arbitrary(u)?));
+            }
+        }
+
+        Ok(arena)
+    }
+}
@@ -0,0 +1,338 @@
+//! Versioned binary encoding of arenas, behind the `std` feature.
+//!
+//! All integers are little endian, and sizes are stored as `u64`. An
+//! encoded arena starts with the magic bytes `ARNA` and a format version,
+//! followed by the arena's generation, the head of its free list, and
+//! every slot:
+//!
+//! - `0`: vacant, with the slot's generation and next free slot
+//! - `1`: reserved, with the slot's generation
+//! - `2`: occupied, with the slot's generation and the encoded item
+//!
+//! Free slots are stored as `u64::MAX` when absent. Slot layout,
+//! generations and the free list round-trip exactly, so indices saved
+//! alongside an arena stay valid after loading.
+//!
+//! Registered callbacks, links and tracking state are not encoded.
+use std::io::{self, Read, Write};
+use std::num::NonZeroUsize;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::{Arena, Entry, Index};
+
+const MAGIC: [u8; 4] = *b"ARNA";
+const VERSION: u8 = 1;
+
+const VACANT: u8 = 0;
+const RESERVED: u8 = 1;
+const OCCUPIED: u8 = 2;
+const NONE: u64 = u64::MAX;
+
+/// Type that can be written in the binary format, see [`Arena::write_to`].
+pub trait Encode {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+/// Type that can be read from the binary format, see [`Arena::read_from`].
+pub trait Decode: Sized {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+impl<T: Encode> Arena<T> {
+    /// Write the arena in the binary format.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(1u32);
+    /// let index1 = arena.insert(2u32);
+    /// arena.remove(index0);
+    ///
+    /// let mut bytes = Vec::new();
+    /// arena.write_to(&mut bytes).unwrap();
+    ///
+    /// let loaded = Arena::<u32>::read_from(bytes.as_slice()).unwrap();
+    /// assert_eq!(loaded.get(index0), None);
+    /// assert_eq!(loaded.get(index1), Some(&2));
+    /// ```
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.write_to_with(writer, |item, writer| item.encode(writer))
+    }
+}
+
+impl<T: Decode> Arena<T> {
+    /// Read an arena written by [`Arena::write_to`].
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the data has another
+    /// version, or doesn't describe a consistent arena.
+    pub fn read_from<R: Read>(reader: R) -> io::Result<Self> {
+        Self::read_from_with(reader, |reader| T::decode(reader))
+    }
+}
+
+impl<T> Arena<T> {
+    /// Write the arena in the binary format, encoding each item with `f`.
+    pub fn write_to_with<W, F>(&self, mut writer: W, mut f: F) -> io::Result<()>
+    where
+        W: Write,
+        F: FnMut(&T, &mut W) -> io::Result<()>,
+    {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        self.generation.encode(&mut writer)?;
+        encode_slot(self.free_head, &mut writer)?;
+        (self.data.len() as u64).encode(&mut writer)?;
+
+        for entry in &self.data {
+            match entry {
+                Entry::Vacant { generation, next } => {
+                    VACANT.encode(&mut writer)?;
+                    generation.encode(&mut writer)?;
+                    encode_slot(*next, &mut writer)?;
+                }
+                Entry::Reserved { generation } => {
+                    RESERVED.encode(&mut writer)?;
+                    generation.encode(&mut writer)?;
+                }
+                Entry::Occupied { generation, item } => {
+                    OCCUPIED.encode(&mut writer)?;
+                    generation.encode(&mut writer)?;
+                    f(item, &mut writer)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read an arena written by [`Arena::write_to_with`], decoding
+    /// each item with `f`.
+    ///
+    /// See [`Arena::read_from`].
+    pub fn read_from_with<R, F>(mut reader: R, mut f: F) -> io::Result<Self>
+    where
+        R: Read,
+        F: FnMut(&mut R) -> io::Result<T>,
+    {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+        if header[..4] != MAGIC {
+            return Err(invalid_data("not an encoded arena"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid_data(format_args!("unsupported format version {}", header[4])));
+        }
+
+        let generation = NonZeroUsize::decode(&mut reader)?;
+        let free_head = decode_slot(&mut reader)?;
+        let len = decode_usize(&mut reader)?;
+        if len > Index::SLOT_MASK {
+            return Err(invalid_data("too many slots"));
+        }
+
+        // The length isn't trusted to allocate up front.
+        let mut data = Vec::with_capacity(len.min(4096));
+        let mut count = 0;
+        for _ in 0..len {
+            let kind = u8::decode(&mut reader)?;
+            let generation = NonZeroUsize::decode(&mut reader)?;
+            data.push(match kind {
+                VACANT => Entry::Vacant {
+                    generation,
+                    next: decode_slot(&mut reader)?,
+                },
+                RESERVED => Entry::Reserved { generation },
+                OCCUPIED => {
+                    count += 1;
+                    Entry::Occupied {
+                        generation,
+                        item: f(&mut reader)?,
+                    }
+                }
+                kind => return Err(invalid_data(format_args!("unknown slot kind {}", kind))),
+            });
+        }
+
+        let mut arena = Arena::new();
+        arena.data = data;
+        arena.generation = generation;
+        arena.free_head = free_head;
+        arena.count = count;
+        // Indices saved alongside the arena were stamped by the original.
+        #[cfg(feature = "debug-checks")]
+        {
+            arena.id = 0;
+        }
+
+        arena.validate().map_err(invalid_data)?;
+        Ok(arena)
+    }
+}
+
+fn invalid_data<E: std::fmt::Display>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+fn encode_slot<W: Write>(slot: Option<usize>, writer: &mut W) -> io::Result<()> {
+    slot.map_or(NONE, |slot| slot as u64).encode(writer)
+}
+
+fn decode_slot<R: Read>(reader: &mut R) -> io::Result<Option<usize>> {
+    match u64::decode(reader)? {
+        NONE => Ok(None),
+        slot => usize::try_from(slot)
+            .map(Some)
+            .map_err(|_| invalid_data("slot out of range")),
+    }
+}
+
+fn decode_usize<R: Read>(reader: &mut R) -> io::Result<usize> {
+    usize::try_from(u64::decode(reader)?).map_err(|_| invalid_data("size out of range"))
+}
+
+macro_rules! impl_number {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    let mut bytes = [0; core::mem::size_of::<$ty>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Encode for usize {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u64).encode(writer)
+    }
+}
+
+impl Decode for usize {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        decode_usize(reader)
+    }
+}
+
+impl Encode for NonZeroUsize {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.get().encode(writer)
+    }
+}
+
+impl Decode for NonZeroUsize {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        NonZeroUsize::new(usize::decode(reader)?).ok_or_else(|| invalid_data("generation is zero"))
+    }
+}
+
+impl Encode for bool {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u8).encode(writer)
+    }
+}
+
+impl Decode for bool {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match u8::decode(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => Err(invalid_data(format_args!("invalid bool {}", byte))),
+        }
+    }
+}
+
+impl Encode for str {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.len().encode(writer)?;
+        writer.write_all(self.as_bytes())
+    }
+}
+
+impl Encode for String {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.as_str().encode(writer)
+    }
+}
+
+impl Decode for String {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = usize::decode(reader)?;
+        let mut bytes = Vec::new();
+        reader.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        String::from_utf8(bytes).map_err(invalid_data)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            None => false.encode(writer),
+            Some(value) => {
+                true.encode(writer)?;
+                value.encode(writer)
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match bool::decode(reader)? {
+            false => Ok(None),
+            true => T::decode(reader).map(Some),
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.len().encode(writer)?;
+        self.iter().try_for_each(|value| value.encode(writer))
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = usize::decode(reader)?;
+        let mut values = Vec::with_capacity(len.min(4096));
+        for _ in 0..len {
+            values.push(T::decode(reader)?);
+        }
+        Ok(values)
+    }
+}
+
+impl Encode for Index {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.slot().encode(writer)?;
+        self.generation.encode(writer)?;
+        self.tag().encode(writer)
+    }
+}
+
+impl Decode for Index {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let slot = usize::decode(reader)?;
+        if slot > Index::SLOT_MASK {
+            return Err(invalid_data(format_args!("slot {} is out of range", slot)));
+        }
+        let generation = NonZeroUsize::decode(reader)?;
+        let tag = u8::decode(reader)?;
+        Ok(Index::new(slot, generation).with_tag(tag))
+    }
+}
@@ -0,0 +1,230 @@
+//! Arena of boxed items, for unsized types such as trait objects.
+use alloc::boxed::Box;
+use core::fmt;
+
+use crate::{Arena, Index, IterIndexed, IterIndexedMut};
+
+/// An [`Arena`] holding unsized items, like `dyn Trait` or slices, each in
+/// its own [`Box`].
+///
+/// Indices behave exactly as in [`Arena`]. Items are inserted already boxed,
+/// and accessed through references to the unsized type.
+///
+/// ```
+/// # use arena::BoxArena;
+/// trait Component {
+///     fn name(&self) -> &str;
+/// }
+///
+/// struct Health(u32);
+/// struct Sprite;
+///
+/// impl Component for Health {
+///     fn name(&self) -> &str {
+///         "health"
+///     }
+/// }
+///
+/// impl Component for Sprite {
+///     fn name(&self) -> &str {
+///         "sprite"
+///     }
+/// }
+///
+/// let mut components = BoxArena::<dyn Component>::new();
+/// let health = components.insert_boxed(Box::new(Health(10)));
+/// let sprite = components.insert_boxed(Box::new(Sprite));
+///
+/// assert_eq!(components.get(health).map(|c| c.name()), Some("health"));
+/// assert_eq!(components[sprite].name(), "sprite");
+/// ```
+pub struct BoxArena<T: ?Sized> {
+    arena: Arena<Box<T>>,
+}
+
+impl<T: ?Sized> BoxArena<T> {
+    pub fn new() -> Self {
+        Self::from_arena(Arena::new())
+    }
+
+    /// Create an empty arena with room for at least `capacity` items
+    /// before reallocating the slots.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_arena(Arena::with_capacity(capacity))
+    }
+
+    /// Wrap an arena of boxes. Its indices stay valid.
+    pub fn from_arena(arena: Arena<Box<T>>) -> Self {
+        BoxArena { arena }
+    }
+
+    /// Unwrap the underlying arena of boxes.
+    pub fn into_arena(self) -> Arena<Box<T>> {
+        self.arena
+    }
+
+    /// The underlying arena of boxes.
+    pub fn as_arena(&self) -> &Arena<Box<T>> {
+        &self.arena
+    }
+
+    /// The underlying arena of boxes, for operations not exposed here.
+    pub fn as_arena_mut(&mut self) -> &mut Arena<Box<T>> {
+        &mut self.arena
+    }
+
+    /// Insert the boxed item, see [`Arena::insert`].
+    pub fn insert_boxed(&mut self, item: Box<T>) -> Index {
+        self.arena.insert(item)
+    }
+
+    /// See [`Arena::remove`].
+    pub fn remove(&mut self, index: Index) {
+        self.arena.remove(index)
+    }
+
+    /// Remove and return the boxed item, see [`Arena::take`].
+    pub fn take(&mut self, index: Index) -> Option<Box<T>> {
+        self.arena.take(index)
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        self.arena.get(index).map(|item| &**item)
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        self.arena.get_mut(index).map(|item| &mut **item)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.arena.contains(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Remove every item. Indices handed out before are invalidated.
+    pub fn clear(&mut self) {
+        self.arena.clear()
+    }
+
+    /// Iterate the items together with their indices.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.arena.iter_indexed(),
+        }
+    }
+
+    /// Mutably iterate the items together with their indices.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.arena.iter_indexed_mut(),
+        }
+    }
+}
+
+impl<T> BoxArena<T> {
+    /// Box and insert a sized item.
+    pub fn insert(&mut self, item: T) -> Index {
+        self.insert_boxed(Box::new(item))
+    }
+}
+
+impl<T: ?Sized> Default for BoxArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> Clone for BoxArena<T>
+where
+    Box<T>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::from_arena(self.arena.clone())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for BoxArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BoxArena").field(&self.arena).finish()
+    }
+}
+
+impl<T: ?Sized> From<Arena<Box<T>>> for BoxArena<T> {
+    fn from(arena: Arena<Box<T>>) -> Self {
+        Self::from_arena(arena)
+    }
+}
+
+impl<T: ?Sized> core::ops::Index<Index> for BoxArena<T> {
+    type Output = T;
+
+    /// # Panic
+    ///
+    /// Panics if `index` is stale or out of bounds.
+    fn index(&self, index: Index) -> &T {
+        self.get(index).expect("index is stale or out of bounds")
+    }
+}
+
+impl<T: ?Sized> core::ops::IndexMut<Index> for BoxArena<T> {
+    fn index_mut(&mut self, index: Index) -> &mut T {
+        self.get_mut(index).expect("index is stale or out of bounds")
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a, T: ?Sized> {
+    inner: IterIndexed<'a, Box<T>>,
+}
+
+impl<'a, T: ?Sized> Iterator for Iter<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, item)| (index, &**item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: ?Sized> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, item)| (index, &**item))
+    }
+}
+
+impl<T: ?Sized> ExactSizeIterator for Iter<'_, T> {}
+
+#[derive(Debug)]
+pub struct IterMut<'a, T: ?Sized> {
+    inner: IterIndexedMut<'a, Box<T>>,
+}
+
+impl<'a, T: ?Sized> Iterator for IterMut<'a, T> {
+    type Item = (Index, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, item)| (index, &mut **item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: ?Sized> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, item)| (index, &mut **item))
+    }
+}
+
+impl<T: ?Sized> ExactSizeIterator for IterMut<'_, T> {}
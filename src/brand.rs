@@ -0,0 +1,192 @@
+//! Indices branded to a single arena, which resolve without any checks.
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{Arena, Index, IterIndexed};
+
+/// Invariant lifetime, unique to each call of [`Arena::scope`].
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+/// An index checked to point to an item of the [`BrandedArena`]
+/// with the same brand `'id`.
+///
+/// Items can't be removed from a branded arena, so a branded index
+/// stays valid for the whole scope, and resolves without checking the
+/// generation or bounds.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BrandedIndex<'id> {
+    index: Index,
+    brand: Brand<'id>,
+}
+
+impl BrandedIndex<'_> {
+    /// The plain index, for use outside of the scope.
+    pub fn index(self) -> Index {
+        self.index
+    }
+}
+
+impl fmt::Debug for BrandedIndex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BrandedIndex").field(&self.index).finish()
+    }
+}
+
+/// Access to an [`Arena`] for the duration of [`Arena::scope`].
+///
+/// Items can be inserted and modified, but not removed.
+pub struct BrandedArena<'id, 'a, T> {
+    arena: &'a mut Arena<T>,
+    brand: Brand<'id>,
+}
+
+impl<T> Arena<T> {
+    /// Call `f` with a branded view of the arena, which hands out
+    /// [`BrandedIndex`] handles that resolve without any checks.
+    ///
+    /// Each call has its own brand, so branded indices can't be used with
+    /// another arena, or escape the scope:
+    ///
+    /// ```compile_fail
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// let escaped = arena.scope(|arena| arena.brand(index).unwrap());
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use arena::Arena;
+    /// # let mut arena0 = Arena::new();
+    /// # let mut arena1 = Arena::new();
+    /// let index = arena0.insert("Foo");
+    /// arena1.insert("Bar");
+    ///
+    /// arena0.scope(|arena0| {
+    ///     arena1.scope(|arena1| {
+    ///         let branded = arena0.brand(index).unwrap();
+    ///         arena1.get(branded);
+    ///     })
+    /// });
+    /// ```
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(1);
+    /// let index1 = arena.insert(2);
+    ///
+    /// arena.scope(|mut arena| {
+    ///     let a = arena.brand(index0).unwrap();
+    ///     let b = arena.brand(index1).unwrap();
+    ///     for _ in 0..10 {
+    ///         *arena.get_mut(a) += *arena.get(b);
+    ///     }
+    /// });
+    /// assert_eq!(arena.get(index0), Some(&21));
+    /// ```
+    pub fn scope<R, F>(&mut self, f: F) -> R
+    where
+        F: for<'id> FnOnce(BrandedArena<'id, '_, T>) -> R,
+    {
+        f(BrandedArena {
+            arena: self,
+            brand: PhantomData,
+        })
+    }
+}
+
+impl<'id, T> BrandedArena<'id, '_, T> {
+    /// Check that `index` points to an item, and brand it.
+    pub fn brand(&self, index: Index) -> Option<BrandedIndex<'id>> {
+        self.arena.get(index).map(|_| BrandedIndex {
+            index,
+            brand: PhantomData,
+        })
+    }
+
+    pub fn get(&self, index: BrandedIndex<'id>) -> &T {
+        // SAFETY: Branded indices are only created for items of this
+        // arena, and items can't be removed while it's branded.
+        unsafe { self.arena.get_unchecked(index.index) }
+    }
+
+    pub fn get_mut(&mut self, index: BrandedIndex<'id>) -> &mut T {
+        // SAFETY: See `get`.
+        unsafe { self.arena.get_unchecked_mut(index.index) }
+    }
+
+    /// Insert the item into the first free slot, and return its branded index.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item would exceed the arena's [memory budget](Arena::set_memory_budget)
+    /// or [limit](Arena::with_limit). The budget handler isn't invoked,
+    /// because it could remove items.
+    pub fn insert(&mut self, item: T) -> BrandedIndex<'id> {
+        self.arena.assert_below_limit();
+        if self.arena.budget.is_some() {
+            let size = self.arena.item_size(&item);
+            let appends = self.arena.free_head.is_none();
+            assert!(
+                self.arena.try_reserve_budget(size, appends),
+                "arena memory budget exceeded"
+            );
+        }
+
+        BrandedIndex {
+            index: self.arena.insert_entry(item),
+            brand: PhantomData,
+        }
+    }
+
+    /// Iterate the items together with their branded indices.
+    pub fn iter(&self) -> Iter<'id, '_, T> {
+        Iter {
+            inner: self.arena.iter_indexed(),
+            brand: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Read-only access to the whole arena.
+    pub fn as_arena(&self) -> &Arena<T> {
+        self.arena
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BrandedArena<'_, '_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BrandedArena").field(&self.arena).finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'id, 'a, T> {
+    inner: IterIndexed<'a, T>,
+    brand: Brand<'id>,
+}
+
+impl<'id, 'a, T> Iterator for Iter<'id, 'a, T> {
+    type Item = (BrandedIndex<'id>, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, item)| {
+            let index = BrandedIndex {
+                index,
+                brand: PhantomData,
+            };
+            (index, item)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
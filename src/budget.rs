@@ -0,0 +1,245 @@
+//! Memory budget enforcement.
+use core::fmt;
+use core::mem::size_of;
+
+use crate::{Arena, Entry, Index};
+
+/// Function measuring the heap memory owned by an item, in bytes.
+pub type SizeFn<T> = fn(&T) -> usize;
+
+/// Function invoked when an insertion would exceed the memory budget,
+/// with the number of bytes that need to be freed.
+pub type BudgetFn<T> = fn(&mut Arena<T>, usize);
+
+#[derive(Debug)]
+pub(crate) struct Budget<T> {
+    limit: usize,
+    sizer: SizeFn<T>,
+    handler: Option<BudgetFn<T>>,
+    /// Sum of the measured sizes of all live items.
+    item_bytes: usize,
+}
+
+impl<T> Clone for Budget<T> {
+    fn clone(&self) -> Self {
+        Self {
+            limit: self.limit,
+            sizer: self.sizer,
+            handler: self.handler,
+            item_bytes: self.item_bytes,
+        }
+    }
+}
+
+impl<T> Arena<T> {
+    /// Limit the memory used by the arena to `limit` bytes.
+    ///
+    /// Memory usage is the size of the backing storage, plus the heap
+    /// memory owned by each item as measured by `sizer` at the time it
+    /// was inserted. Use `|_| 0` to only account for the storage.
+    ///
+    /// Once set, [`Arena::try_insert`] refuses items that would exceed the
+    /// budget, while [`Arena::insert`] and [`Arena::push`] panic. Storage
+    /// growth is capped to what the budget allows.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let mut arena = Arena::<Vec<u8>>::new();
+    /// arena.set_memory_budget(4096, |item| item.capacity());
+    ///
+    /// assert!(arena.try_insert(vec![0; 1024]).is_ok());
+    /// assert!(arena.try_insert(vec![0; 4096]).is_err());
+    /// ```
+    pub fn set_memory_budget(&mut self, limit: usize, sizer: SizeFn<T>) {
+        let handler = self.budget.as_ref().and_then(|budget| budget.handler);
+        let item_bytes = self.iter().map(sizer).sum();
+
+        self.budget = Some(Budget {
+            limit,
+            sizer,
+            handler,
+            item_bytes,
+        });
+    }
+
+    /// Register a function which is invoked when an insertion would exceed
+    /// the memory budget, giving it a chance to evict items or compact
+    /// the arena before the budget is checked again.
+    ///
+    /// Has no effect unless a budget is set.
+    pub fn set_budget_handler(&mut self, handler: BudgetFn<T>) {
+        if let Some(budget) = &mut self.budget {
+            budget.handler = Some(handler);
+        }
+    }
+
+    /// Remove the memory budget and its handler.
+    pub fn clear_memory_budget(&mut self) {
+        self.budget = None;
+    }
+
+    /// Bytes of memory used by the arena, as accounted for by the budget.
+    ///
+    /// Without a budget, only the backing storage is counted.
+    pub fn memory_usage(&self) -> usize {
+        self.storage_bytes(self.data.capacity()) + self.budget.as_ref().map_or(0, |budget| budget.item_bytes)
+    }
+
+    /// Insert the item into the first free slot, unless it would exceed
+    /// the arena's memory budget or limit.
+    ///
+    /// See [`Arena::set_memory_budget`] and [`Arena::with_limit`].
+    pub fn try_insert(&mut self, item: T) -> Result<Index, Full<T>> {
+        if !self.is_full() && self.reserve_budget(&item, self.free_head.is_none()) {
+            Ok(self.insert_entry(item))
+        } else {
+            Err(Full(item))
+        }
+    }
+
+    /// Check whether `item` fits in the budget, invoking the handler once
+    /// if it doesn't. When `appends` is set and storage is full, capacity is
+    /// reserved up front, growing no further than the budget allows.
+    pub(crate) fn reserve_budget(&mut self, item: &T, appends: bool) -> bool {
+        let item_size = self.item_size(item);
+        self.reserve_budget_bytes(item_size, appends)
+    }
+
+    /// Like [`Arena::reserve_budget`], for an item taking the place of
+    /// the one in `slot`, so only its growth counts.
+    pub(crate) fn reserve_budget_replacing(&mut self, item: &T, slot: usize) -> bool {
+        let original = match &self.data[slot] {
+            Entry::Occupied { item, .. } => self.item_size(item),
+            Entry::Vacant { .. } | Entry::Reserved { .. } => 0,
+        };
+        let growth = self.item_size(item).saturating_sub(original);
+        self.reserve_budget_bytes(growth, false)
+    }
+
+    /// Like [`Arena::reserve_budget`], for an item of `item_size` bytes.
+    pub(crate) fn reserve_budget_bytes(&mut self, item_size: usize, appends: bool) -> bool {
+        let handler = match &self.budget {
+            Some(budget) => budget.handler,
+            None => return true,
+        };
+
+        if self.try_reserve_budget(item_size, appends) {
+            return true;
+        }
+
+        if let Some(handler) = handler {
+            let needed = self.budget_needed(item_size, appends);
+            handler(self, needed);
+
+            // The handler may have freed up a slot.
+            let appends = appends && self.free_head.is_none();
+            return self.try_reserve_budget(item_size, appends);
+        }
+
+        false
+    }
+
+    pub(crate) fn try_reserve_budget(&mut self, item_size: usize, appends: bool) -> bool {
+        if self.budget_needed(item_size, appends) > 0 {
+            return false;
+        }
+
+        let len = self.data.len();
+        if appends && len == self.data.capacity() {
+            // Amortized growth, capped by the remaining budget.
+            let budget = self.budget.as_ref().unwrap();
+            let remaining = budget.limit - budget.item_bytes - item_size;
+            let affordable = match size_of::<Entry<T>>() {
+                0 => usize::MAX,
+                entry_size => remaining / entry_size,
+            };
+            let capacity = (len * 2).max(4).min(affordable).max(len + 1);
+            self.data.reserve_exact(capacity - len);
+        }
+
+        true
+    }
+
+    /// Bytes over budget if an item of `item_size` was inserted.
+    fn budget_needed(&self, item_size: usize, appends: bool) -> usize {
+        let budget = match &self.budget {
+            Some(budget) => budget,
+            None => return 0,
+        };
+
+        let len = self.data.len();
+        let capacity = if appends && len == self.data.capacity() {
+            len + 1
+        } else {
+            self.data.capacity()
+        };
+        let usage = self.storage_bytes(capacity) + budget.item_bytes + item_size;
+
+        usage.saturating_sub(budget.limit)
+    }
+
+    #[inline]
+    fn storage_bytes(&self, capacity: usize) -> usize {
+        capacity * size_of::<Entry<T>>()
+    }
+
+    #[inline]
+    pub(crate) fn item_size(&self, item: &T) -> usize {
+        self.budget.as_ref().map_or(0, |budget| (budget.sizer)(item))
+    }
+
+    /// Measure every item again, after the items were replaced wholesale.
+    pub(crate) fn recount_item_bytes(&mut self) {
+        if let Some(budget) = &self.budget {
+            let item_bytes = self.iter().map(budget.sizer).sum();
+            self.budget.as_mut().unwrap().item_bytes = item_bytes;
+        }
+    }
+
+    #[inline]
+    pub(crate) fn add_item_bytes(&mut self, size: usize) {
+        if let Some(budget) = &mut self.budget {
+            budget.item_bytes += size;
+        }
+    }
+
+    #[inline]
+    pub(crate) fn account_added(&mut self, item: &T) {
+        let size = self.item_size(item);
+        self.add_item_bytes(size);
+    }
+
+    #[inline]
+    pub(crate) fn account_removed(&mut self, item: &T) {
+        if let Some(budget) = &mut self.budget {
+            budget.item_bytes = budget.item_bytes.saturating_sub((budget.sizer)(item));
+        }
+    }
+}
+
+/// Error returned when an item can't be inserted because the arena is full.
+///
+/// Holds the rejected item.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Full<T>(pub T);
+
+impl<T> Full<T> {
+    /// Take back the item that couldn't be inserted.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for Full<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Full").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for Full<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arena is full")
+    }
+}
+
+impl<T> core::error::Error for Full<T> {}
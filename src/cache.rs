@@ -0,0 +1,245 @@
+//! Arena evicting its least recently used items, for caches.
+use alloc::vec::Vec;
+
+use crate::{Arena, Entry, Index, Iter, IterIndexed};
+
+/// Function called with every item evicted from a [`CacheArena`].
+pub type EvictFn<T> = fn(Index, T);
+
+/// Neighbours of a slot in the recency list.
+#[derive(Debug, Default, Clone, Copy)]
+struct Link {
+    /// Slot used more recently.
+    newer: Option<usize>,
+    /// Slot used less recently.
+    older: Option<usize>,
+}
+
+/// Arena holding up to a fixed number of items, which evicts the least
+/// recently used item to make room for a new one.
+///
+/// Inserting and accessing an item through [`CacheArena::get`] or
+/// [`CacheArena::get_mut`] marks it as the most recently used.
+/// [`CacheArena::peek`] reads an item without affecting its recency.
+///
+/// ```
+/// # use arena::CacheArena;
+/// let mut cache = CacheArena::new(2);
+/// let index0 = cache.insert("Foo");
+/// let index1 = cache.insert("Bar");
+///
+/// cache.get(index0);
+/// let index2 = cache.insert("Baz");
+///
+/// assert_eq!(cache.peek(index0), Some(&"Foo"));
+/// assert_eq!(cache.peek(index1), None);
+/// # assert_eq!(cache.peek(index2), Some(&"Baz"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CacheArena<T> {
+    arena: Arena<T>,
+    links: Vec<Link>,
+    newest: Option<usize>,
+    oldest: Option<usize>,
+    capacity: usize,
+    on_evict: Option<EvictFn<T>>,
+}
+
+impl<T> CacheArena<T> {
+    /// Create an empty cache holding up to `capacity` items.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "cache capacity must be greater than zero");
+
+        Self {
+            arena: Arena::new(),
+            links: Vec::new(),
+            newest: None,
+            oldest: None,
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    /// Register a function which is called with every evicted item.
+    /// Replaces any previously registered one.
+    pub fn set_eviction_handler(&mut self, on_evict: EvictFn<T>) {
+        self.on_evict = Some(on_evict);
+    }
+
+    /// Remove the eviction handler, if any.
+    pub fn clear_eviction_handler(&mut self) {
+        self.on_evict = None;
+    }
+
+    /// Maximum number of items the cache holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the maximum number of items, evicting the least
+    /// recently used ones until they fit.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        assert!(capacity > 0, "cache capacity must be greater than zero");
+        self.capacity = capacity;
+        while self.arena.len() > capacity {
+            self.evict();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Insert the item as the most recently used one, evicting the least
+    /// recently used item first if the cache is full.
+    pub fn insert(&mut self, item: T) -> Index {
+        if self.arena.len() >= self.capacity {
+            self.evict();
+        }
+
+        let index = self.arena.insert(item);
+        let slot = index.slot();
+        if slot >= self.links.len() {
+            self.links.resize(slot + 1, Link::default());
+        }
+        self.push_newest(slot);
+        index
+    }
+
+    /// Return a reference to the item, and mark it as the most recently used.
+    pub fn get(&mut self, index: Index) -> Option<&T> {
+        self.touch(index);
+        self.arena.get(index)
+    }
+
+    /// Return a mutable reference to the item, and mark it as the most recently used.
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        self.touch(index);
+        self.arena.get_mut(index)
+    }
+
+    /// Return a reference to the item, without affecting its recency.
+    pub fn peek(&self, index: Index) -> Option<&T> {
+        self.arena.get(index)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.arena.contains(index)
+    }
+
+    /// Remove and return the item, without calling the eviction handler.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn take(&mut self, index: Index) -> Option<T> {
+        let item = self.arena.take(index)?;
+        self.unlink(index.slot());
+        Some(item)
+    }
+
+    /// Remove the item, without calling the eviction handler.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: Index) {
+        self.take(index);
+    }
+
+    /// Index of the least recently used item, which is evicted next.
+    pub fn oldest(&self) -> Option<Index> {
+        self.oldest.map(|slot| self.index_of(slot))
+    }
+
+    /// Index of the most recently used item.
+    pub fn newest(&self) -> Option<Index> {
+        self.newest.map(|slot| self.index_of(slot))
+    }
+
+    /// Iterate the items in slot order, without affecting their recency.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.arena.iter()
+    }
+
+    /// Iterate the items in slot order together with their indices,
+    /// without affecting their recency.
+    pub fn iter_indexed(&self) -> IterIndexed<'_, T> {
+        self.arena.iter_indexed()
+    }
+
+    /// Remove every item, without calling the eviction handler.
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        self.links.clear();
+        self.newest = None;
+        self.oldest = None;
+    }
+
+    /// Read-only access to the underlying arena.
+    pub fn as_arena(&self) -> &Arena<T> {
+        &self.arena
+    }
+
+    /// Remove the least recently used item, and pass it to the eviction handler.
+    fn evict(&mut self) {
+        let Some(index) = self.oldest() else {
+            return;
+        };
+
+        let item = self.take(index).unwrap();
+        if let Some(on_evict) = self.on_evict {
+            on_evict(index, item);
+        }
+    }
+
+    fn touch(&mut self, index: Index) {
+        if self.arena.contains(index) && self.newest != Some(index.slot()) {
+            self.unlink(index.slot());
+            self.push_newest(index.slot());
+        }
+    }
+
+    fn index_of(&self, slot: usize) -> Index {
+        match &self.arena.data[slot] {
+            Entry::Occupied { generation, .. } => self.arena.stamp(Index::new(slot, *generation)),
+            _ => unreachable!("recency list points to a vacant slot"),
+        }
+    }
+
+    fn push_newest(&mut self, slot: usize) {
+        self.links[slot] = Link {
+            newer: None,
+            older: self.newest,
+        };
+        match self.newest {
+            Some(newest) => self.links[newest].newer = Some(slot),
+            None => self.oldest = Some(slot),
+        }
+        self.newest = Some(slot);
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let Link { newer, older } = core::mem::take(&mut self.links[slot]);
+        match newer {
+            Some(newer) => self.links[newer].older = older,
+            None => self.newest = older,
+        }
+        match older {
+            Some(older) => self.links[older].newer = newer,
+            None => self.oldest = newer,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Arena whose items are borrowed one at a time through a shared reference.
+use core::cell::{Ref, RefCell, RefMut};
+use core::fmt;
+
+use crate::{Arena, ArenaError, Index};
+
+/// Arena tracking borrows of each item separately, like a `RefCell`
+/// around every item.
+///
+/// Items are borrowed through `&self`, so several disjoint items can be
+/// mutably borrowed at once, from code that can't be handed a
+/// `&mut Arena`. Borrowing an item that is already mutably borrowed, or
+/// mutably borrowing one that is borrowed at all, fails like it would
+/// with a `RefCell`. Inserting and removing items still takes `&mut self`,
+/// so it can't happen while any item is borrowed.
+///
+/// ```
+/// # use arena::ArenaCell;
+/// let mut arena = ArenaCell::new();
+/// let index0 = arena.insert(1);
+/// let index1 = arena.insert(2);
+///
+/// let arena = &arena;
+/// let mut a = arena.borrow_mut(index0).unwrap();
+/// let mut b = arena.borrow_mut(index1).unwrap();
+/// core::mem::swap(&mut *a, &mut *b);
+/// assert!(arena.try_borrow(index0).is_err());
+///
+/// drop((a, b));
+/// assert_eq!(*arena.borrow(index0).unwrap(), 2);
+/// ```
+pub struct ArenaCell<T> {
+    arena: Arena<RefCell<T>>,
+}
+
+/// Reason an item of an [`ArenaCell`] couldn't be borrowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorrowError {
+    /// The index doesn't resolve.
+    Unresolved(ArenaError),
+    /// The item is mutably borrowed, or borrowed at all for a mutable borrow.
+    Borrowed(Index),
+}
+
+impl fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BorrowError::Unresolved(err) => fmt::Display::fmt(err, f),
+            BorrowError::Borrowed(index) => write!(f, "item at {:?} is already borrowed", index),
+        }
+    }
+}
+
+impl core::error::Error for BorrowError {}
+
+impl<T> ArenaCell<T> {
+    pub fn new() -> Self {
+        Self { arena: Arena::new() }
+    }
+
+    /// Wrap the items of `arena`, which keep their indices.
+    pub fn from_arena(arena: Arena<T>) -> Self {
+        Self {
+            arena: arena.map(|_, item| RefCell::new(item)),
+        }
+    }
+
+    /// Unwrap the items into a plain arena, keeping their indices.
+    pub fn into_arena(self) -> Arena<T> {
+        self.arena.map(|_, cell| cell.into_inner())
+    }
+
+    pub fn insert(&mut self, item: T) -> Index {
+        self.arena.insert(RefCell::new(item))
+    }
+
+    /// Remove the item at the given `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: Index) {
+        self.take(index);
+    }
+
+    /// Remove and return the item at the given `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn take(&mut self, index: Index) -> Option<T> {
+        self.arena.take(index).map(RefCell::into_inner)
+    }
+
+    /// Borrow the item at `index`, or return `None` if the index doesn't resolve.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item is mutably borrowed, see [`ArenaCell::try_borrow`].
+    pub fn borrow(&self, index: Index) -> Option<Ref<'_, T>> {
+        self.arena.get(index).map(RefCell::borrow)
+    }
+
+    /// Mutably borrow the item at `index`, or return `None` if the index
+    /// doesn't resolve.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item is borrowed, see [`ArenaCell::try_borrow_mut`].
+    pub fn borrow_mut(&self, index: Index) -> Option<RefMut<'_, T>> {
+        self.arena.get(index).map(RefCell::borrow_mut)
+    }
+
+    /// Borrow the item at `index`, or return why it can't be.
+    pub fn try_borrow(&self, index: Index) -> Result<Ref<'_, T>, BorrowError> {
+        let cell = self.arena.try_get(index).map_err(BorrowError::Unresolved)?;
+        cell.try_borrow().map_err(|_| BorrowError::Borrowed(index))
+    }
+
+    /// Mutably borrow the item at `index`, or return why it can't be.
+    pub fn try_borrow_mut(&self, index: Index) -> Result<RefMut<'_, T>, BorrowError> {
+        let cell = self.arena.try_get(index).map_err(BorrowError::Unresolved)?;
+        cell.try_borrow_mut().map_err(|_| BorrowError::Borrowed(index))
+    }
+
+    /// Mutable access to the item at `index` without tracking the borrow,
+    /// which the exclusive reference makes unnecessary.
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        self.arena.get_mut(index).map(RefCell::get_mut)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.arena.contains(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Remove every item. Indices handed out before are invalidated.
+    pub fn clear(&mut self) {
+        self.arena.clear();
+    }
+}
+
+impl<T> Default for ArenaCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Arena<T>> for ArenaCell<T> {
+    fn from(arena: Arena<T>) -> Self {
+        Self::from_arena(arena)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArenaCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArenaCell").field(&self.arena).finish()
+    }
+}
@@ -0,0 +1,110 @@
+//! Detection of indices used with an arena other than the one that
+//! handed them out, behind the `debug-checks` feature.
+#[cfg(feature = "debug-checks")]
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use core::num::NonZeroUsize;
+
+use crate::{Arena, Index};
+
+#[cfg(feature = "debug-checks")]
+static NEXT_ARENA_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Unique ID for a new arena. IDs are only reused after the counter wraps.
+#[cfg(feature = "debug-checks")]
+pub(crate) fn next_arena_id() -> u32 {
+    NEXT_ARENA_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Mark of the arena an iterator hands out indices for, which outlives
+/// the borrow of the arena itself.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stamp {
+    #[cfg(feature = "debug-checks")]
+    id: u32,
+}
+
+impl Stamp {
+    /// Stamp of indices that no arena tracks, accepted by every arena.
+    pub(crate) const NONE: Stamp = Stamp {
+        #[cfg(feature = "debug-checks")]
+        id: 0,
+    };
+
+    /// Index of `slot` at `generation`, marked as handed out by the arena.
+    #[inline(always)]
+    pub(crate) fn index(self, slot: usize, generation: NonZeroUsize) -> Index {
+        #[cfg(feature = "debug-checks")]
+        {
+            Index {
+                arena: self.id,
+                ..Index::new(slot, generation)
+            }
+        }
+
+        #[cfg(not(feature = "debug-checks"))]
+        {
+            Index::new(slot, generation)
+        }
+    }
+}
+
+impl<T> Arena<T> {
+    /// Mark `index` as handed out by this arena.
+    #[inline(always)]
+    pub(crate) fn stamp(&self, index: Index) -> Index {
+        #[cfg(feature = "debug-checks")]
+        {
+            Index {
+                arena: self.id,
+                ..index
+            }
+        }
+
+        #[cfg(not(feature = "debug-checks"))]
+        {
+            index
+        }
+    }
+
+    /// Mark for iterators to stamp the indices they hand out with.
+    #[inline(always)]
+    pub(crate) fn stamper(&self) -> Stamp {
+        Stamp {
+            #[cfg(feature = "debug-checks")]
+            id: self.id,
+        }
+    }
+
+    /// Assert, in debug builds, that `index` wasn't handed out by another arena.
+    ///
+    /// Indices that weren't stamped by an arena, such as deserialized ones,
+    /// are accepted everywhere. So is every index by an arena with an ID
+    /// of `0`, such as a deserialized one.
+    #[inline(always)]
+    pub(crate) fn check_owner(&self, index: Index) {
+        #[cfg(feature = "debug-checks")]
+        debug_assert!(
+            index.arena == 0 || self.id == 0 || index.arena == self.id,
+            "index {:?} was handed out by another arena",
+            index
+        );
+
+        #[cfg(not(feature = "debug-checks"))]
+        let _ = index;
+    }
+
+    /// Share the ID of `other`, for an arena with the same slot layout,
+    /// whose indices are interchangeable with it.
+    #[inline(always)]
+    #[allow(dead_code)]
+    pub(crate) fn inherit_id<U>(&mut self, other: &Arena<U>) {
+        #[cfg(feature = "debug-checks")]
+        {
+            self.id = other.id;
+        }
+
+        #[cfg(not(feature = "debug-checks"))]
+        let _ = other;
+    }
+}
@@ -0,0 +1,423 @@
+//! Compaction of fragmented arenas.
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::secondary::LinkEvent;
+use crate::{Arena, ChangeKind, Entry, GenerationOverflow, Index, IndexRemap, Keys};
+
+/// Automatic compaction settings, see [`Arena::compact_when`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CompactionPolicy {
+    threshold: f32,
+    on_relocate: fn(Index, Index),
+}
+
+impl<T> Arena<T> {
+    /// Ratio of vacant slots to the total number of slots in storage.
+    ///
+    /// Returns `0.0` for an arena without any slots.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    ///
+    /// assert_eq!(arena.fragmentation(), 0.5);
+    /// ```
+    pub fn fragmentation(&self) -> f32 {
+        if self.data.is_empty() {
+            0.0
+        } else {
+            (self.data.len() - self.count) as f32 / self.data.len() as f32
+        }
+    }
+
+    /// Compact the arena during [`Arena::maintain`] whenever its
+    /// [`fragmentation`](Arena::fragmentation) exceeds `threshold`.
+    ///
+    /// `on_relocate` is called with the old and new index of every item
+    /// that is moved, so stored handles can be patched. Replaces any
+    /// previously configured policy.
+    ///
+    /// ```
+    /// # use arena::{Arena, Index};
+    /// fn on_relocate(old: Index, new: Index) {
+    ///     println!("moved {:?} to {:?}", old, new);
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.compact_when(0.5, on_relocate);
+    ///
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// let index2 = arena.insert("Baz");
+    /// arena.remove(index0);
+    /// assert!(!arena.maintain());
+    ///
+    /// arena.remove(index1);
+    /// assert!(arena.maintain());
+    /// # assert_eq!(arena.fragmentation(), 0.0);
+    /// ```
+    pub fn compact_when(&mut self, threshold: f32, on_relocate: fn(Index, Index)) {
+        self.compaction = Some(CompactionPolicy { threshold, on_relocate });
+    }
+
+    /// Remove the automatic compaction policy, if any.
+    pub fn clear_compaction_policy(&mut self) {
+        self.compaction = None;
+    }
+
+    /// Run periodic upkeep according to the arena's configured policies.
+    ///
+    /// Returns `true` if the arena was compacted.
+    pub fn maintain(&mut self) -> bool {
+        match self.compaction {
+            Some(policy) if self.fragmentation() > policy.threshold => {
+                self.compact(policy.on_relocate);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Relocate the item at `from` into the vacant slot `to_slot`.
+    ///
+    /// Returns the item's new index. The old index becomes stale, and the
+    /// vacated slot is made available for reuse.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    ///
+    /// let moved = arena.move_slot(index1, 0).unwrap();
+    /// assert_eq!(arena.get(index1), None);
+    /// assert_eq!(arena.get(moved), Some(&"Bar"));
+    /// ```
+    pub fn move_slot(&mut self, from: Index, to_slot: usize) -> Result<Index, MoveError> {
+        if !self.is_live(from) {
+            return Err(MoveError::Stale(from));
+        }
+
+        let from_slot = from.slot();
+        if to_slot == from_slot {
+            return Ok(from);
+        }
+
+        match self.data.get(to_slot) {
+            None => return Err(MoveError::OutOfBounds(to_slot)),
+            Some(Entry::Occupied { .. } | Entry::Reserved { .. }) => return Err(MoveError::Occupied(to_slot)),
+            Some(entry) if self.is_retired(entry) => return Err(MoveError::Occupied(to_slot)),
+            Some(Entry::Vacant { .. }) => {}
+        }
+
+        let vacated = self.next_generation(from_slot, from.generation);
+        self.unlink_free(to_slot);
+
+        let generation = self.data[to_slot].generation();
+        let item = self.vacate(from_slot, vacated).unwrap_occupied().1;
+        self.data[to_slot] = Entry::Occupied { generation, item };
+        self.touch(from_slot);
+        self.touch(to_slot);
+
+        let to = self.stamp(Index::new(to_slot, generation));
+        self.recent.relocate(&[(from, to)]);
+        self.links.notify(LinkEvent::Relocated(from, to));
+        self.record_change(to, ChangeKind::Relocated { from });
+        Ok(to)
+    }
+
+    /// Exchange the slots of the items at `a` and `b`, and return their
+    /// new indices, in the same order.
+    ///
+    /// Both items get a new generation in their new slot, so the old
+    /// indices become stale. Linked [secondary maps](crate::SecondaryMap)
+    /// move their values along. Together with [`Arena::move_slot`], this
+    /// places items in chosen slots, for layouts that must match
+    /// between arenas.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// let (foo, bar) = arena.swap_slots(index0, index1).unwrap();
+    /// assert_eq!(arena.get(index0), None);
+    /// assert_eq!(foo.slot(), index1.slot());
+    /// assert_eq!(arena.get(foo), Some(&"Foo"));
+    /// # assert_eq!(arena.get(bar), Some(&"Bar"));
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if a slot's generation overflows under the
+    /// [`Panic`](GenerationOverflow::Panic) or [`Retire`](GenerationOverflow::Retire) policy.
+    pub fn swap_slots(&mut self, a: Index, b: Index) -> Result<(Index, Index), MoveError> {
+        for index in [a, b] {
+            if !self.is_live(index) {
+                return Err(MoveError::Stale(index));
+            }
+        }
+
+        let (slot_a, slot_b) = (a.slot(), b.slot());
+        if slot_a == slot_b {
+            return Ok((a, b));
+        }
+
+        let generation_a = self.regenerate(slot_b, b.generation);
+        let generation_b = self.regenerate(slot_a, a.generation);
+        self.data.swap(slot_a, slot_b);
+        for (slot, next) in [(slot_b, generation_a), (slot_a, generation_b)] {
+            if let Entry::Occupied { generation, .. } = &mut self.data[slot] {
+                *generation = next;
+            }
+            self.touch(slot);
+        }
+
+        let new_a = self.stamp(Index::new(slot_b, generation_a));
+        let new_b = self.stamp(Index::new(slot_a, generation_b));
+        self.recent.relocate(&[(a, new_a), (b, new_b)]);
+        self.links.notify(LinkEvent::Swapped([(a, new_a), (b, new_b)]));
+        self.record_change(new_a, ChangeKind::Relocated { from: a });
+        self.record_change(new_b, ChangeKind::Relocated { from: b });
+        Ok((new_a, new_b))
+    }
+
+    /// Make every index to an item stale, while keeping the items in place.
+    ///
+    /// Each item gets a new index in the same slot, and the returned iterator
+    /// yields them all. Linked [secondary maps](crate::SecondaryMap) move their
+    /// values to the new indices. Pending [reservations](Arena::reserve_index)
+    /// are not affected.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    ///
+    /// let fresh: Vec<_> = arena.invalidate_all().collect();
+    /// assert_eq!(arena.get(index), None);
+    /// assert_eq!(arena.get(fresh[0]), Some(&"Foo"));
+    /// ```
+    pub fn invalidate_all(&mut self) -> Keys<'_, T> {
+        for slot in 0..self.data.len() {
+            if let Entry::Occupied { generation, .. } = self.data[slot] {
+                let next = self.regenerate(slot, generation);
+                if let Entry::Occupied { generation, .. } = &mut self.data[slot] {
+                    *generation = next;
+                }
+                let old = self.stamp(Index::new(slot, generation));
+                let new = self.stamp(Index::new(slot, next));
+
+                self.links.notify(LinkEvent::Relocated(old, new));
+                self.record_change(new, ChangeKind::Relocated { from: old });
+            }
+        }
+
+        self.keys()
+    }
+
+    /// Drop the vacant slots at the end of storage, without moving any items.
+    ///
+    /// Returns the number of slots dropped. The arena remembers their
+    /// generations, so all indices stay valid, and stale ones stay stale.
+    /// Use [`Arena::shrink_to_fit`] to also release the memory.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// let index2 = arena.insert("Baz");
+    /// arena.remove(index1);
+    /// arena.remove(index2);
+    ///
+    /// assert_eq!(arena.truncate_vacant_tail(), 2);
+    /// assert_eq!(arena.fragmentation(), 0.0);
+    /// assert_eq!(arena.get(index0), Some(&"Foo"));
+    /// ```
+    pub fn truncate_vacant_tail(&mut self) -> usize {
+        let len = self
+            .data
+            .iter()
+            .rposition(|entry| !entry.is_vacant() || self.is_retired(entry))
+            .map_or(0, |slot| slot + 1);
+        let dropped = self.data.len() - len;
+        if dropped == 0 {
+            return 0;
+        }
+
+        // Unlink the slots about to be dropped, keeping the order of the rest.
+        let mut prev: Option<usize> = None;
+        let mut cursor = self.free_head;
+        while let Some(slot) = cursor {
+            let next = self.data[slot].next_free();
+            if slot < len {
+                prev = Some(slot);
+            } else {
+                match prev {
+                    Some(prev) => self.data[prev].set_next_free(next),
+                    None => self.free_head = next,
+                }
+            }
+            cursor = next;
+        }
+
+        for entry in self.data.drain(len..) {
+            self.generation = self.generation.max(entry.generation());
+        }
+
+        dropped
+    }
+
+    /// [Compact](Arena::compact) the arena, and return the translation of
+    /// the moved items' indices.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    ///
+    /// let remap = arena.compact_with_remap();
+    /// assert_eq!(remap.len(), 1);
+    /// assert_eq!(arena.get(remap.translate(index1).unwrap()), Some(&"Bar"));
+    /// ```
+    pub fn compact_with_remap(&mut self) -> IndexRemap {
+        let mut remap = IndexRemap::new();
+        self.compact(|old, new| remap.insert(old, new));
+        remap
+    }
+
+    /// Move all occupied entries to the front of storage, preserving
+    /// their order, and drop the trailing vacant slots.
+    ///
+    /// Relocated items take on the generation of their new slot, and the
+    /// arena remembers the generations of the dropped slots, so indices to
+    /// old slots never resolve to a different item.
+    ///
+    /// Pending [reservations](Arena::reserve_index) are relocated and
+    /// reported like items.
+    ///
+    /// `on_relocate` is called with the old and new index of every moved
+    /// item, so stored handles can be patched. It is only invoked once the
+    /// arena is consistent again, so a panicking callback can't corrupt it.
+    ///
+    /// The capacity of the backing storage is kept, see [`Arena::shrink_to_fit`].
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let mut index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    ///
+    /// arena.compact(|old, new| {
+    ///     if index1 == old {
+    ///         index1 = new;
+    ///     }
+    /// });
+    /// assert_eq!(arena.get(index1), Some(&"Bar"));
+    /// assert_eq!(arena.fragmentation(), 0.0);
+    /// ```
+    pub fn compact<F>(&mut self, mut on_relocate: F)
+    where
+        F: FnMut(Index, Index),
+    {
+        if self.count == self.data.len() {
+            return;
+        }
+
+        // Check for overflow up front, so a panic can't leave the arena half compacted.
+        if self.overflow == GenerationOverflow::Panic {
+            let first_vacant = self.data.iter().position(Entry::is_vacant).unwrap_or(0);
+            for (slot, entry) in self.data.iter().enumerate().skip(first_vacant) {
+                if !entry.is_vacant() {
+                    self.next_generation(slot, entry.generation());
+                }
+            }
+        }
+
+        let mut relocations = Vec::new();
+        let mut write = 0;
+
+        for read in 0..self.data.len() {
+            if self.data[read].is_vacant() {
+                continue;
+            }
+            while self.is_retired(&self.data[write]) {
+                write += 1;
+            }
+
+            if read != write {
+                let old = self.stamp(Index::new(read, self.data[read].generation()));
+                let new = self.stamp(Index::new(write, self.data[write].generation()));
+                self.data.swap(read, write);
+                self.data[read] = Entry::Vacant {
+                    generation: self.next_generation(read, old.generation),
+                    next: None,
+                };
+
+                match &mut self.data[write] {
+                    Entry::Occupied { generation, .. } | Entry::Reserved { generation } => *generation = new.generation,
+                    Entry::Vacant { .. } => unreachable!(),
+                }
+                relocations.push((old, new));
+            }
+
+            write += 1;
+        }
+
+        // Retired slots stay in storage, and vacant ones before them are reused.
+        let len = self.data[write..]
+            .iter()
+            .rposition(|entry| self.is_retired(entry))
+            .map_or(write, |offset| write + offset + 1);
+        for entry in self.data.drain(len..) {
+            self.generation = self.generation.max(entry.generation());
+        }
+        self.rebuild_free_list();
+        self.recent.relocate(&relocations);
+
+        for &(old, new) in &relocations {
+            self.touch(new.slot());
+            self.links.notify(LinkEvent::Relocated(old, new));
+            self.record_change(new, ChangeKind::Relocated { from: old });
+        }
+        self.metric_compacted(relocations.len());
+
+        for (old, new) in relocations {
+            on_relocate(old, new);
+        }
+    }
+}
+
+/// Error returned by [`Arena::move_slot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// The index to move does not point to a live item.
+    Stale(Index),
+    /// The destination slot is beyond the arena's storage.
+    OutOfBounds(usize),
+    /// The destination slot already holds an item or a reservation,
+    /// or is [retired](crate::GenerationOverflow::Retire).
+    Occupied(usize),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::Stale(index) => write!(f, "index {:?} does not point to a live item", index),
+            MoveError::OutOfBounds(slot) => write!(f, "destination slot {} is out of bounds", slot),
+            MoveError::Occupied(slot) => write!(f, "destination slot {} is occupied or retired", slot),
+        }
+    }
+}
+
+impl core::error::Error for MoveError {}
@@ -0,0 +1,244 @@
+//! Drop-in replacement for the `slab` crate's `Slab`, backed by an [`Arena`].
+//!
+//! Code written against `slab` can switch to [`Slab`] by changing an import.
+//! Keys are plain slot numbers, as in `slab`, so they aren't checked
+//! against removals. Each key can be upgraded to a generational [`Index`]
+//! with [`Slab::index`], to migrate one access at a time.
+use core::ops;
+
+use crate::{Arena, Entry, Index, IterIndexed, IterIndexedMut};
+
+/// Arena with the API of `slab::Slab`, keying items by `usize`.
+///
+/// ```
+/// # use arena::compat::Slab;
+/// let mut slab = Slab::new();
+/// let key = slab.insert("Foo");
+/// assert_eq!(slab[key], "Foo");
+///
+/// let index = slab.index(key).unwrap();
+/// assert_eq!(slab.remove(key), "Foo");
+///
+/// // The key is reused, but the generational index no longer resolves.
+/// assert_eq!(slab.insert("Bar"), key);
+/// assert_eq!(slab.as_arena().get(index), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Slab<T> {
+    arena: Arena<T>,
+}
+
+impl<T> Slab<T> {
+    pub fn new() -> Self {
+        Self::from_arena(Arena::new())
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_arena(Arena::with_capacity(capacity))
+    }
+
+    /// Wrap an arena, keying its items by slot.
+    pub fn from_arena(arena: Arena<T>) -> Self {
+        Slab { arena }
+    }
+
+    /// Unwrap the underlying arena.
+    pub fn into_arena(self) -> Arena<T> {
+        self.arena
+    }
+
+    /// The underlying arena, for resolving generational indices.
+    pub fn as_arena(&self) -> &Arena<T> {
+        &self.arena
+    }
+
+    /// The underlying arena, for operations not exposed by the slab API.
+    pub fn as_arena_mut(&mut self) -> &mut Arena<T> {
+        &mut self.arena
+    }
+
+    /// Generational index of the item currently stored at `key`.
+    pub fn index(&self, key: usize) -> Option<Index> {
+        match self.arena.data.get(key) {
+            Some(Entry::Occupied { generation, .. }) => Some(self.arena.stamp(Index::new(key, *generation))),
+            _ => None,
+        }
+    }
+
+    /// Insert the item, and return its key.
+    pub fn insert(&mut self, item: T) -> usize {
+        self.arena.insert(item).slot()
+    }
+
+    /// Key the next inserted item will get.
+    pub fn vacant_key(&self) -> usize {
+        self.arena.next_index().slot()
+    }
+
+    /// Remove and return the item at `key`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `key` isn't occupied.
+    pub fn remove(&mut self, key: usize) -> T {
+        self.try_remove(key).expect("invalid key")
+    }
+
+    /// Remove and return the item at `key`, if it's occupied.
+    pub fn try_remove(&mut self, key: usize) -> Option<T> {
+        let index = self.index(key)?;
+        self.arena.take(index)
+    }
+
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.arena.get(self.index(key)?)
+    }
+
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        let index = self.index(key)?;
+        self.arena.get_mut(index)
+    }
+
+    pub fn contains(&self, key: usize) -> bool {
+        self.index(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.arena.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.arena.reserve(additional)
+    }
+
+    pub fn clear(&mut self) {
+        self.arena.clear()
+    }
+
+    /// Keep only the items for which `f` returns `true`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        self.arena.retain_mut(|index, item| f(index.slot(), item))
+    }
+
+    /// Iterate the items together with their keys.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.arena.iter_indexed(),
+        }
+    }
+
+    /// Mutably iterate the items together with their keys.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.arena.iter_indexed_mut(),
+        }
+    }
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Arena<T>> for Slab<T> {
+    fn from(arena: Arena<T>) -> Self {
+        Self::from_arena(arena)
+    }
+}
+
+impl<T> ops::Index<usize> for Slab<T> {
+    type Output = T;
+
+    /// # Panic
+    ///
+    /// Panics if `key` isn't occupied.
+    fn index(&self, key: usize) -> &T {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<T> ops::IndexMut<usize> for Slab<T> {
+    fn index_mut(&mut self, key: usize) -> &mut T {
+        self.get_mut(key).expect("invalid key")
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Slab<T> {
+    type Item = (usize, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Slab<T> {
+    type Item = (usize, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    inner: IterIndexed<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, item)| (index.slot(), item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, item)| (index.slot(), item))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+#[derive(Debug)]
+pub struct IterMut<'a, T> {
+    inner: IterIndexedMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, item)| (index.slot(), item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, item)| (index.slot(), item))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
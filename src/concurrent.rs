@@ -0,0 +1,147 @@
+//! Arena sharded across locks, for insertion from many threads.
+use alloc::boxed::Box;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::sync::Mutex;
+use crate::{Arena, Index};
+
+/// Arena which can be modified from several threads at once.
+///
+/// Items are spread over a number of shards, each an [`Arena`] behind its
+/// own lock. Insertions take turns between the shards, so threads inserting
+/// at the same time rarely wait for each other. An index records its shard
+/// in its [tag](Index::tag), so tags can't be used for anything else.
+///
+/// ```
+/// # use arena::ConcurrentArena;
+/// let arena = ConcurrentArena::new();
+///
+/// let indices: Vec<_> = std::thread::scope(|scope| {
+///     let workers: Vec<_> = (0..4)
+///         .map(|n| {
+///             let arena = &arena;
+///             scope.spawn(move || (0..100).map(|m| arena.insert(n * 100 + m)).collect::<Vec<_>>())
+///         })
+///         .collect();
+///     workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+/// });
+///
+/// assert_eq!(arena.len(), 400);
+/// assert_eq!(arena.with(indices[150], |item| *item), Some(150));
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentArena<T> {
+    shards: Box<[Mutex<Arena<T>>]>,
+    /// Shard the next insertion starts with.
+    next: AtomicUsize,
+}
+
+impl<T> ConcurrentArena<T> {
+    /// Create an empty arena with 16 shards.
+    pub fn new() -> Self {
+        Self::with_shards(16)
+    }
+
+    /// Create an empty arena with the given number of shards.
+    ///
+    /// More shards mean less waiting between threads, and more
+    /// scattered items.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `shards` is zero, or more than 256.
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(
+            (1..=u8::MAX as usize + 1).contains(&shards),
+            "shard count must be between 1 and 256"
+        );
+
+        Self {
+            shards: (0..shards).map(|_| Mutex::new(Arena::new())).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Insert the item into the next shard in turn.
+    pub fn insert(&self, item: T) -> Index {
+        let shard = self.next.fetch_add(1, Ordering::Relaxed) % self.shards.len();
+        self.shards[shard].lock().insert(item).with_tag(shard as u8)
+    }
+
+    /// Remove the item at the given `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&self, index: Index) {
+        self.take(index);
+    }
+
+    /// Remove and return the item at the given `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn take(&self, index: Index) -> Option<T> {
+        self.shard(index)?.lock().take(index)
+    }
+
+    /// Call `f` with a reference to the item at the given `index`, while
+    /// its shard is locked.
+    pub fn with<R, F: FnOnce(&T) -> R>(&self, index: Index, f: F) -> Option<R> {
+        self.shard(index)?.lock().get(index).map(f)
+    }
+
+    /// Call `f` with a mutable reference to the item at the given
+    /// `index`, while its shard is locked.
+    pub fn with_mut<R, F: FnOnce(&mut T) -> R>(&self, index: Index, f: F) -> Option<R> {
+        self.shard(index)?.lock().get_mut(index).map(f)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.with(index, |_| ()).is_some()
+    }
+
+    /// Return a mutable reference to the item at the given `index`.
+    ///
+    /// Exclusive access to the arena means no locking is needed.
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        let shard = self.shards.get_mut(usize::from(index.tag()))?;
+        shard.get_mut().get_mut(index)
+    }
+
+    /// Number of items in all shards.
+    ///
+    /// Locks each shard in turn, so the count may already
+    /// be outdated if other threads are modifying the arena.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.lock().len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Call `f` with every item and its index, one shard at a time.
+    pub fn for_each<F: FnMut(Index, &T)>(&self, mut f: F) {
+        for (shard, arena) in self.shards.iter().enumerate() {
+            for (index, item) in arena.lock().iter_indexed() {
+                f(index.with_tag(shard as u8), item);
+            }
+        }
+    }
+
+    fn shard(&self, index: Index) -> Option<&Mutex<Arena<T>>> {
+        self.shards.get(usize::from(index.tag()))
+    }
+}
+
+impl<T> Default for ConcurrentArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,271 @@
+//! Conversions between arenas and other containers.
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+
+use crate::{Arena, Entry, Index};
+
+impl<T> From<Vec<T>> for Arena<T> {
+    /// Create an arena holding the items of `vec`, each in the
+    /// slot matching its position in the vector.
+    fn from(vec: Vec<T>) -> Self {
+        Arena::from_iter_dense(vec)
+    }
+}
+
+impl<T> From<Vec<Option<T>>> for Arena<T> {
+    /// Create an arena holding the `Some` items of `vec`, each in the
+    /// slot matching its position in the vector. The `None` positions
+    /// become vacant slots.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let arena: Arena<&str> = Arena::from(vec![Some("Foo"), None, Some("Bar")]);
+    /// assert_eq!(arena.len(), 2);
+    /// assert_eq!(arena.slot_count(), 3);
+    /// assert_eq!(arena.iter_indexed().map(|(index, _)| index.slot()).collect::<Vec<_>>(), [0, 2]);
+    /// ```
+    fn from(vec: Vec<Option<T>>) -> Self {
+        let mut arena = Arena::new();
+        let generation = arena.generation;
+        arena.data = vec
+            .into_iter()
+            .map(|item| match item {
+                Some(item) => Entry::Occupied { generation, item },
+                None => Entry::Vacant { generation, next: None },
+            })
+            .collect();
+        assert!(
+            arena.data.len() <= Index::SLOT_MASK,
+            "arena exceeded the maximum number of slots"
+        );
+        arena.count = arena.data.iter().filter(|entry| entry.is_occupied()).count();
+        arena.rebuild_free_list();
+        arena
+    }
+}
+
+impl<T> From<Arena<T>> for Vec<Option<T>> {
+    /// Collect the slots of an arena, with `None` for every slot
+    /// that doesn't hold an item.
+    ///
+    /// Generations are discarded, but converting back with
+    /// [`Arena::from`] puts every item in the same slot.
+    fn from(arena: Arena<T>) -> Self {
+        arena
+            .data
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Occupied { item, .. } => Some(item),
+                Entry::Vacant { .. } | Entry::Reserved { .. } => None,
+            })
+            .collect()
+    }
+}
+
+impl<T> Arena<T> {
+    /// Create an arena holding the items of `iter` in consecutive slots,
+    /// starting from the first, without any vacant slots.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let arena = Arena::from_iter_dense(vec!["Foo", "Bar"]);
+    /// assert_eq!(arena.first().map(|(index, item)| (index.slot(), *item)), Some((0, "Foo")));
+    /// ```
+    pub fn from_iter_dense<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut arena = Arena::new();
+        let generation = arena.generation;
+        arena.data = iter
+            .into_iter()
+            .map(|item| Entry::Occupied { generation, item })
+            .collect();
+        assert!(
+            arena.data.len() <= Index::SLOT_MASK,
+            "arena exceeded the maximum number of slots"
+        );
+        arena.count = arena.data.len();
+        arena
+    }
+
+    /// Consume the arena, collecting its items in slot order.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// arena.insert("Bar");
+    /// arena.remove(index);
+    /// assert_eq!(arena.into_vec(), ["Bar"]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    /// Consume the arena, collecting its items in slot order, together
+    /// with the index each was stored at.
+    pub fn into_vec_with_indices(self) -> (Vec<T>, Vec<Index>) {
+        let mut items = Vec::with_capacity(self.count);
+        let mut indices = Vec::with_capacity(self.count);
+        let stamp = self.stamper();
+
+        for (slot, entry) in self.data.into_iter().enumerate() {
+            if let Entry::Occupied { generation, item } = entry {
+                items.push(item);
+                indices.push(stamp.index(slot, generation));
+            }
+        }
+
+        (items, indices)
+    }
+
+    /// Build a new arena by transforming every item.
+    ///
+    /// The new arena has exactly the same slot layout and generations,
+    /// so indices into this arena are also valid for the returned one.
+    /// Registered callbacks and tracking state are not carried over.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// let owned = arena.map(|_, item| item.to_owned());
+    /// assert_eq!(owned.get(index), Some(&"Foo".to_owned()));
+    /// ```
+    pub fn map<U, F>(mut self, mut f: F) -> Arena<U>
+    where
+        F: FnMut(Index, T) -> U,
+    {
+        let stamp = self.stamper();
+        let data = core::mem::take(&mut self.data)
+            .into_iter()
+            .enumerate()
+            .map(|(slot, entry)| match entry {
+                Entry::Vacant { generation, next } => Entry::Vacant { generation, next },
+                Entry::Reserved { generation } => Entry::Reserved { generation },
+                Entry::Occupied { generation, item } => Entry::Occupied {
+                    generation,
+                    item: f(stamp.index(slot, generation), item),
+                },
+            })
+            .collect();
+
+        self.with_layout(data)
+    }
+
+    /// Build a new arena from a reference to every item, leaving this
+    /// one untouched.
+    ///
+    /// Like [`map`](Arena::map), indices into this arena are also
+    /// valid for the returned one.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert([1.0, 2.0]);
+    /// let lengths = arena.map_ref(|_, [x, y]| (x * x + y * y) as f32);
+    /// assert_eq!(lengths[index], 5.0);
+    /// ```
+    pub fn map_ref<U, F>(&self, mut f: F) -> Arena<U>
+    where
+        F: FnMut(Index, &T) -> U,
+    {
+        let data = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(slot, entry)| match entry {
+                Entry::Vacant { generation, next } => Entry::Vacant {
+                    generation: *generation,
+                    next: *next,
+                },
+                Entry::Reserved { generation } => Entry::Reserved {
+                    generation: *generation,
+                },
+                Entry::Occupied { generation, item } => Entry::Occupied {
+                    generation: *generation,
+                    item: f(self.stamp(Index::new(slot, *generation)), item),
+                },
+            })
+            .collect();
+
+        self.with_layout(data)
+    }
+
+    /// Arena holding `data`, laid out like this one, with the same settings.
+    pub(crate) fn with_layout<U>(&self, data: Vec<Entry<U>>) -> Arena<U> {
+        let mut arena = Arena::new();
+        arena.inherit_id(self);
+        arena.limit = self.limit;
+        arena.overflow = self.overflow;
+        arena.reuse = self.reuse;
+        arena.data = data;
+        arena.generation = self.generation;
+        arena.free_head = self.free_head;
+        arena.free_tail = self.free_tail;
+        arena.count = self.count;
+        arena
+    }
+}
+
+impl<T> FromIterator<T> for Arena<T> {
+    /// Create an arena holding the items of `iter`, in consecutive slots.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut arena = Arena::new();
+        arena.extend(iter);
+        arena
+    }
+}
+
+impl<T> Extend<T> for Arena<T> {
+    /// Insert every item of `iter`, reserving room for
+    /// the iterator's lower size bound up front.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+#[cfg(feature = "slab")]
+impl<T> From<slab::Slab<T>> for Arena<T> {
+    /// Create an arena from a [`Slab`](slab::Slab), placing each
+    /// item in the slot matching its slab key.
+    fn from(slab: slab::Slab<T>) -> Self {
+        let mut arena = Arena::new();
+        let generation = arena.generation;
+        arena.data.reserve(slab.capacity());
+
+        for (key, item) in slab {
+            while arena.data.len() < key {
+                arena.data.push(Entry::Vacant { generation, next: None });
+            }
+            arena.data.push(Entry::Occupied { generation, item });
+            arena.count += 1;
+        }
+
+        arena.rebuild_free_list();
+        arena
+    }
+}
+
+#[cfg(feature = "slab")]
+impl<T> From<Arena<T>> for slab::Slab<T> {
+    /// Create a [`Slab`](slab::Slab) from an arena, keying each
+    /// item by its slot.
+    ///
+    /// Generations are discarded, so indices into the arena
+    /// can't be checked against the slab.
+    fn from(arena: Arena<T>) -> Self {
+        arena
+            .data
+            .into_iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Entry::Occupied { item, .. } => Some((slot, item)),
+                Entry::Vacant { .. } | Entry::Reserved { .. } => None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,99 @@
+//! Cursor for modifying an arena while walking it.
+use crate::{Arena, Entry, Index};
+
+/// Cursor walking the items of an arena in slot order, which can remove
+/// or replace the item it's on, created by [`Arena::cursor_mut`].
+///
+/// The cursor starts before the first item. Removing or replacing the
+/// current item doesn't disturb the walk, and the next call to
+/// [`move_next`](CursorMut::move_next) continues with the following slot.
+#[derive(Debug)]
+pub struct CursorMut<'a, T> {
+    arena: &'a mut Arena<T>,
+    /// Next slot to examine.
+    slot: usize,
+    current: Option<Index>,
+}
+
+impl<T> Arena<T> {
+    /// Create a cursor for walking the items while removing or replacing them.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// arena.insert(3);
+    /// arena.insert(-1);
+    /// arena.insert(-4);
+    ///
+    /// let mut cursor = arena.cursor_mut();
+    /// while let Some((_, health)) = cursor.move_next() {
+    ///     if *health < -2 {
+    ///         cursor.remove();
+    ///     } else if *health < 0 {
+    ///         cursor.replace(0);
+    ///     }
+    /// }
+    /// assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [3, 0]);
+    /// ```
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            arena: self,
+            slot: 0,
+            current: None,
+        }
+    }
+}
+
+impl<T> CursorMut<'_, T> {
+    /// Move to the next item, and return it together with its index.
+    ///
+    /// Returns `None` once every item was visited.
+    pub fn move_next(&mut self) -> Option<(Index, &mut T)> {
+        self.current = None;
+        while let Some(entry) = self.arena.data.get(self.slot) {
+            let slot = self.slot;
+            self.slot += 1;
+
+            if let Entry::Occupied { generation, .. } = entry {
+                self.current = Some(self.arena.stamp(Index::new(slot, *generation)));
+                return self.current();
+            }
+        }
+
+        None
+    }
+
+    /// The item the cursor is on, together with its index.
+    ///
+    /// Returns `None` before the first item, after the last one,
+    /// and once the current item was removed.
+    pub fn current(&mut self) -> Option<(Index, &mut T)> {
+        let index = self.current?;
+        self.arena.get_mut(index).map(|item| (index, item))
+    }
+
+    /// Index of the item the cursor is on.
+    pub fn index(&self) -> Option<Index> {
+        self.current
+    }
+
+    /// Remove and return the item the cursor is on, as if by [`Arena::take`].
+    pub fn remove(&mut self) -> Option<T> {
+        self.arena.take(self.current.take()?)
+    }
+
+    /// Replace the item the cursor is on, as if by [`Arena::replace`],
+    /// and return the original.
+    ///
+    /// The new item gets a new index, which the cursor moves to.
+    pub fn replace(&mut self, item: T) -> Option<T> {
+        let (index, original) = self.arena.replace(self.current?, item);
+        self.current = Some(index);
+        original
+    }
+
+    /// Read-only access to the whole arena.
+    pub fn as_arena(&self) -> &Arena<T> {
+        self.arena
+    }
+}
@@ -0,0 +1,120 @@
+//! [`defmt`] formatting, behind the `defmt` feature.
+use crate::{
+    Access, AliasError, ArenaError, ArenaStats, GenerationOverflow, Index, MoveError, StaleAccess, ValidationError,
+};
+
+impl defmt::Format for Index {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Index {{ slot: {=usize}, generation: {=usize}, tag: {=u8} }}",
+            self.slot(),
+            self.generation.get(),
+            self.tag()
+        )
+    }
+}
+
+impl defmt::Format for Access {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Access::Get => defmt::write!(f, "Get"),
+            Access::GetMut => defmt::write!(f, "GetMut"),
+            Access::Remove => defmt::write!(f, "Remove"),
+        }
+    }
+}
+
+impl defmt::Format for StaleAccess {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "StaleAccess {{ access: {}, index: {}, current: {} }}",
+            self.access,
+            self.index,
+            self.current.map(|generation| generation.get())
+        )
+    }
+}
+
+impl defmt::Format for MoveError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            MoveError::Stale(index) => defmt::write!(f, "Stale({})", index),
+            MoveError::OutOfBounds(slot) => defmt::write!(f, "OutOfBounds({=usize})", slot),
+            MoveError::Occupied(slot) => defmt::write!(f, "Occupied({=usize})", slot),
+        }
+    }
+}
+
+impl defmt::Format for AliasError {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "AliasError {{ slot: {=usize} }}", self.slot)
+    }
+}
+
+impl defmt::Format for ArenaError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            ArenaError::OutOfBounds(index) => defmt::write!(f, "OutOfBounds({})", index),
+            ArenaError::StaleGeneration { index, current } => defmt::write!(
+                f,
+                "StaleGeneration {{ index: {}, current: {=usize} }}",
+                index,
+                current.get()
+            ),
+            ArenaError::Vacant(index) => defmt::write!(f, "Vacant({})", index),
+            ArenaError::Pending(index) => defmt::write!(f, "Pending({})", index),
+            ArenaError::TagMismatch(index) => defmt::write!(f, "TagMismatch({})", index),
+        }
+    }
+}
+
+impl defmt::Format for ArenaStats {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ArenaStats {{ capacity: {=usize}, occupied: {=usize}, reserved: {=usize}, vacant: {=usize}, \
+             retired: {=usize}, free_list_len: {=usize}, high_water_mark: {=usize}, fragmentation: {=f32}, memory_usage: {=usize} }}",
+            self.capacity,
+            self.occupied,
+            self.reserved,
+            self.vacant,
+            self.retired,
+            self.free_list_len,
+            self.high_water_mark,
+            self.fragmentation,
+            self.memory_usage
+        )
+    }
+}
+
+impl defmt::Format for ValidationError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            ValidationError::TooManySlots(len) => defmt::write!(f, "TooManySlots({=usize})", len),
+            ValidationError::FreeListOutOfBounds(slot) => defmt::write!(f, "FreeListOutOfBounds({=usize})", slot),
+            ValidationError::FreeListNotVacant(slot) => defmt::write!(f, "FreeListNotVacant({=usize})", slot),
+            ValidationError::FreeListRetired(slot) => defmt::write!(f, "FreeListRetired({=usize})", slot),
+            ValidationError::FreeListCycle(slot) => defmt::write!(f, "FreeListCycle({=usize})", slot),
+            ValidationError::Unlinked(slot) => defmt::write!(f, "Unlinked({=usize})", slot),
+            ValidationError::CountMismatch { count, occupied } => defmt::write!(
+                f,
+                "CountMismatch {{ count: {=usize}, occupied: {=usize} }}",
+                count,
+                occupied
+            ),
+        }
+    }
+}
+
+impl defmt::Format for GenerationOverflow {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            GenerationOverflow::Saturate => defmt::write!(f, "Saturate"),
+            GenerationOverflow::Wrap => defmt::write!(f, "Wrap"),
+            GenerationOverflow::Panic => defmt::write!(f, "Panic"),
+            GenerationOverflow::Retire => defmt::write!(f, "Retire"),
+        }
+    }
+}
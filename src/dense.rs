@@ -0,0 +1,294 @@
+//! Arena storing its items contiguously, for fast iteration.
+use alloc::vec::Vec;
+use core::iter::Zip;
+use core::num::NonZeroUsize;
+use core::slice::{Iter as SliceIter, IterMut as SliceIterMut};
+
+use crate::Index;
+
+/// Arena keeping its items packed together in a `Vec`, in the style of a
+/// slot map.
+///
+/// Indices go through a table of slots, which point into the packed items.
+/// Removal moves the last item into the gap, so iterating never visits a
+/// vacant slot, at the cost of an extra indirection on every lookup. Items
+/// are iterated in packed order, which changes as items are removed.
+///
+/// Indices are the same [`Index`] type as in [`Arena`](crate::Arena), but
+/// are only meaningful in the arena that handed them out.
+///
+/// ```
+/// # use arena::DenseArena;
+/// let mut arena = DenseArena::new();
+/// let index0 = arena.insert("Foo");
+/// let index1 = arena.insert("Bar");
+/// let index2 = arena.insert("Baz");
+///
+/// arena.remove(index0);
+/// assert_eq!(arena.as_slice(), ["Baz", "Bar"]);
+/// assert_eq!(arena.get(index2), Some(&"Baz"));
+/// # assert_eq!(arena.get(index1), Some(&"Bar"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DenseArena<T> {
+    slots: Vec<Slot>,
+    items: Vec<T>,
+    /// Slot of each packed item.
+    owners: Vec<usize>,
+    free_head: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Vacant {
+        generation: NonZeroUsize,
+        next: Option<usize>,
+    },
+    Occupied {
+        generation: NonZeroUsize,
+        position: usize,
+    },
+}
+
+impl<T> DenseArena<T> {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            items: Vec::new(),
+            owners: Vec::new(),
+            free_head: None,
+        }
+    }
+
+    /// Create an empty arena with room for at least `capacity` items
+    /// before reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            items: Vec::with_capacity(capacity),
+            owners: Vec::with_capacity(capacity),
+            free_head: None,
+        }
+    }
+
+    /// Insert the item, reusing a free slot if there is one.
+    pub fn insert(&mut self, item: T) -> Index {
+        let position = self.items.len();
+
+        let (slot, generation) = match self.free_head {
+            Some(slot) => match self.slots[slot] {
+                Slot::Vacant { generation, next } => {
+                    self.free_head = next;
+                    (slot, generation)
+                }
+                Slot::Occupied { .. } => unreachable!("free list points to an occupied slot"),
+            },
+            None => {
+                let slot = self.slots.len();
+                assert!(slot < Index::SLOT_MASK, "arena exceeded the maximum number of slots");
+                self.slots.push(Slot::Vacant {
+                    generation: NonZeroUsize::MIN,
+                    next: None,
+                });
+                (slot, NonZeroUsize::MIN)
+            }
+        };
+
+        self.slots[slot] = Slot::Occupied { generation, position };
+        self.items.push(item);
+        self.owners.push(slot);
+        Index::new(slot, generation)
+    }
+
+    /// Remove the item at the given `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: Index) {
+        self.take(index);
+    }
+
+    /// Remove and return the item at the given `index`.
+    ///
+    /// The last item in packed order is moved into its place.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn take(&mut self, index: Index) -> Option<T> {
+        let slot = index.slot();
+        assert!(slot < self.slots.len(), "index out of bounds");
+
+        let position = self.position(index)?;
+        self.slots[slot] = Slot::Vacant {
+            generation: index.generation.saturating_add(1),
+            next: self.free_head,
+        };
+        self.free_head = Some(slot);
+
+        let item = self.items.swap_remove(position);
+        self.owners.swap_remove(position);
+        if let Some(&moved) = self.owners.get(position) {
+            if let Slot::Occupied { position: moved_to, .. } = &mut self.slots[moved] {
+                *moved_to = position;
+            }
+        }
+
+        Some(item)
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        self.position(index).map(|position| &self.items[position])
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        self.position(index).map(|position| &mut self.items[position])
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.position(index).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Remove every item. Indices handed out before are invalidated.
+    pub fn clear(&mut self) {
+        for slot in self.owners.drain(..) {
+            if let Slot::Occupied { generation, .. } = self.slots[slot] {
+                self.slots[slot] = Slot::Vacant {
+                    generation: generation.saturating_add(1),
+                    next: self.free_head,
+                };
+                self.free_head = Some(slot);
+            }
+        }
+        self.items.clear();
+    }
+
+    /// The items, packed together in iteration order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The items, packed together in iteration order.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.items
+    }
+
+    /// Iterate the items in packed order.
+    pub fn iter(&self) -> SliceIter<'_, T> {
+        self.items.iter()
+    }
+
+    /// Mutably iterate the items in packed order.
+    pub fn iter_mut(&mut self) -> SliceIterMut<'_, T> {
+        self.items.iter_mut()
+    }
+
+    /// Iterate the items in packed order, together with their indices.
+    pub fn iter_indexed(&self) -> IterIndexed<'_, T> {
+        IterIndexed {
+            slots: &self.slots,
+            inner: self.owners.iter().zip(self.items.iter()),
+        }
+    }
+
+    /// Mutably iterate the items in packed order, together with their indices.
+    pub fn iter_indexed_mut(&mut self) -> IterIndexedMut<'_, T> {
+        IterIndexedMut {
+            slots: &self.slots,
+            inner: self.owners.iter().zip(self.items.iter_mut()),
+        }
+    }
+
+    /// Position of a live `index`'s item in the packed items.
+    #[inline]
+    fn position(&self, index: Index) -> Option<usize> {
+        match self.slots.get(index.slot()) {
+            Some(Slot::Occupied { generation, position }) if *generation == index.generation => Some(*position),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Default for DenseArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> core::ops::Index<Index> for DenseArena<T> {
+    type Output = T;
+
+    /// # Panic
+    ///
+    /// Panics if `index` is stale or out of bounds.
+    fn index(&self, index: Index) -> &T {
+        self.get(index).expect("index is stale or out of bounds")
+    }
+}
+
+impl<T> core::ops::IndexMut<Index> for DenseArena<T> {
+    fn index_mut(&mut self, index: Index) -> &mut T {
+        self.get_mut(index).expect("index is stale or out of bounds")
+    }
+}
+
+#[inline]
+fn index_of(slots: &[Slot], slot: usize) -> Index {
+    match slots[slot] {
+        Slot::Occupied { generation, .. } => Index::new(slot, generation),
+        Slot::Vacant { .. } => unreachable!("packed item owned by a vacant slot"),
+    }
+}
+
+#[derive(Debug)]
+pub struct IterIndexed<'a, T> {
+    slots: &'a [Slot],
+    inner: Zip<SliceIter<'a, usize>, SliceIter<'a, T>>,
+}
+
+impl<'a, T> Iterator for IterIndexed<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(&slot, item)| (index_of(self.slots, slot), item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IterIndexed<'_, T> {}
+
+#[derive(Debug)]
+pub struct IterIndexedMut<'a, T> {
+    slots: &'a [Slot],
+    inner: Zip<SliceIter<'a, usize>, SliceIterMut<'a, T>>,
+}
+
+impl<'a, T> Iterator for IterIndexedMut<'a, T> {
+    type Item = (Index, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(&slot, item)| (index_of(self.slots, slot), item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IterIndexedMut<'_, T> {}
@@ -0,0 +1,220 @@
+//! Comparing arenas, and replaying the differences.
+use alloc::vec::Vec;
+
+use crate::{Arena, ChangeKind, Entry, Index};
+
+/// Difference between two arenas at a single index, see [`Arena::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArenaEdit<T> {
+    /// The item was added at `index`.
+    Added { index: Index, item: T },
+    /// The item at `index` was removed.
+    Removed { index: Index },
+    /// The item at `index` was changed to `item`.
+    Changed { index: Index, item: T },
+}
+
+impl<T: Clone + PartialEq> Arena<T> {
+    /// List the edits that turn this arena into `other`, in slot order.
+    ///
+    /// Items are matched by index, so an item replaced by another one in
+    /// the same slot is reported as removed and added, rather than changed.
+    /// Vacant slots and the order they are reused in are not compared.
+    ///
+    /// ```
+    /// # use arena::{Arena, ArenaEdit};
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(1);
+    /// let index1 = arena.insert(2);
+    ///
+    /// let mut other = arena.clone();
+    /// *other.get_mut(index0).unwrap() = 10;
+    /// other.remove(index1);
+    ///
+    /// let edits = arena.diff(&other);
+    /// assert_eq!(
+    ///     edits,
+    ///     [
+    ///         ArenaEdit::Changed { index: index0, item: 10 },
+    ///         ArenaEdit::Removed { index: index1 },
+    ///     ]
+    /// );
+    ///
+    /// arena.apply(edits);
+    /// assert_eq!(arena.diff(&other), []);
+    /// ```
+    pub fn diff(&self, other: &Arena<T>) -> Vec<ArenaEdit<T>> {
+        let mut edits = Vec::new();
+
+        for slot in 0..self.data.len().max(other.data.len()) {
+            let ours = match self.data.get(slot) {
+                Some(Entry::Occupied { generation, item }) => Some((self.stamp(Index::new(slot, *generation)), item)),
+                _ => None,
+            };
+            let theirs = match other.data.get(slot) {
+                Some(Entry::Occupied { generation, item }) => Some((self.stamp(Index::new(slot, *generation)), item)),
+                _ => None,
+            };
+
+            match (ours, theirs) {
+                (Some((ours, a)), Some((theirs, b))) if ours == theirs => {
+                    if a != b {
+                        edits.push(ArenaEdit::Changed {
+                            index: theirs,
+                            item: b.clone(),
+                        });
+                    }
+                }
+                (ours, theirs) => {
+                    if let Some((index, _)) = ours {
+                        edits.push(ArenaEdit::Removed { index });
+                    }
+                    if let Some((index, item)) = theirs {
+                        edits.push(ArenaEdit::Added {
+                            index,
+                            item: item.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        edits
+    }
+}
+
+impl<T: PartialEq> PartialEq for Arena<T> {
+    /// Arenas are equal if the same indices resolve, to equal items.
+    ///
+    /// Vacant slots, their generations and the order they are reused in
+    /// are not compared, nor are settings and registered callbacks.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let mut arena0 = Arena::new();
+    /// let mut arena1 = Arena::new();
+    /// arena0.insert("Foo");
+    /// arena1.insert("Foo");
+    /// assert_eq!(arena0, arena1);
+    ///
+    /// let index = arena1.insert("Bar");
+    /// arena1.remove(index);
+    /// assert_eq!(arena0, arena1);
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        self.count == other.count && self.iter_indexed().eq(other.iter_indexed())
+    }
+}
+
+impl<T: Eq> Eq for Arena<T> {}
+
+impl<T: PartialEq> Arena<T> {
+    /// Compare the items of two arenas in iteration order, ignoring
+    /// their indices.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let mut arena0 = Arena::new();
+    /// let mut arena1 = Arena::new();
+    /// arena0.insert("Foo");
+    /// let index = arena1.insert("Bar");
+    /// arena1.insert("Foo");
+    /// arena1.remove(index);
+    ///
+    /// assert_ne!(arena0, arena1);
+    /// assert!(arena0.items_eq(&arena1));
+    /// ```
+    pub fn items_eq(&self, other: &Arena<T>) -> bool {
+        self.count == other.count && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Arena<T> {
+    /// Apply edits produced by [`Arena::diff`], in order.
+    ///
+    /// Added items are stored at exactly their index, growing storage if
+    /// needed, so indices into the arena that the edits were taken against
+    /// resolve the same in this one. They take the generation of their
+    /// index, even if the slot already handed out newer ones here.
+    ///
+    /// # Panic
+    ///
+    /// Panics if an edit removes or changes an index that doesn't resolve,
+    /// or adds an item to a slot that isn't vacant or is
+    /// [retired](crate::GenerationOverflow::Retire). Also panics if an
+    /// added item would exceed the arena's [memory budget](Arena::set_memory_budget)
+    /// or [limit](Arena::with_limit), or a changed item would exceed the
+    /// budget by its growth over the item it replaces. Edits applied before
+    /// that remain.
+    pub fn apply<I: IntoIterator<Item = ArenaEdit<T>>>(&mut self, edits: I) {
+        for edit in edits {
+            match edit {
+                ArenaEdit::Added { index, item } => self.occupy_at(index, item),
+                ArenaEdit::Removed { index } => {
+                    assert!(self.is_live(index), "removed index {:?} doesn't resolve", index);
+                    self.remove_entry(index);
+                }
+                ArenaEdit::Changed { index, item } => {
+                    assert!(self.is_live(index), "changed index {:?} doesn't resolve", index);
+                    assert!(
+                        self.reserve_budget_replacing(&item, index.slot()),
+                        "arena memory budget exceeded"
+                    );
+                    assert!(self.is_live(index), "budget handler removed changed index {:?}", index);
+                    self.account_added(&item);
+                    let original = core::mem::replace(self.get_mut(index).unwrap(), item);
+                    self.account_removed(&original);
+                    self.notify_removed(index, &original);
+                }
+            }
+        }
+    }
+
+    /// Store the item in the vacant slot of `index`, with the index's generation.
+    fn occupy_at(&mut self, index: Index, item: T) {
+        let slot = index.slot();
+        let capacity = self.data.capacity();
+        self.assert_below_limit();
+        if self.budget.is_some() && self.data.len() <= slot {
+            // Grow no further than the slot, the budget counts the capacity.
+            self.data.reserve_exact(slot + 1 - self.data.len());
+        }
+        while self.data.len() <= slot {
+            let pos = self.data.len();
+            assert!(pos < Index::SLOT_MASK, "arena exceeded the maximum number of slots");
+            self.data.push(Entry::Vacant {
+                generation: self.generation,
+                next: self.free_head,
+            });
+            self.free_head = Some(pos);
+        }
+
+        self.metric_grew(capacity);
+
+        assert!(
+            self.data[slot].is_vacant() && !self.is_retired(&self.data[slot]),
+            "added index {:?} points to a slot that isn't vacant",
+            index
+        );
+        assert!(self.reserve_budget(&item, false), "arena memory budget exceeded");
+        assert!(
+            self.data[slot].is_vacant(),
+            "budget handler reused the slot of added index {:?}",
+            index
+        );
+        self.unlink_free(slot);
+
+        self.account_added(&item);
+        self.data[slot] = Entry::Occupied {
+            generation: index.generation,
+            item,
+        };
+        self.count += 1;
+        self.touch(slot);
+
+        let index = self.stamp(Index::new(slot, index.generation));
+        self.recent.record(index);
+        self.record_change(index, ChangeKind::Inserted);
+        self.metric_inserted(index);
+    }
+}
@@ -0,0 +1,126 @@
+//! Readable output of an arena's items and slot layout, for debugging.
+use core::fmt;
+
+use crate::{Arena, Entry};
+
+/// Number of slots drawn on each row of a [`LayoutDump`].
+const ROW_LEN: usize = 64;
+
+impl<T: fmt::Debug> fmt::Debug for Arena<T> {
+    /// Lists the items as `index => item`, in slot order.
+    ///
+    /// Vacant slots and the free list are left out, see
+    /// [`Arena::dump_layout`] for those.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// arena.remove(index);
+    /// let index = arena.insert("Bar");
+    ///
+    /// assert_eq!(format!("{:?}", arena), format!("{{{:?}: \"Bar\"}}", index));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter_indexed().map(|(index, item)| (self.stamp(index), item)))
+            .finish()
+    }
+}
+
+impl<T> Arena<T> {
+    /// Draw the occupancy of every slot and the chain of the free list,
+    /// for tracking down fragmentation.
+    ///
+    /// Each slot is drawn as `#` if occupied, `R` if reserved, `.` if
+    /// vacant, or `x` if [retired](crate::GenerationOverflow::Retire),
+    /// in rows of 64 prefixed with their first slot.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// arena.insert("Bar");
+    /// let index2 = arena.insert("Baz");
+    /// arena.reserve_index();
+    /// arena.remove(index0);
+    /// arena.remove(index2);
+    ///
+    /// assert_eq!(
+    ///     arena.dump_layout().to_string(),
+    ///     "4 slots, 1 occupied, 1 reserved, 2 vacant\n\
+    ///      \x20    0 .#.R\n\
+    ///      free list: 2 -> 0\n"
+    /// );
+    /// ```
+    pub fn dump_layout(&self) -> LayoutDump<'_, T> {
+        LayoutDump { arena: self }
+    }
+}
+
+/// Drawing of an arena's slots, created by [`Arena::dump_layout`].
+pub struct LayoutDump<'a, T> {
+    arena: &'a Arena<T>,
+}
+
+impl<T> fmt::Display for LayoutDump<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arena = self.arena;
+        let stats = arena.stats();
+        write!(
+            f,
+            "{} slots, {} occupied, {} reserved, {} vacant",
+            arena.data.len(),
+            stats.occupied,
+            stats.reserved,
+            stats.vacant
+        )?;
+        if stats.retired > 0 {
+            write!(f, ", {} retired", stats.retired)?;
+        }
+        writeln!(f)?;
+
+        for (row, entries) in arena.data.chunks(ROW_LEN).enumerate() {
+            write!(f, "{:>6} ", row * ROW_LEN)?;
+            for entry in entries {
+                let cell = match entry {
+                    Entry::Vacant { .. } if arena.is_retired(entry) => 'x',
+                    Entry::Vacant { .. } => '.',
+                    Entry::Reserved { .. } => 'R',
+                    Entry::Occupied { .. } => '#',
+                };
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        // Bounded by the number of slots, in case the list is corrupt.
+        write!(f, "free list:")?;
+        let mut next = arena.free_head;
+        let mut first = true;
+        for _ in 0..=arena.data.len() {
+            let Some(slot) = next else { break };
+            write!(f, "{}{}", if first { " " } else { " -> " }, slot)?;
+            first = false;
+            next = match arena.data.get(slot) {
+                Some(Entry::Vacant { next, .. }) => *next,
+                _ => {
+                    write!(f, " (not vacant)")?;
+                    None
+                }
+            };
+        }
+        if first {
+            write!(f, " empty")?;
+        } else if next.is_some() {
+            write!(f, " -> ...")?;
+        }
+        writeln!(f)
+    }
+}
+
+impl<T> fmt::Debug for LayoutDump<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,258 @@
+//! In-place access to a single slot, in the style of `HashMap::entry`.
+use core::fmt;
+
+use crate::{Access, Arena, Entry, Index};
+
+/// A view into a single slot of an [`Arena`], created by [`Arena::entry`].
+pub enum SlotEntry<'a, T> {
+    Occupied(OccupiedEntry<'a, T>),
+    Vacant(VacantEntry<'a, T>),
+}
+
+/// A slot holding the item of the index passed to [`Arena::entry`].
+pub struct OccupiedEntry<'a, T> {
+    arena: &'a mut Arena<T>,
+    index: Index,
+}
+
+/// A vacant or reserved slot, which can be filled through the entry.
+pub struct VacantEntry<'a, T> {
+    arena: &'a mut Arena<T>,
+    index: Index,
+}
+
+impl<T> Arena<T> {
+    /// Get the entry for the slot of `index`, to inspect or fill it
+    /// with a single lookup.
+    ///
+    /// The entry is occupied if `index` resolves to an item. It is vacant
+    /// if the slot is free, or is the pending [reservation](Arena::reserve_index)
+    /// of `index`. Filling a free slot hands out a new index for it, which
+    /// is different from `index`.
+    ///
+    /// Returns `None` if `index` is out of bounds, or its slot holds
+    /// another item or reservation.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.reserve_index();
+    ///
+    /// *arena.entry(index).unwrap().or_insert(0) += 1;
+    /// *arena.entry(index).unwrap().or_insert(0) += 1;
+    /// assert_eq!(arena.get(index), Some(&2));
+    /// ```
+    pub fn entry(&mut self, index: Index) -> Option<SlotEntry<'_, T>> {
+        self.watchpoints.notify(index.slot(), Access::GetMut, index);
+
+        if self.is_live(index) {
+            self.touch(index.slot());
+            return Some(SlotEntry::Occupied(OccupiedEntry { arena: self, index }));
+        }
+
+        let vacant = match self.data.get(index.slot()) {
+            Some(entry @ Entry::Vacant { generation, .. }) if !self.is_retired(entry) => {
+                self.stamp(Index::new(index.slot(), *generation))
+            }
+            Some(Entry::Reserved { generation }) if *generation == index.generation => index,
+            _ => {
+                self.report_stale(Access::GetMut, index);
+                return None;
+            }
+        };
+
+        Some(SlotEntry::Vacant(VacantEntry {
+            arena: self,
+            index: vacant,
+        }))
+    }
+
+    /// Return the item at `index`, or fill its slot with the result of `f`
+    /// if it's free or reserved for `index`, together with the item's index.
+    ///
+    /// A free slot is filled at a new generation, so the returned index
+    /// replaces `index`. Returns `None` if `index` is out of bounds, or its
+    /// slot holds another item or reservation. See [`Arena::entry`].
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let old = arena.insert("Foo");
+    /// arena.remove(old);
+    ///
+    /// let (new, item) = arena.get_or_insert_with(old, || "Bar").unwrap();
+    /// assert_eq!(*item, "Bar");
+    /// assert_eq!(new.slot(), old.slot());
+    /// assert_eq!(arena.get_or_insert_with(new, || "Baz"), Some((new, &mut "Bar")));
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item would exceed the arena's [memory budget](Arena::set_memory_budget).
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, index: Index, f: F) -> Option<(Index, &mut T)> {
+        let entry = self.entry(index)?;
+        let index = entry.index();
+        Some((index, entry.or_insert_with(f)))
+    }
+}
+
+impl<'a, T> SlotEntry<'a, T> {
+    /// The index of the item in the slot, or the index
+    /// the item will get when the slot is filled.
+    pub fn index(&self) -> Index {
+        match self {
+            SlotEntry::Occupied(entry) => entry.index(),
+            SlotEntry::Vacant(entry) => entry.index(),
+        }
+    }
+
+    /// Fill the slot with `default` if it's vacant, and return
+    /// a mutable reference to the item in it.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item would exceed the arena's [memory budget](Arena::set_memory_budget).
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        match self {
+            SlotEntry::Occupied(entry) => entry.into_mut(),
+            SlotEntry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Fill the slot with the result of `default` if it's vacant, and
+    /// return a mutable reference to the item in it.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item would exceed the arena's [memory budget](Arena::set_memory_budget).
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            SlotEntry::Occupied(entry) => entry.into_mut(),
+            SlotEntry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Call `f` with the item if the slot is occupied.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert(1);
+    ///
+    /// arena.entry(index).unwrap().and_modify(|item| *item += 1).or_insert(0);
+    /// assert_eq!(arena.get(index), Some(&2));
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let SlotEntry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, T: Default> SlotEntry<'a, T> {
+    /// Fill the slot with the default value if it's vacant, and
+    /// return a mutable reference to the item in it.
+    pub fn or_default(self) -> &'a mut T {
+        self.or_insert_with(T::default)
+    }
+}
+
+impl<'a, T> OccupiedEntry<'a, T> {
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    pub fn get(&self) -> &T {
+        self.arena.lookup(self.index).unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        match &mut self.arena.data[self.index.slot()] {
+            Entry::Occupied { item, .. } => item,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Convert the entry into a mutable reference to its item,
+    /// which lives as long as the borrow of the arena.
+    pub fn into_mut(self) -> &'a mut T {
+        match &mut self.arena.data[self.index.slot()] {
+            Entry::Occupied { item, .. } => item,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Replace the item in place, keeping its index, and return the old one.
+    pub fn insert(&mut self, item: T) -> T {
+        self.arena.account_added(&item);
+        let original = core::mem::replace(self.get_mut(), item);
+        self.arena.account_removed(&original);
+        self.arena.notify_removed(self.index, &original);
+        original
+    }
+
+    /// Remove the item from the arena, as if by [`Arena::take`].
+    pub fn remove(self) -> T {
+        self.arena.remove_entry(self.index)
+    }
+}
+
+impl<'a, T> VacantEntry<'a, T> {
+    /// The index the item will get when the slot is filled.
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    /// Fill the slot with `item`, and return a mutable reference to it.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item would exceed the arena's [memory budget](Arena::set_memory_budget)
+    /// or [limit](Arena::with_limit).
+    pub fn insert(self, item: T) -> &'a mut T {
+        let VacantEntry { arena, index } = self;
+        let slot = index.slot();
+        arena.assert_below_limit();
+        assert!(arena.reserve_budget(&item, false), "arena memory budget exceeded");
+
+        match arena.data.get(slot) {
+            Some(Entry::Vacant { generation, .. }) if *generation == index.generation => {
+                arena.occupy_vacant(slot, item);
+            }
+            Some(Entry::Reserved { generation }) if *generation == index.generation => {
+                arena.occupy_reserved(index, item);
+            }
+            _ => panic!("budget handler reused the slot of the vacant entry"),
+        }
+
+        match &mut arena.data[slot] {
+            Entry::Occupied { item, .. } => item,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SlotEntry<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlotEntry::Occupied(entry) => f.debug_tuple("SlotEntry").field(entry).finish(),
+            SlotEntry::Vacant(entry) => f.debug_tuple("SlotEntry").field(entry).finish(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OccupiedEntry<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("index", &self.index)
+            .field("item", self.get())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for VacantEntry<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantEntry").field("index", &self.index).finish()
+    }
+}
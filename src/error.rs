@@ -0,0 +1,139 @@
+//! Errors shared by several arena operations.
+use core::fmt;
+use core::num::NonZeroUsize;
+
+use crate::{Access, Arena, Entry, Index};
+
+/// Error returned when indices that must be disjoint point to the same slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AliasError {
+    /// The slot referred to more than once.
+    pub slot: usize,
+}
+
+impl fmt::Display for AliasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "indices point to the same slot {}", self.slot)
+    }
+}
+
+impl core::error::Error for AliasError {}
+
+/// Reason an index failed to resolve, returned by the `try_` accessors
+/// such as [`Arena::try_get`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaError {
+    /// The index points beyond the arena's storage.
+    OutOfBounds(Index),
+    /// The slot holds a newer generation than the index.
+    StaleGeneration { index: Index, current: NonZeroUsize },
+    /// The slot is vacant, its item was removed.
+    Vacant(Index),
+    /// The slot is [reserved](Arena::reserve_index), but not filled yet.
+    Pending(Index),
+    /// The item was rejected by the arena's [tag checker](Arena::set_tag_checker).
+    TagMismatch(Index),
+}
+
+impl fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArenaError::OutOfBounds(index) => write!(f, "index {:?} is out of bounds", index),
+            ArenaError::StaleGeneration { index, current } => {
+                write!(f, "index {:?} is stale, its slot holds generation {}", index, current)
+            }
+            ArenaError::Vacant(index) => write!(f, "index {:?} points to a vacant slot", index),
+            ArenaError::Pending(index) => write!(f, "index {:?} points to a reservation that isn't filled", index),
+            ArenaError::TagMismatch(index) => write!(f, "index {:?} has a tag rejected by the tag checker", index),
+        }
+    }
+}
+
+impl core::error::Error for ArenaError {}
+
+impl<T> Arena<T> {
+    /// Return a reference to the item at the given `index`, or the
+    /// reason it can't be resolved.
+    ///
+    /// The `try_` accessors report a failed lookup only through their
+    /// result. They never panic in [strict mode](Arena::set_strict), nor
+    /// invoke the [stale handler](Arena::set_stale_handler).
+    ///
+    /// ```
+    /// # use arena::{Arena, ArenaError};
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// assert_eq!(arena.try_get(index), Ok(&"Foo"));
+    ///
+    /// arena.remove(index);
+    /// assert_eq!(arena.try_get(index), Err(ArenaError::Vacant(index)));
+    /// ```
+    pub fn try_get(&self, index: Index) -> Result<&T, ArenaError> {
+        self.watchpoints.notify(index.slot(), Access::Get, index);
+        self.lookup(index).ok_or_else(|| self.diagnose(index))
+    }
+
+    /// Return a mutable reference to the item at the given `index`, or
+    /// the reason it can't be resolved.
+    pub fn try_get_mut(&mut self, index: Index) -> Result<&mut T, ArenaError> {
+        if !self.is_live(index) {
+            self.watchpoints.notify(index.slot(), Access::GetMut, index);
+            return Err(self.diagnose(index));
+        }
+
+        Ok(self.get_mut(index).unwrap())
+    }
+
+    /// Remove and return the item at the given `index`, or the reason it
+    /// can't be resolved. Unlike [`Arena::take`], never panics.
+    pub fn try_take(&mut self, index: Index) -> Result<T, ArenaError> {
+        if !self.is_live(index) {
+            self.watchpoints.notify(index.slot(), Access::Remove, index);
+            return Err(self.diagnose(index));
+        }
+
+        Ok(self.take(index).unwrap())
+    }
+
+    /// Remove the item at the given `index`, or return the reason it
+    /// can't be resolved. Unlike [`Arena::remove`], never panics.
+    pub fn try_remove(&mut self, index: Index) -> Result<(), ArenaError> {
+        self.try_take(index).map(|_| ())
+    }
+
+    /// Exchange the items at `a` and `b`, or return the reason one of
+    /// them can't be resolved. Unlike [`Arena::swap`], never panics.
+    pub fn try_swap(&mut self, a: Index, b: Index) -> Result<(), ArenaError> {
+        for index in [a, b] {
+            if !self.is_live(index) {
+                return Err(self.diagnose(index));
+            }
+        }
+
+        // Both resolve, so they point to the same item if they share a slot.
+        if a.slot() != b.slot() {
+            if let [Some(a), Some(b)] = self.get_disjoint_mut([a, b]) {
+                core::mem::swap(a, b);
+            }
+        }
+        Ok(())
+    }
+
+    /// Explain why `index` doesn't resolve to an item.
+    fn diagnose(&self, index: Index) -> ArenaError {
+        match self.data.get(index.slot()) {
+            None => ArenaError::OutOfBounds(index),
+            Some(Entry::Vacant { .. }) => ArenaError::Vacant(index),
+            Some(Entry::Reserved { generation } | Entry::Occupied { generation, .. })
+                if *generation != index.generation =>
+            {
+                ArenaError::StaleGeneration {
+                    index,
+                    current: *generation,
+                }
+            }
+            Some(Entry::Reserved { .. }) => ArenaError::Pending(index),
+            Some(Entry::Occupied { .. }) => ArenaError::TagMismatch(index),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Handles for foreign code, and a C API behind the `ffi` feature.
+//!
+//! The C API stores opaque item pointers owned by the host, in an
+//! [`Arena`] created by [`arena_new`] and destroyed by [`arena_free`].
+//! Stale indices and [`CIndex::NULL`] resolve to null pointers.
+#[cfg(feature = "ffi")]
+use core::ffi::c_void;
+
+#[cfg(feature = "ffi")]
+use crate::Arena;
+use crate::{Index, PackIndexError};
+
+/// FFI-safe [`Index`] with a fixed layout, for handles held by foreign code.
+///
+/// A generation of `0` never resolves, and marks the null index.
+///
+/// ```
+/// # use arena::{Arena, CIndex};
+/// # let mut arena = Arena::new();
+/// let index = arena.insert("Foo");
+///
+/// let handle = CIndex::try_from(index).unwrap();
+/// assert_eq!(handle.generation, 1);
+/// assert_eq!(handle.to_index(), Some(index));
+/// assert_eq!(CIndex::NULL.to_index(), None);
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CIndex {
+    pub slot: u64,
+    pub generation: u64,
+}
+
+impl CIndex {
+    /// Index which never resolves.
+    pub const NULL: CIndex = CIndex { slot: 0, generation: 0 };
+
+    pub fn is_null(&self) -> bool {
+        self.generation == 0
+    }
+
+    /// The index, or `None` for the null index or one that doesn't fit
+    /// this platform's indices.
+    pub fn to_index(self) -> Option<Index> {
+        let slot = usize::try_from(self.slot)
+            .ok()
+            .filter(|slot| *slot <= Index::SLOT_MASK)?;
+        let generation = usize::try_from(self.generation).ok()?;
+        Some(Index::new(slot, core::num::NonZeroUsize::new(generation)?))
+    }
+}
+
+impl TryFrom<Index> for CIndex {
+    type Error = PackIndexError;
+
+    /// Fails if the index carries a tag, which isn't preserved.
+    fn try_from(index: Index) -> Result<Self, Self::Error> {
+        if index.tag() != 0 {
+            return Err(PackIndexError::Tagged);
+        }
+
+        Ok(CIndex {
+            slot: u64::try_from(index.slot()).map_err(|_| PackIndexError::SlotOverflow)?,
+            generation: u64::try_from(index.generation()).map_err(|_| PackIndexError::GenerationOverflow)?,
+        })
+    }
+}
+
+/// Arena used by the C API.
+#[cfg(feature = "ffi")]
+pub type CArena = Arena<*mut c_void>;
+
+#[cfg(feature = "ffi")]
+fn resolve(arena: &CArena, index: CIndex) -> Option<Index> {
+    index.to_index().filter(|index| arena.contains(*index))
+}
+
+/// Create an empty arena, to be destroyed with [`arena_free`].
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn arena_new() -> *mut CArena {
+    alloc::boxed::Box::into_raw(alloc::boxed::Box::new(Arena::new()))
+}
+
+/// Destroy an arena created by [`arena_new`]. The stored pointers
+/// are not freed.
+///
+/// # Safety
+///
+/// `arena` must be null, or created by [`arena_new`] and not yet freed.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn arena_free(arena: *mut CArena) {
+    if !arena.is_null() {
+        drop(alloc::boxed::Box::from_raw(arena));
+    }
+}
+
+/// Insert the pointer, and return its index.
+///
+/// # Safety
+///
+/// `arena` must be a live arena created by [`arena_new`].
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn arena_insert(arena: *mut CArena, item: *mut c_void) -> CIndex {
+    let index = (*arena).insert(item);
+    CIndex::try_from(index).unwrap_or(CIndex::NULL)
+}
+
+/// The pointer stored at `index`, or null if the index is stale.
+///
+/// # Safety
+///
+/// `arena` must be a live arena created by [`arena_new`].
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn arena_get(arena: *const CArena, index: CIndex) -> *mut c_void {
+    let arena = &*arena;
+    match resolve(arena, index) {
+        Some(index) => arena[index],
+        None => core::ptr::null_mut(),
+    }
+}
+
+/// Remove and return the pointer stored at `index`, or null if the
+/// index is stale.
+///
+/// # Safety
+///
+/// `arena` must be a live arena created by [`arena_new`].
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn arena_remove(arena: *mut CArena, index: CIndex) -> *mut c_void {
+    let arena = &mut *arena;
+    match resolve(arena, index) {
+        Some(index) => arena.take(index).unwrap_or(core::ptr::null_mut()),
+        None => core::ptr::null_mut(),
+    }
+}
+
+/// Whether `index` points to a pointer in the arena.
+///
+/// # Safety
+///
+/// `arena` must be a live arena created by [`arena_new`].
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn arena_contains(arena: *const CArena, index: CIndex) -> bool {
+    resolve(&*arena, index).is_some()
+}
+
+/// Number of pointers in the arena.
+///
+/// # Safety
+///
+/// `arena` must be a live arena created by [`arena_new`].
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn arena_len(arena: *const CArena) -> usize {
+    (*arena).len()
+}
@@ -0,0 +1,242 @@
+//! Arena with a fixed capacity, stored inline without heap allocation.
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+
+use crate::checks::Stamp;
+use crate::{Arena, Entry, Full, Index, Iter, IterIndexed, IterIndexedMut, IterMut, Keys};
+
+/// Arena holding at most `N` items in an inline array.
+///
+/// Never allocates, so it can live on the stack, in a `static`, or be used
+/// where allocation isn't allowed, like an audio thread. Indices have the
+/// same generation semantics as in [`Arena`](crate::Arena), but are only
+/// meaningful in the arena that handed them out.
+///
+/// ```
+/// # use arena::FixedArena;
+/// let mut arena = FixedArena::<_, 2>::new();
+/// let index0 = arena.insert("Foo");
+/// arena.insert("Bar");
+///
+/// let full = arena.try_insert("Baz").unwrap_err();
+/// assert_eq!(full.into_inner(), "Baz");
+///
+/// arena.remove(index0);
+/// assert!(arena.try_insert("Baz").is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedArena<T, const N: usize> {
+    data: [Entry<T>; N],
+    free_head: Option<usize>,
+    count: usize,
+}
+
+impl<T, const N: usize> FixedArena<T, N> {
+    /// Create an empty arena, with every slot vacant.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `N` exceeds the number of slots an index can address.
+    pub fn new() -> Self {
+        assert!(N <= Index::SLOT_MASK, "arena exceeded the maximum number of slots");
+
+        Self {
+            data: core::array::from_fn(|slot| Entry::Vacant {
+                generation: NonZeroUsize::MIN,
+                next: (slot + 1 < N).then_some(slot + 1),
+            }),
+            free_head: (N > 0).then_some(0),
+            count: 0,
+        }
+    }
+
+    /// Maximum number of items the arena can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.free_head.is_none()
+    }
+
+    /// Insert the item into the first free slot.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the arena is full.
+    pub fn insert(&mut self, item: T) -> Index {
+        match self.try_insert(item) {
+            Ok(index) => index,
+            Err(_) => panic!("fixed arena of capacity {} is full", N),
+        }
+    }
+
+    /// Insert the item into the first free slot, or hand it back if the arena is full.
+    pub fn try_insert(&mut self, item: T) -> Result<Index, Full<T>> {
+        let Some(slot) = self.free_head else {
+            return Err(Full(item));
+        };
+
+        let (generation, next) = match self.data[slot] {
+            Entry::Vacant { generation, next } => (generation, next),
+            _ => unreachable!("free list points to an occupied slot"),
+        };
+        self.free_head = next;
+        self.data[slot] = Entry::Occupied { generation, item };
+        self.count += 1;
+
+        Ok(Index::new(slot, generation))
+    }
+
+    /// Remove the item at the given `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: Index) {
+        self.take(index);
+    }
+
+    /// Remove and return the item at the given `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn take(&mut self, index: Index) -> Option<T> {
+        let slot = index.slot();
+        assert!(slot < N, "index out of bounds");
+
+        if !self.contains(index) {
+            return None;
+        }
+
+        let vacant = Entry::Vacant {
+            generation: index.generation.saturating_add(1),
+            next: self.free_head,
+        };
+        let item = core::mem::replace(&mut self.data[slot], vacant).unwrap_occupied().1;
+        self.free_head = Some(slot);
+        self.count -= 1;
+
+        Some(item)
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        match self.data.get(index.slot()) {
+            Some(Entry::Occupied { generation, item }) if *generation == index.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        match self.data.get_mut(index.slot()) {
+            Some(Entry::Occupied { generation, item }) if *generation == index.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Remove every item. Indices handed out before are invalidated.
+    pub fn clear(&mut self) {
+        self.free_head = None;
+
+        for slot in (0..N).rev() {
+            let generation = match &self.data[slot] {
+                Entry::Occupied { generation, .. } | Entry::Reserved { generation } => generation.saturating_add(1),
+                Entry::Vacant { generation, .. } => *generation,
+            };
+            self.data[slot] = Entry::Vacant {
+                generation,
+                next: self.free_head,
+            };
+            self.free_head = Some(slot);
+        }
+
+        self.count = 0;
+    }
+
+    /// Iterate the items in slot order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.data.iter(),
+            remaining: self.count,
+        }
+    }
+
+    /// Mutably iterate the items in slot order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.data.iter_mut(),
+            remaining: self.count,
+        }
+    }
+
+    /// Iterate the items in slot order, together with their indices.
+    pub fn iter_indexed(&self) -> IterIndexed<'_, T> {
+        IterIndexed {
+            inner: self.data.iter().enumerate(),
+            remaining: self.count,
+            stamp: Stamp::NONE,
+        }
+    }
+
+    /// Mutably iterate the items in slot order, together with their indices.
+    pub fn iter_indexed_mut(&mut self) -> IterIndexedMut<'_, T> {
+        IterIndexedMut {
+            inner: self.data.iter_mut().enumerate(),
+            remaining: self.count,
+            stamp: Stamp::NONE,
+        }
+    }
+
+    /// Iterate the indices of the items in slot order.
+    pub fn keys(&self) -> Keys<'_, T> {
+        Keys {
+            inner: self.iter_indexed(),
+        }
+    }
+
+    /// Move the slots to a heap allocated arena, where all
+    /// indices stay valid.
+    pub fn into_arena(self) -> Arena<T> {
+        let mut arena = Arena::new();
+        arena.data = Vec::from(self.data);
+        arena.free_head = self.free_head;
+        arena.count = self.count;
+        arena
+    }
+}
+
+impl<T, const N: usize> Default for FixedArena<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> core::ops::Index<Index> for FixedArena<T, N> {
+    type Output = T;
+
+    /// # Panic
+    ///
+    /// Panics if `index` is stale or out of bounds.
+    fn index(&self, index: Index) -> &T {
+        self.get(index).expect("index is stale or out of bounds")
+    }
+}
+
+impl<T, const N: usize> core::ops::IndexMut<Index> for FixedArena<T, N> {
+    fn index_mut(&mut self, index: Index) -> &mut T {
+        self.get_mut(index).expect("index is stale or out of bounds")
+    }
+}
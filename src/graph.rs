@@ -0,0 +1,357 @@
+//! Directed graphs with nodes and edges stored in arenas.
+use alloc::vec::Vec;
+use core::slice::Iter as SliceIter;
+
+use crate::{Arena, Index, IterIndexed};
+
+/// Handle to a node of an [`ArenaGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeIndex(Index);
+
+impl NodeIndex {
+    /// The index of the node in the graph's node arena.
+    pub fn index(self) -> Index {
+        self.0
+    }
+}
+
+/// Handle to an edge of an [`ArenaGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeIndex(Index);
+
+impl EdgeIndex {
+    /// The index of the edge in the graph's edge arena.
+    pub fn index(self) -> Index {
+        self.0
+    }
+}
+
+/// Direction of the edges to follow from a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Edges starting at the node.
+    Outgoing,
+    /// Edges ending at the node.
+    Incoming,
+}
+
+#[derive(Debug, Clone)]
+struct Node<N> {
+    weight: N,
+    outgoing: Vec<EdgeIndex>,
+    incoming: Vec<EdgeIndex>,
+}
+
+impl<N> Node<N> {
+    fn edges(&self, direction: Direction) -> &[EdgeIndex] {
+        match direction {
+            Direction::Outgoing => &self.outgoing,
+            Direction::Incoming => &self.incoming,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Edge<E> {
+    weight: E,
+    source: NodeIndex,
+    target: NodeIndex,
+}
+
+/// Directed graph with node weights `N` and edge weights `E`.
+///
+/// Nodes and edges live in two arenas, so their handles stay valid until
+/// they are removed, and go stale afterwards. Removing a node removes the
+/// edges to and from it. Parallel edges and loops are allowed.
+///
+/// ```
+/// # use arena::graph::ArenaGraph;
+/// let mut graph = ArenaGraph::new();
+/// let app = graph.add_node("app");
+/// let lib = graph.add_node("lib");
+/// let core = graph.add_node("core");
+/// graph.add_edge(app, lib, ());
+/// graph.add_edge(lib, core, ());
+/// graph.add_edge(app, core, ());
+///
+/// assert_eq!(graph.neighbors(app).collect::<Vec<_>>(), [lib, core]);
+///
+/// graph.remove_node(lib);
+/// assert_eq!(graph.edge_count(), 1);
+/// assert_eq!(graph.neighbors(app).collect::<Vec<_>>(), [core]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArenaGraph<N, E> {
+    nodes: Arena<Node<N>>,
+    edges: Arena<Edge<E>>,
+}
+
+impl<N, E> ArenaGraph<N, E> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Arena::new(),
+            edges: Arena::new(),
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn add_node(&mut self, weight: N) -> NodeIndex {
+        NodeIndex(self.nodes.insert(Node {
+            weight,
+            outgoing: Vec::new(),
+            incoming: Vec::new(),
+        }))
+    }
+
+    /// Add an edge from `source` to `target`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if either node is stale.
+    pub fn add_edge(&mut self, source: NodeIndex, target: NodeIndex, weight: E) -> EdgeIndex {
+        assert!(self.contains_node(source), "source node {:?} is stale", source);
+        assert!(self.contains_node(target), "target node {:?} is stale", target);
+
+        let edge = EdgeIndex(self.edges.insert(Edge { weight, source, target }));
+        self.nodes[source.0].outgoing.push(edge);
+        self.nodes[target.0].incoming.push(edge);
+        edge
+    }
+
+    /// Remove the node and every edge to and from it, and return its weight.
+    pub fn remove_node(&mut self, node: NodeIndex) -> Option<N> {
+        let Node {
+            weight,
+            outgoing,
+            incoming,
+        } = self.nodes.take(node.0)?;
+
+        for edge in outgoing.into_iter().chain(incoming) {
+            self.remove_edge(edge);
+        }
+        Some(weight)
+    }
+
+    /// Remove the edge, and return its weight.
+    pub fn remove_edge(&mut self, edge: EdgeIndex) -> Option<E> {
+        let Edge { weight, source, target } = self.edges.take(edge.0)?;
+
+        // Either end may already be gone when removing a node.
+        if let Some(node) = self.nodes.get_mut(source.0) {
+            node.outgoing.retain(|other| *other != edge);
+        }
+        if let Some(node) = self.nodes.get_mut(target.0) {
+            node.incoming.retain(|other| *other != edge);
+        }
+        Some(weight)
+    }
+
+    pub fn contains_node(&self, node: NodeIndex) -> bool {
+        self.nodes.contains(node.0)
+    }
+
+    pub fn contains_edge(&self, edge: EdgeIndex) -> bool {
+        self.edges.contains(edge.0)
+    }
+
+    pub fn node(&self, node: NodeIndex) -> Option<&N> {
+        self.nodes.get(node.0).map(|node| &node.weight)
+    }
+
+    pub fn node_mut(&mut self, node: NodeIndex) -> Option<&mut N> {
+        self.nodes.get_mut(node.0).map(|node| &mut node.weight)
+    }
+
+    pub fn edge(&self, edge: EdgeIndex) -> Option<&E> {
+        self.edges.get(edge.0).map(|edge| &edge.weight)
+    }
+
+    pub fn edge_mut(&mut self, edge: EdgeIndex) -> Option<&mut E> {
+        self.edges.get_mut(edge.0).map(|edge| &mut edge.weight)
+    }
+
+    /// Source and target of the edge.
+    pub fn edge_endpoints(&self, edge: EdgeIndex) -> Option<(NodeIndex, NodeIndex)> {
+        self.edges.get(edge.0).map(|edge| (edge.source, edge.target))
+    }
+
+    /// The first edge from `source` to `target`.
+    pub fn find_edge(&self, source: NodeIndex, target: NodeIndex) -> Option<EdgeIndex> {
+        self.edges_directed(source, Direction::Outgoing)
+            .find(|edge| self.edges[edge.0].target == target)
+    }
+
+    /// Iterate the targets of the edges starting at `node`.
+    ///
+    /// A node reached by several edges is visited once per edge.
+    pub fn neighbors(&self, node: NodeIndex) -> Neighbors<'_, E> {
+        self.neighbors_directed(node, Direction::Outgoing)
+    }
+
+    /// Iterate the nodes at the other end of the edges in `direction`.
+    ///
+    /// A stale node has no neighbors.
+    pub fn neighbors_directed(&self, node: NodeIndex, direction: Direction) -> Neighbors<'_, E> {
+        Neighbors {
+            edges: &self.edges,
+            inner: self.edges_directed(node, direction),
+            direction,
+        }
+    }
+
+    /// Iterate the edges of `node` in `direction`.
+    ///
+    /// A stale node has no edges.
+    pub fn edges_directed(&self, node: NodeIndex, direction: Direction) -> EdgeIndices<'_> {
+        let edges = match self.nodes.get(node.0) {
+            Some(node) => node.edges(direction),
+            None => &[],
+        };
+        EdgeIndices { inner: edges.iter() }
+    }
+
+    /// Iterate the nodes, together with their handles.
+    pub fn nodes(&self) -> Nodes<'_, N> {
+        Nodes {
+            inner: self.nodes.iter_indexed(),
+        }
+    }
+
+    /// Iterate the edges, together with their handles.
+    pub fn edges(&self) -> Edges<'_, E> {
+        Edges {
+            inner: self.edges.iter_indexed(),
+        }
+    }
+
+    /// Remove every node and edge. Handles given out before are invalidated.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.edges.clear();
+    }
+}
+
+impl<N, E> Default for ArenaGraph<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, E> core::ops::Index<NodeIndex> for ArenaGraph<N, E> {
+    type Output = N;
+
+    /// # Panic
+    ///
+    /// Panics if `node` is stale.
+    fn index(&self, node: NodeIndex) -> &N {
+        self.node(node).expect("node is stale")
+    }
+}
+
+impl<N, E> core::ops::IndexMut<NodeIndex> for ArenaGraph<N, E> {
+    fn index_mut(&mut self, node: NodeIndex) -> &mut N {
+        self.node_mut(node).expect("node is stale")
+    }
+}
+
+impl<N, E> core::ops::Index<EdgeIndex> for ArenaGraph<N, E> {
+    type Output = E;
+
+    /// # Panic
+    ///
+    /// Panics if `edge` is stale.
+    fn index(&self, edge: EdgeIndex) -> &E {
+        self.edge(edge).expect("edge is stale")
+    }
+}
+
+impl<N, E> core::ops::IndexMut<EdgeIndex> for ArenaGraph<N, E> {
+    fn index_mut(&mut self, edge: EdgeIndex) -> &mut E {
+        self.edge_mut(edge).expect("edge is stale")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EdgeIndices<'a> {
+    inner: SliceIter<'a, EdgeIndex>,
+}
+
+impl Iterator for EdgeIndices<'_> {
+    type Item = EdgeIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for EdgeIndices<'_> {}
+
+#[derive(Debug)]
+pub struct Neighbors<'a, E> {
+    edges: &'a Arena<Edge<E>>,
+    inner: EdgeIndices<'a>,
+    direction: Direction,
+}
+
+impl<E> Iterator for Neighbors<'_, E> {
+    type Item = NodeIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let edge = &self.edges[self.inner.next()?.0];
+        match self.direction {
+            Direction::Outgoing => Some(edge.target),
+            Direction::Incoming => Some(edge.source),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<E> ExactSizeIterator for Neighbors<'_, E> {}
+
+#[derive(Debug)]
+pub struct Nodes<'a, N> {
+    inner: IterIndexed<'a, Node<N>>,
+}
+
+impl<'a, N> Iterator for Nodes<'a, N> {
+    type Item = (NodeIndex, &'a N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, node)| (NodeIndex(index), &node.weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[derive(Debug)]
+pub struct Edges<'a, E> {
+    inner: IterIndexed<'a, Edge<E>>,
+}
+
+impl<'a, E> Iterator for Edges<'a, E> {
+    type Item = (EdgeIndex, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, edge)| (EdgeIndex(index), &edge.weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
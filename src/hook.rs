@@ -0,0 +1,48 @@
+//! Callbacks observing items as they leave the arena.
+use crate::{Arena, Index};
+
+/// Function invoked with every item that leaves the arena,
+/// see [`Arena::set_remove_hook`].
+pub type RemoveFn<T> = fn(Index, &T);
+
+impl<T> Arena<T> {
+    /// Register a function to call with every item that leaves the arena,
+    /// together with the index it was stored at.
+    ///
+    /// The hook sees items that are removed or taken, replaced by
+    /// [`Arena::replace`] or through an [entry](Arena::entry), and dropped by
+    /// [`Arena::clear`], draining, retaining or rolling back a
+    /// [snapshot](Arena::restore). It is not called when the arena itself is
+    /// dropped, nor for items relocated by compaction.
+    ///
+    /// The hook is called once the arena is consistent again, so a
+    /// panicking hook can't corrupt it. Replaces any previous hook.
+    ///
+    /// ```
+    /// # use arena::{Arena, Index};
+    /// fn release(index: Index, texture: &u32) {
+    ///     println!("releasing texture {} at {:?}", texture, index);
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.set_remove_hook(release);
+    ///
+    /// let index = arena.insert(7);
+    /// arena.remove(index); // releasing texture 7
+    /// ```
+    pub fn set_remove_hook(&mut self, hook: RemoveFn<T>) {
+        self.remove_hook = Some(hook);
+    }
+
+    /// Remove the hook registered with [`Arena::set_remove_hook`], if any.
+    pub fn clear_remove_hook(&mut self) {
+        self.remove_hook = None;
+    }
+
+    #[inline]
+    pub(crate) fn notify_removed(&self, index: Index, item: &T) {
+        if let Some(hook) = self.remove_hook {
+            hook(index, item);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Generational ID allocator, for arenas without any items.
+use alloc::vec::Vec;
+use core::iter::Enumerate;
+use core::num::NonZeroUsize;
+use core::slice::Iter as SliceIter;
+
+use crate::Index;
+
+/// Marks a slot whose ID is in use, in place of a free list link.
+const ALLOCATED: usize = usize::MAX;
+
+/// Ends the free list.
+const END: usize = usize::MAX - 1;
+
+/// Hands out generational [`Index`] IDs, like an `Arena<()>` but storing
+/// only a generation and a free list link per slot.
+///
+/// IDs behave like indices into an arena. Once freed, an ID no longer
+/// [resolves](IdAllocator::contains), even after its slot was reused.
+///
+/// ```
+/// # use arena::IdAllocator;
+/// let mut ids = IdAllocator::new();
+/// let id0 = ids.alloc();
+/// let id1 = ids.alloc();
+///
+/// assert!(ids.free(id0));
+/// let id2 = ids.alloc();
+/// assert!(!ids.contains(id0));
+/// assert!(ids.contains(id2));
+/// # assert!(ids.contains(id1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct IdAllocator {
+    slots: Vec<Slot>,
+    free_head: usize,
+    count: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    generation: NonZeroUsize,
+    /// Next free slot, or [`ALLOCATED`] if the slot is in use.
+    next: usize,
+}
+
+impl IdAllocator {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free_head: END,
+            count: 0,
+        }
+    }
+
+    /// Create an empty allocator with room for at least `capacity` IDs
+    /// before reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// Hand out a new ID, reusing a freed slot if there is one.
+    pub fn alloc(&mut self) -> Index {
+        let slot = if self.free_head == END {
+            let slot = self.slots.len();
+            assert!(slot < Index::SLOT_MASK, "arena exceeded the maximum number of slots");
+            self.slots.push(Slot {
+                generation: NonZeroUsize::MIN,
+                next: ALLOCATED,
+            });
+            slot
+        } else {
+            let slot = self.free_head;
+            self.free_head = self.slots[slot].next;
+            self.slots[slot].next = ALLOCATED;
+            slot
+        };
+
+        self.count += 1;
+        Index::new(slot, self.slots[slot].generation)
+    }
+
+    /// Free the ID, so it no longer resolves and its slot can be reused.
+    ///
+    /// Returns whether the ID was in use.
+    pub fn free(&mut self, index: Index) -> bool {
+        if !self.contains(index) {
+            return false;
+        }
+
+        let slot = &mut self.slots[index.slot()];
+        slot.generation = slot.generation.saturating_add(1);
+        slot.next = self.free_head;
+        self.free_head = index.slot();
+        self.count -= 1;
+        true
+    }
+
+    /// Check whether the ID was handed out and not freed yet.
+    pub fn contains(&self, index: Index) -> bool {
+        matches!(
+            self.slots.get(index.slot()),
+            Some(slot) if slot.next == ALLOCATED && slot.generation == index.generation
+        )
+    }
+
+    /// Number of IDs in use.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Number of slots the allocator can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Free every ID.
+    pub fn clear(&mut self) {
+        for slot in 0..self.slots.len() {
+            if self.slots[slot].next == ALLOCATED {
+                let generation = self.slots[slot].generation;
+                self.free(Index::new(slot, generation));
+            }
+        }
+    }
+
+    /// Iterate the IDs in use, in slot order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.slots.iter().enumerate(),
+            remaining: self.count,
+        }
+    }
+}
+
+impl Default for IdAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a> {
+    inner: Enumerate<SliceIter<'a, Slot>>,
+    remaining: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        for (slot, entry) in self.inner.by_ref() {
+            if entry.next == ALLOCATED {
+                self.remaining -= 1;
+                return Some(Index::new(slot, entry.generation));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
@@ -0,0 +1,143 @@
+//! Iterating the indices shared by several arenas and secondary maps.
+use core::iter::Peekable;
+
+use crate::{secondary, Arena, Index, IterIndexed, IterIndexedMut, SecondaryMap};
+
+/// Container that can take part in a [`join`], visiting its items in
+/// slot order.
+pub trait Joinable {
+    type Item;
+    type Iter: Iterator<Item = (Index, Self::Item)>;
+
+    fn join_iter(self) -> Self::Iter;
+}
+
+impl<'a, T> Joinable for &'a Arena<T> {
+    type Item = &'a T;
+    type Iter = IterIndexed<'a, T>;
+
+    fn join_iter(self) -> Self::Iter {
+        self.iter_indexed()
+    }
+}
+
+impl<'a, T> Joinable for &'a mut Arena<T> {
+    type Item = &'a mut T;
+    type Iter = IterIndexedMut<'a, T>;
+
+    fn join_iter(self) -> Self::Iter {
+        self.iter_indexed_mut()
+    }
+}
+
+impl<'a, V> Joinable for &'a SecondaryMap<V> {
+    type Item = &'a V;
+    type Iter = secondary::Iter<'a, V>;
+
+    fn join_iter(self) -> Self::Iter {
+        self.iter()
+    }
+}
+
+impl<'a, V> Joinable for &'a mut SecondaryMap<V> {
+    type Item = &'a mut V;
+    type Iter = secondary::IterMut<'a, V>;
+
+    fn join_iter(self) -> Self::Iter {
+        self.iter_mut()
+    }
+}
+
+/// Tuple of two to four [`Joinable`] containers.
+pub trait JoinSet {
+    type Item;
+    #[doc(hidden)]
+    type Iters;
+
+    #[doc(hidden)]
+    fn into_iters(self) -> Self::Iters;
+    #[doc(hidden)]
+    fn next(iters: &mut Self::Iters) -> Option<Self::Item>;
+}
+
+/// Iterate the indices that are live in every container of `set`,
+/// together with the items or values of each container.
+///
+/// Containers are walked side by side in slot order, and a slot is only
+/// visited if every container holds the same generation in it.
+///
+/// ```
+/// # use arena::{join, Arena, SecondaryMap};
+/// let mut positions = Arena::new();
+/// let mut velocities = SecondaryMap::new();
+///
+/// let index0 = positions.insert(0.0);
+/// let index1 = positions.insert(10.0);
+/// velocities.insert(index1, 2.0);
+///
+/// for (_, position, velocity) in join((&mut positions, &velocities)) {
+///     *position += velocity;
+/// }
+/// assert_eq!(positions[index0], 0.0);
+/// assert_eq!(positions[index1], 12.0);
+/// ```
+pub fn join<S: JoinSet>(set: S) -> Join<S> {
+    Join {
+        iters: set.into_iters(),
+    }
+}
+
+/// Iterator returned by [`join`].
+pub struct Join<S: JoinSet> {
+    iters: S::Iters,
+}
+
+impl<S: JoinSet> Iterator for Join<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        S::next(&mut self.iters)
+    }
+}
+
+macro_rules! impl_join_set {
+    ($($name:ident $iter:ident),+) => {
+        impl<$($name: Joinable),+> JoinSet for ($($name,)+) {
+            type Item = (Index, $($name::Item),+);
+            type Iters = ($(Peekable<$name::Iter>,)+);
+
+            fn into_iters(self) -> Self::Iters {
+                let ($($iter,)+) = self;
+                ($($iter.join_iter().peekable(),)+)
+            }
+
+            fn next(iters: &mut Self::Iters) -> Option<Self::Item> {
+                let ($($iter,)+) = iters;
+                loop {
+                    // Catch every container up to the furthest slot.
+                    let slot = 0 $(.max($iter.peek()?.0.slot()))+;
+                    $(
+                        while $iter.peek()?.0.slot() < slot {
+                            $iter.next();
+                        }
+                    )+
+                    if $($iter.peek()?.0.slot() != slot)||+ {
+                        continue;
+                    }
+
+                    let generations = [$($iter.peek()?.0.generation),+];
+                    if generations.iter().all(|generation| *generation == generations[0]) {
+                        return Some((Index::new(slot, generations[0]), $($iter.next()?.1),+));
+                    }
+
+                    // Each container holds a different item in the slot.
+                    $($iter.next();)+
+                }
+            }
+        }
+    };
+}
+
+impl_join_set!(A a, B b);
+impl_join_set!(A a, B b, C c);
+impl_join_set!(A a, B b, C c, D d);
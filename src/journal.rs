@@ -0,0 +1,75 @@
+//! Journal of insertions and removals, for replicating an arena.
+use alloc::vec::Vec;
+
+use crate::{Arena, Index};
+
+/// Kind of change recorded in the journal, see [`Arena::track_changes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Inserted,
+    Removed,
+    /// The item was replaced by a new one, stored under a new index.
+    Replaced {
+        previous: Index,
+    },
+    /// The item was moved to a new slot, by compaction or by swapping slots.
+    Relocated {
+        from: Index,
+    },
+}
+
+impl<T> Arena<T> {
+    /// Start recording every insertion, removal, replacement and
+    /// relocation, until the changes are collected by [`Arena::take_changes`].
+    ///
+    /// Modifications through mutable references aren't recorded, see
+    /// [`Arena::track_revisions`] for those.
+    ///
+    /// Calling this when tracking is already enabled has no effect.
+    ///
+    /// ```
+    /// # use arena::{Arena, ChangeKind};
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// arena.track_changes();
+    ///
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    /// assert_eq!(
+    ///     arena.take_changes(),
+    ///     [(index1, ChangeKind::Inserted), (index0, ChangeKind::Removed)]
+    /// );
+    /// assert!(arena.take_changes().is_empty());
+    /// ```
+    pub fn track_changes(&mut self) {
+        if self.journal.is_none() {
+            self.journal = Some(Vec::new());
+        }
+    }
+
+    /// Stop recording changes, and discard the recorded ones.
+    pub fn untrack_changes(&mut self) {
+        self.journal = None;
+    }
+
+    pub fn is_tracking_changes(&self) -> bool {
+        self.journal.is_some()
+    }
+
+    /// Return the changes recorded since the last call, oldest first.
+    ///
+    /// Returns an empty list if changes are not tracked.
+    pub fn take_changes(&mut self) -> Vec<(Index, ChangeKind)> {
+        match &mut self.journal {
+            Some(journal) => core::mem::take(journal),
+            None => Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn record_change(&mut self, index: Index, kind: ChangeKind) {
+        if let Some(journal) = &mut self.journal {
+            journal.push((index, kind));
+        }
+    }
+}
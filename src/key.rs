@@ -0,0 +1,85 @@
+//! Keys for using indices in ordered and hashed containers.
+use core::cmp::Ordering;
+use core::fmt;
+
+use crate::Index;
+
+/// The slot of an [`Index`], without its generation and tag, for keying
+/// containers by position in an arena's storage.
+///
+/// Every index into the same slot has the same key, whether it's live or
+/// stale. Like an [`Index`], keys hash the slot as a `u64`, so the hash is
+/// the same on every pointer width.
+///
+/// ```
+/// # use arena::{Arena, SlotKey};
+/// # use std::collections::BTreeMap;
+/// # let mut arena = Arena::new();
+/// let index0 = arena.insert("Foo");
+/// let mut names = BTreeMap::new();
+/// names.insert(index0.slot_key(), "first");
+///
+/// arena.remove(index0);
+/// let index1 = arena.insert("Bar");
+/// assert_eq!(names.get(&index1.slot_key()), Some(&"first"));
+/// assert_eq!(SlotKey::from(index1).slot(), index0.slot());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SlotKey {
+    slot: usize,
+}
+
+impl SlotKey {
+    /// Key of the given slot.
+    pub fn new(slot: usize) -> Self {
+        Self { slot }
+    }
+
+    pub fn slot(self) -> usize {
+        self.slot
+    }
+}
+
+impl From<Index> for SlotKey {
+    fn from(index: Index) -> Self {
+        index.slot_key()
+    }
+}
+
+impl core::hash::Hash for SlotKey {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.slot as u64);
+    }
+}
+
+impl fmt::Debug for SlotKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SlotKey").field(&self.slot).finish()
+    }
+}
+
+impl Index {
+    /// The key of the index's slot, ignoring its generation and tag.
+    #[inline]
+    pub fn slot_key(self) -> SlotKey {
+        SlotKey::new(self.slot())
+    }
+
+    /// Compare the slots of two indices, ignoring their generations and tags.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # use core::cmp::Ordering;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// arena.remove(index0);
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// assert_eq!(index0.cmp_slot_only(&index1), Ordering::Equal);
+    /// assert_eq!(index0.cmp(&index1), Ordering::Less);
+    /// ```
+    #[inline]
+    pub fn cmp_slot_only(&self, other: &Index) -> Ordering {
+        self.slot().cmp(&other.slot())
+    }
+}
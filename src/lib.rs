@@ -1,30 +1,249 @@
 //! Generations use [`NonZeroUsize`] to reduce the size of `Option<Index>`.
-use std::iter::Iterator;
-use std::num::NonZeroUsize;
-use std::slice::{Iter as SliceIter, IterMut as SliceIterMut};
+//!
+//! The crate is `no_std` compatible when the default `std` feature is
+//! disabled, and only requires the `alloc` crate.
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::{Enumerate, FusedIterator, Iterator};
+use core::num::NonZeroUsize;
+use core::slice::{Iter as SliceIter, IterMut as SliceIterMut};
+
+use self::checks::Stamp;
+use self::secondary::LinkEvent;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+#[cfg(feature = "std")]
+mod binary;
+pub mod boxed;
+pub mod brand;
+mod budget;
+pub mod cache;
+mod cell;
+mod checks;
+mod compact;
+#[cfg(feature = "slab-compat")]
+pub mod compat;
+pub mod concurrent;
+mod convert;
+mod cursor;
+#[cfg(feature = "defmt")]
+mod defmt_impls;
+pub mod dense;
+mod diff;
+mod dump;
+mod entry;
+mod error;
+pub mod ffi;
+pub mod fixed;
+pub mod graph;
+mod hook;
+pub mod ids;
+mod join;
+mod journal;
+mod key;
+mod limit;
+pub mod list;
+mod merge;
+mod metrics;
+pub mod multi;
+mod overflow;
+pub mod packed;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod persistent;
+pub mod pin;
+pub mod rc;
+mod reader;
+mod recent;
+pub mod remap;
+mod reserve;
+mod reuse;
+mod revision;
+#[cfg(feature = "schemars")]
+mod schema;
+pub mod secondary;
+#[cfg(feature = "serde")]
+mod serde_impls;
+pub mod set;
+mod slots;
+pub mod small;
+mod snapshot;
+mod sort;
+mod spare;
+mod stats;
+mod status;
+mod strict;
+mod sync;
+pub mod typed;
+mod vacant;
+mod validate;
+pub mod view;
+pub mod watch;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "std")]
+pub use self::binary::{Decode, Encode};
+pub use self::boxed::BoxArena;
+pub use self::brand::{BrandedArena, BrandedIndex};
+pub use self::budget::{BudgetFn, Full, SizeFn};
+pub use self::cache::{CacheArena, EvictFn};
+pub use self::cell::{ArenaCell, BorrowError};
+pub use self::compact::MoveError;
+pub use self::concurrent::ConcurrentArena;
+pub use self::cursor::CursorMut;
+pub use self::dense::DenseArena;
+pub use self::diff::ArenaEdit;
+pub use self::dump::LayoutDump;
+pub use self::entry::{OccupiedEntry, SlotEntry, VacantEntry};
+pub use self::error::{AliasError, ArenaError};
+pub use self::ffi::CIndex;
+pub use self::fixed::FixedArena;
+pub use self::graph::ArenaGraph;
+pub use self::hook::RemoveFn;
+pub use self::ids::IdAllocator;
+pub use self::join::{join, Join, JoinSet, Joinable};
+pub use self::journal::ChangeKind;
+pub use self::key::SlotKey;
+pub use self::list::ArenaList;
+#[cfg(feature = "metrics")]
+pub use self::metrics::{ArenaEvent, MetricsFn};
+pub use self::multi::MultiArena;
+pub use self::overflow::GenerationOverflow;
+pub use self::packed::{CompactIndex, PackIndexError, PackedIndex};
+pub use self::persistent::PersistentArena;
+pub use self::pin::{ChunkedArena, PinArena};
+pub use self::rc::RcArena;
+pub use self::reader::ArenaReader;
+pub use self::recent::{Epoch, InsertedSince};
+pub use self::remap::IndexRemap;
+pub use self::reuse::ReusePolicy;
+pub use self::revision::ChangedSince;
+pub use self::secondary::SecondaryMap;
+#[cfg(feature = "serde")]
+pub use self::serde_impls::Compacted;
+pub use self::set::IndexSet;
+pub use self::slots::{IterSlots, SlotState};
+pub use self::small::SmallArena;
+pub use self::snapshot::Snapshot;
+pub use self::spare::SpareSlots;
+pub use self::stats::ArenaStats;
+pub use self::status::IndexStatus;
+pub use self::strict::{StaleAccess, StaleFn};
+pub use self::typed::{TypedArena, TypedIndex};
+pub use self::vacant::VacantSlots;
+pub use self::validate::ValidationError;
+pub use self::view::{ArenaView, ArenaViewMut, FilteredView};
+pub use self::watch::Access;
+
 /// Generation Arena.
-#[derive(Debug, Clone)]
+///
+/// Storage always comes from the global heap, as it does for the arenas
+/// built on this one. Only [`PinArena`] can take its storage from a custom
+/// allocator, see [`PinArena::new_in`].
 pub struct Arena<T> {
     data: Vec<Entry<T>>,
+    /// Generation of slots appended to storage. Raised past the generations
+    /// of truncated slots, so their indices can't resolve once they're reused.
     generation: NonZeroUsize,
     free_head: Option<usize>,
+    /// Last known tail of the free list, see [`ReusePolicy::Fifo`].
+    free_tail: Option<usize>,
     count: usize,
+    tag_checker: Option<fn(u8, &T) -> bool>,
+    watchpoints: watch::Watchpoints,
+    compaction: Option<compact::CompactionPolicy>,
+    links: secondary::Links,
+    revisions: Option<revision::Revisions>,
+    journal: Option<Vec<(Index, ChangeKind)>>,
+    recent: recent::Recent,
+    stale_handler: Option<strict::StaleFn>,
+    budget: Option<budget::Budget<T>>,
+    remove_hook: Option<hook::RemoveFn<T>>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<metrics::MetricsFn>,
+    limit: Option<usize>,
+    overflow: GenerationOverflow,
+    reuse: ReusePolicy,
+    #[cfg(feature = "debug-checks")]
+    id: u32,
 }
 
-#[derive(Debug, Clone)]
+/// A slot in the arena's storage.
+///
+/// Each slot keeps its own generation, which advances
+/// only when the slot's item is removed or replaced.
+#[derive(Debug)]
 pub enum Entry<T> {
-    Vacant { next: Option<usize> },
-    Occupied { generation: NonZeroUsize, item: T },
+    /// A free slot, holding the generation its next item will be given.
+    Vacant {
+        generation: NonZeroUsize,
+        next: Option<usize>,
+    },
+    /// A slot handed out by [`Arena::reserve_index`], waiting to be filled.
+    Reserved {
+        generation: NonZeroUsize,
+    },
+    Occupied {
+        generation: NonZeroUsize,
+        item: T,
+    },
+}
+
+impl<T: Clone> Clone for Entry<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Entry::Vacant { generation, next } => Entry::Vacant {
+                generation: *generation,
+                next: *next,
+            },
+            Entry::Reserved { generation } => Entry::Reserved {
+                generation: *generation,
+            },
+            Entry::Occupied { generation, item } => Entry::Occupied {
+                generation: *generation,
+                item: item.clone(),
+            },
+        }
+    }
+
+    /// Reuses the item's resources if both entries are occupied.
+    fn clone_from(&mut self, source: &Self) {
+        match (self, source) {
+            (
+                Entry::Occupied { generation, item },
+                Entry::Occupied {
+                    generation: source_generation,
+                    item: source_item,
+                },
+            ) => {
+                *generation = *source_generation;
+                item.clone_from(source_item);
+            }
+            (entry, source) => *entry = source.clone(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Handle to an item in an [`Arena`], made of its slot and the slot's
+/// generation when the item was inserted.
+///
+/// The high [`Index::TAG_BITS`] bits of the slot hold a user
+/// [tag](Index::tag), so an index stays two words wide.
+#[derive(Clone, Copy)]
 pub struct Index {
     generation: NonZeroUsize,
     slot: usize,
+    /// ID of the arena that handed out the index, or `0` if unknown.
+    /// Not part of the index's identity.
+    #[cfg(feature = "debug-checks")]
+    arena: u32,
 }
 
 impl<T> Arena<T> {
@@ -44,7 +263,206 @@ impl<T> Arena<T> {
             data: Vec::new(),
             generation: NonZeroUsize::new(1).unwrap(),
             free_head: None,
+            free_tail: None,
             count: 0,
+            tag_checker: None,
+            watchpoints: watch::Watchpoints::new(),
+            compaction: None,
+            links: secondary::Links::new(),
+            revisions: None,
+            journal: None,
+            recent: recent::Recent::default(),
+            stale_handler: None,
+            budget: None,
+            remove_hook: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            limit: None,
+            overflow: GenerationOverflow::Saturate,
+            reuse: ReusePolicy::Lifo,
+            #[cfg(feature = "debug-checks")]
+            id: checks::next_arena_id(),
+        }
+    }
+
+    /// Create an empty arena with room for at least `capacity` items
+    /// before reallocating.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let arena = Arena::<&str>::with_capacity(16);
+    /// assert!(arena.capacity() >= 16);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut arena = Self::new();
+        arena.data.reserve_exact(capacity);
+        arena
+    }
+
+    /// Create an arena holding `n` items in slots `0..n`, produced
+    /// by calling `f` with each slot number.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let arena = Arena::from_fn(4, |i| i * i);
+    /// # assert_eq!(arena.len(), 4);
+    /// # assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [0, 1, 4, 9]);
+    /// ```
+    pub fn from_fn<F>(n: usize, f: F) -> Self
+    where
+        F: FnMut(usize) -> T,
+    {
+        let mut arena = Self::new();
+        let generation = arena.generation;
+        arena.data = (0..n).map(f).map(|item| Entry::Occupied { generation, item }).collect();
+        arena.count = n;
+        arena
+    }
+
+    /// Like [`Arena::from_fn`], but also returns the index of each item,
+    /// in slot order.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let (arena, indices) = Arena::from_fn_with_indices(3, |i| i + 1);
+    /// assert_eq!(arena.get(indices[2]), Some(&3));
+    /// ```
+    pub fn from_fn_with_indices<F>(n: usize, f: F) -> (Self, Vec<Index>)
+    where
+        F: FnMut(usize) -> T,
+    {
+        let arena = Self::from_fn(n, f);
+        let indices = (0..n)
+            .map(|slot| arena.stamp(Index::new(slot, arena.generation)))
+            .collect();
+        (arena, indices)
+    }
+
+    /// Register a function that validates an index's user tag against
+    /// the item it points to.
+    ///
+    /// While a checker is registered, accessors treat an index whose
+    /// tag is rejected as if it was stale.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// const MESH: u8 = 1;
+    /// const TEXTURE: u8 = 2;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.set_tag_checker(|tag, item: &(u8, &str)| item.0 == tag);
+    ///
+    /// let index = arena.insert((MESH, "cube")).with_tag(MESH);
+    /// assert!(arena.get(index).is_some());
+    /// assert!(arena.get(index.with_tag(TEXTURE)).is_none());
+    /// ```
+    pub fn set_tag_checker(&mut self, checker: fn(u8, &T) -> bool) {
+        self.tag_checker = Some(checker);
+    }
+
+    /// Remove the registered tag checker, if any.
+    pub fn clear_tag_checker(&mut self) {
+        self.tag_checker = None;
+    }
+
+    /// Register a watchpoint on the slot referred to by `index`.
+    ///
+    /// The `callback` is invoked whenever [`Arena::get`], [`Arena::get_mut`],
+    /// [`Arena::remove`] or [`Arena::take`] is called with an index pointing
+    /// at the watched slot, regardless of the index's generation. Intended
+    /// as a debugging aid, for example to log or set a breakpoint when a
+    /// particular entity is touched.
+    ///
+    /// ```
+    /// # use arena::{Access, Arena, Index};
+    /// fn on_access(access: Access, index: Index) {
+    ///     println!("{:?} {:?}", access, index);
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// arena.watch(index, on_access);
+    ///
+    /// arena.get(index); // prints "Get Index { .. }"
+    /// # arena.unwatch(index);
+    /// ```
+    pub fn watch(&mut self, index: Index, callback: watch::WatchFn) {
+        self.watchpoints.insert(index.slot(), callback);
+    }
+
+    /// Remove all watchpoints on the slot referred to by `index`.
+    pub fn unwatch(&mut self, index: Index) {
+        self.watchpoints.remove(index.slot());
+    }
+
+    #[inline]
+    fn tag_matches(&self, index: Index, item: &T) -> bool {
+        match self.tag_checker {
+            Some(checker) => checker(index.tag(), item),
+            None => true,
+        }
+    }
+
+    /// Return the item if `index` points to an occupied slot with a
+    /// matching generation and tag.
+    ///
+    /// Unlike [`Arena::get`], this does not trigger watchpoints or strict mode.
+    #[inline]
+    fn lookup(&self, index: Index) -> Option<&T> {
+        self.check_owner(index);
+
+        match self.data.get(index.slot()) {
+            Some(Entry::Occupied { generation, item })
+                if index.generation == *generation && self.tag_matches(index, item) =>
+            {
+                Some(item)
+            }
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn is_live(&self, index: Index) -> bool {
+        self.lookup(index).is_some()
+    }
+
+    /// Remove a vacant `slot` from the free list.
+    ///
+    /// Returns `false` if the slot was not found in the free list.
+    fn unlink_free(&mut self, slot: usize) -> bool {
+        let mut prev: Option<usize> = None;
+        let mut cursor = self.free_head;
+
+        while let Some(current) = cursor {
+            let next = self.data[current].next_free();
+
+            if current == slot {
+                match prev {
+                    Some(prev) => self.data[prev].set_next_free(next),
+                    None => self.free_head = next,
+                }
+                return true;
+            }
+
+            prev = Some(current);
+            cursor = next;
+        }
+
+        false
+    }
+
+    /// Relink all vacant slots into the free list, lowest slot first.
+    fn rebuild_free_list(&mut self) {
+        self.free_head = None;
+
+        for slot in (0..self.data.len()).rev() {
+            if self.is_retired(&self.data[slot]) {
+                continue;
+            }
+            if let Entry::Vacant { next, .. } = &mut self.data[slot] {
+                *next = self.free_head;
+                self.free_head = Some(slot);
+            }
         }
     }
 
@@ -66,14 +484,7 @@ impl<T> Arena<T> {
     ///
     /// Panics if `index` is out of bounds.
     pub fn remove(&mut self, index: Index) {
-        if let Entry::Occupied { generation, .. } = &self.data[index.slot] {
-            if index.generation == *generation {
-                self.data[index.slot] = Entry::Vacant { next: self.free_head };
-                self.free_head = Some(index.slot);
-                self.generation = self.generation.saturating_add(1);
-                self.count -= 1;
-            }
-        }
+        self.take(index);
     }
 
     /// Remove and return the item at the given `index`.
@@ -92,26 +503,175 @@ impl<T> Arena<T> {
     ///
     /// Panics if `index` is out of bounds.
     pub fn take(&mut self, index: Index) -> Option<T> {
-        let entry = &mut self.data[index.slot];
-
-        if entry.is_occupied() {
-            let original = std::mem::replace(entry, Entry::Vacant { next: self.free_head });
-            self.free_head = Some(index.slot);
-            self.generation = self.generation.saturating_add(1);
-            self.count -= 1;
-            Some(original.unwrap_occupied().1)
-        } else {
-            None
+        let slot = index.slot();
+        self.watchpoints.notify(slot, Access::Remove, index);
+        assert!(slot < self.data.len(), "index out of bounds");
+
+        if !self.is_live(index) {
+            self.report_stale(Access::Remove, index);
+            return None;
+        }
+
+        Some(self.remove_entry(index))
+    }
+
+    /// Remove and return the item in the highest occupied slot,
+    /// together with its index.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// assert_eq!(arena.pop_last(), Some((index1, "Bar")));
+    /// assert_eq!(arena.pop_last(), Some((index0, "Foo")));
+    /// assert_eq!(arena.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<(Index, T)> {
+        let (index, _) = self.last()?;
+        let index = self.stamp(index);
+        self.take(index).map(|item| (index, item))
+    }
+
+    /// Remove the items at all the given indices, and return how many
+    /// were removed.
+    ///
+    /// Unlike [`Arena::remove`], stale, duplicate and out-of-bounds indices
+    /// are skipped, without reporting them to the
+    /// [stale handler](Arena::set_stale_handler).
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// let index2 = arena.insert("Baz");
+    /// arena.remove(index1);
+    ///
+    /// assert_eq!(arena.remove_all([index0, index1, index2, index0]), 2);
+    /// assert!(arena.is_empty());
+    /// ```
+    pub fn remove_all<I: IntoIterator<Item = Index>>(&mut self, indices: I) -> usize {
+        let mut removed = 0;
+        for index in indices {
+            self.watchpoints.notify(index.slot(), Access::Remove, index);
+            if self.is_live(index) {
+                self.remove_entry(index);
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    /// Vacate the occupied slot of a live `index` and return its item.
+    fn remove_entry(&mut self, index: Index) -> T {
+        let slot = index.slot();
+        let generation = self.next_generation(slot, index.generation);
+        let original = self.vacate(slot, generation);
+        self.count -= 1;
+        self.touch(slot);
+        self.links.notify(LinkEvent::Removed(index));
+        self.record_change(index, ChangeKind::Removed);
+
+        let item = original.unwrap_occupied().1;
+        self.account_removed(&item);
+        self.metric_removed(index);
+        self.notify_removed(index, &item);
+        item
+    }
+
+    /// Retain only the items for which the predicate returns `true`.
+    ///
+    /// Every other item is removed, as if by [`Arena::remove`]. Items are
+    /// visited in slot order.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(10);
+    /// let index1 = arena.insert(-5);
+    /// let index2 = arena.insert(0);
+    ///
+    /// arena.retain(|_, health| *health > 0);
+    /// assert_eq!(arena.len(), 1);
+    /// assert_eq!(arena.get(index0), Some(&10));
+    /// assert_eq!(arena.get(index1), None);
+    /// # assert_eq!(arena.get(index2), None);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Index, &T) -> bool,
+    {
+        self.retain_entries(false, |index, item| f(index, item));
+    }
+
+    /// Retain only the items for which the predicate returns `true`,
+    /// passing each item mutably.
+    ///
+    /// See [`Arena::retain`].
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(Index, &mut T) -> bool,
+    {
+        self.retain_entries(true, f);
+    }
+
+    /// Removes items one at a time, so the arena stays consistent
+    /// even if the predicate panics.
+    fn retain_entries<F>(&mut self, mutable: bool, mut f: F)
+    where
+        F: FnMut(Index, &mut T) -> bool,
+    {
+        let stamp = self.stamper();
+        for slot in 0..self.data.len() {
+            let (index, keep) = match &mut self.data[slot] {
+                Entry::Occupied { generation, item } => {
+                    let index = stamp.index(slot, *generation);
+                    (index, f(index, item))
+                }
+                Entry::Vacant { .. } | Entry::Reserved { .. } => continue,
+            };
+
+            if keep {
+                if mutable {
+                    self.touch(slot);
+                }
+            } else {
+                self.watchpoints.notify(slot, Access::Remove, index);
+                self.remove_entry(index);
+            }
         }
     }
 
     /// Appends the item to the end of the arena.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item would exceed the arena's [memory budget](Arena::set_memory_budget)
+    /// or [limit](Arena::with_limit).
     pub fn push(&mut self, item: T) -> Index {
+        self.assert_below_limit();
+        assert!(self.reserve_budget(&item, true), "arena memory budget exceeded");
+        self.push_entry(item)
+    }
+
+    fn push_entry(&mut self, item: T) -> Index {
         let generation = self.generation;
         let pos = self.data.len();
+        assert!(pos < Index::SLOT_MASK, "arena exceeded the maximum number of slots");
+        self.account_added(&item);
+        let capacity = self.data.capacity();
         self.data.push(Entry::Occupied { generation, item });
         self.count += 1;
-        Index { generation, slot: pos }
+        self.touch(pos);
+
+        let index = self.stamp(Index::new(pos, generation));
+        self.recent.record(index);
+        self.record_change(index, ChangeKind::Inserted);
+        self.metric_grew(capacity);
+        self.metric_inserted(index);
+        index
     }
 
     /// Insert the item into the first free slot.
@@ -126,15 +686,126 @@ impl<T> Arena<T> {
     /// # assert_ne!(index, index0);
     /// # assert_eq!(arena.len(), 2);
     /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item would exceed the arena's [memory budget](Arena::set_memory_budget)
+    /// or [limit](Arena::with_limit).
     pub fn insert(&mut self, item: T) -> Index {
-        match self.free_head.take() {
-            Some(pos) => {
-                let generation = self.generation;
-                self.data[pos] = Entry::Occupied { generation, item };
-                self.count += 1;
-                Index { generation, slot: pos }
-            }
-            None => self.push(item),
+        self.assert_below_limit();
+        assert!(
+            self.reserve_budget(&item, self.free_head.is_none()),
+            "arena memory budget exceeded"
+        );
+        self.insert_entry(item)
+    }
+
+    /// Insert every item of `iter`, and return their indices in order.
+    ///
+    /// Room for the iterator's lower size bound is reserved up front.
+    /// Like [`Arena::insert`], free slots are filled before new ones are
+    /// appended.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// arena.remove(index0);
+    ///
+    /// let indices = arena.insert_many(["Bar", "Baz"]);
+    /// assert_eq!(arena.get(indices[0]), Some(&"Bar"));
+    /// assert_eq!(arena.get(indices[1]), Some(&"Baz"));
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if an item would exceed the arena's [memory budget](Arena::set_memory_budget).
+    /// The items inserted before it stay in the arena.
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Vec<Index> {
+        let iter = iter.into_iter();
+        let additional = iter.size_hint().0;
+        self.reserve(additional);
+
+        let mut indices = Vec::with_capacity(additional);
+        indices.extend(iter.map(|item| self.insert(item)));
+        indices
+    }
+
+    fn insert_entry(&mut self, item: T) -> Index {
+        match self.free_head {
+            Some(pos) => self.occupy_vacant(pos, item),
+            None => self.push_entry(item),
+        }
+    }
+
+    /// Place the item in a vacant `slot`, removing it from the free list.
+    fn occupy_vacant(&mut self, slot: usize, item: T) -> Index {
+        self.unlink_free(slot);
+
+        let generation = self.data[slot].generation();
+        self.account_added(&item);
+        self.data[slot] = Entry::Occupied { generation, item };
+        self.count += 1;
+        self.touch(slot);
+
+        let index = self.stamp(Index::new(slot, generation));
+        self.recent.record(index);
+        self.record_change(index, ChangeKind::Inserted);
+        self.metric_inserted(index);
+        index
+    }
+
+    /// The index the next call to [`Arena::insert`] will return.
+    fn next_index(&self) -> Index {
+        match self.free_head {
+            Some(slot) => self.stamp(Index::new(slot, self.data[slot].generation())),
+            None => self.stamp(Index::new(self.data.len(), self.generation)),
+        }
+    }
+
+    /// Insert the item produced by `f`, which is given the index
+    /// the item will be stored at.
+    ///
+    /// Useful for items that need to know their own index.
+    ///
+    /// ```
+    /// # use arena::{Arena, Index};
+    /// struct Node {
+    ///     this: Index,
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// let index = arena.insert_with(|this| Node { this });
+    /// assert_eq!(arena.get(index).unwrap().this, index);
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if the item would exceed the arena's [memory budget](Arena::set_memory_budget)
+    /// or [limit](Arena::with_limit), or the budget handler fills the slot that was handed to `f`.
+    pub fn insert_with<F>(&mut self, f: F) -> Index
+    where
+        F: FnOnce(Index) -> T,
+    {
+        self.assert_below_limit();
+        let index = self.next_index();
+        let item = f(index);
+        assert!(
+            self.reserve_budget(&item, index.slot() == self.data.len()),
+            "arena memory budget exceeded"
+        );
+
+        let slot = index.slot();
+        let reserved = match self.data.get(slot) {
+            None => slot == self.data.len() && self.generation == index.generation,
+            Some(entry) => entry.is_vacant() && entry.generation() == index.generation,
+        };
+        assert!(reserved, "budget handler reused the slot handed to `insert_with`");
+
+        if slot == self.data.len() {
+            self.push_entry(item)
+        } else {
+            self.occupy_vacant(slot, item)
         }
     }
 
@@ -158,49 +829,92 @@ impl<T> Arena<T> {
     ///
     /// # Panic
     ///
-    /// Panics if `index` is out of bounds.
+    /// Panics if `index` is out of bounds, or its slot has run out of
+    /// generations, see [`GenerationOverflow`]. Also panics if the item
+    /// would exceed the arena's [memory budget](Arena::set_memory_budget),
+    /// counting only its growth over the item it replaces, or the budget
+    /// handler empties or fills the slot. Filling a free slot panics if
+    /// the arena is at its [limit](Arena::with_limit).
     pub fn replace(&mut self, index: Index, item: T) -> (Index, Option<T>) {
-        let entry = &mut self.data[index.slot];
+        let slot = index.slot();
+        let size = self.item_size(&item);
+        assert!(!self.is_retired(&self.data[slot]), "slot {} is retired", slot);
 
-        if entry.is_occupied() {
-            let generation = self.generation.saturating_add(1);
-            let original = std::mem::replace(entry, Entry::Occupied { generation, item });
-            self.generation = generation;
-            (
-                Index {
-                    generation,
-                    slot: index.slot,
-                },
-                Some(original.unwrap_occupied().1),
-            )
+        let occupied = self.data[slot].is_occupied();
+        if !occupied {
+            self.assert_below_limit();
+        }
+        let state = (occupied, self.data[slot].generation());
+        assert!(
+            self.reserve_budget_replacing(&item, slot),
+            "arena memory budget exceeded"
+        );
+        assert!(
+            state == (self.data[slot].is_occupied(), self.data[slot].generation()),
+            "budget handler changed the slot being replaced"
+        );
+
+        let (result, previous) = if occupied {
+            let generation = self.regenerate(slot, self.data[slot].generation());
+            let (previous, original) =
+                core::mem::replace(&mut self.data[slot], Entry::Occupied { generation, item }).unwrap_occupied();
+            let previous = self.stamp(Index::new(slot, previous));
+            self.links.notify(LinkEvent::Removed(previous));
+            self.account_removed(&original);
+            let index = self.stamp(Index::new(slot, generation));
+            self.record_change(index, ChangeKind::Replaced { previous });
+            ((index, Some(original)), Some(previous))
         } else {
-            let generation = self.generation;
-            *entry = Entry::Occupied { generation, item };
+            let generation = self.data[slot].generation();
+            self.unlink_free(slot);
+            self.data[slot] = Entry::Occupied { generation, item };
             self.count += 1;
-            (
-                Index {
-                    generation,
-                    slot: index.slot,
-                },
-                None,
-            )
+            let index = self.stamp(Index::new(slot, generation));
+            self.record_change(index, ChangeKind::Inserted);
+            ((index, None), None)
+        };
+
+        self.touch(slot);
+        self.recent.record(result.0);
+        self.add_item_bytes(size);
+        if let Some(previous) = previous {
+            self.metric_removed(previous);
+        }
+        self.metric_inserted(result.0);
+        if let (Some(previous), Some(original)) = (previous, &result.1) {
+            self.notify_removed(previous, original);
         }
+        result
     }
 
+    /// Set the item at the given `index`, dropping the existing item
+    /// if the slot is occupied.
+    ///
+    /// See [`Arena::replace`].
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
     pub fn set(&mut self, index: Index, item: T) {
-        let entry = &mut self.data[index.slot];
+        self.replace(index, item);
+    }
 
-        if entry.is_occupied() {
-            let generation = self.generation.saturating_add(1);
-            let _ = std::mem::replace(entry, Entry::Occupied { generation, item });
-            self.generation = generation;
-        } else {
-            *entry = Entry::Occupied {
-                generation: self.generation,
-                item,
-            };
-            self.count += 1;
-        }
+    /// Check whether `index` points to a live item.
+    ///
+    /// Out of bounds and stale indices return `false`. Unlike [`Arena::get`],
+    /// this does not trigger watchpoints or strict mode.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// assert!(arena.contains(index));
+    ///
+    /// arena.remove(index);
+    /// assert!(!arena.contains(index));
+    /// ```
+    pub fn contains(&self, index: Index) -> bool {
+        self.is_live(index)
     }
 
     /// Return a reference to the item at the given `index`.
@@ -215,13 +929,14 @@ impl<T> Arena<T> {
     /// # assert_eq!(object.position, [2.0, 3.0]);
     /// ```
     pub fn get(&self, index: Index) -> Option<&T> {
-        if let Some(Entry::Occupied { generation, item }) = self.data.get(index.slot) {
-            if index.generation == *generation {
-                return Some(item);
-            }
+        self.watchpoints.notify(index.slot(), Access::Get, index);
+
+        let item = self.lookup(index);
+        if item.is_none() {
+            self.report_stale(Access::Get, index);
         }
 
-        None
+        item
     }
 
     /// Return a mutable reference to the item at the given `index`.
@@ -239,49 +954,110 @@ impl<T> Arena<T> {
     /// # assert_eq!(arena.get_mut(index).unwrap().position, [7.0, 11.0])
     /// ```
     pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
-        if let Some(Entry::Occupied { generation, item }) = self.data.get_mut(index.slot) {
-            if index.generation == *generation {
-                return Some(item);
-            }
+        self.watchpoints.notify(index.slot(), Access::GetMut, index);
+
+        if !self.is_live(index) {
+            self.report_stale(Access::GetMut, index);
+            return None;
         }
 
-        None
+        self.touch(index.slot());
+        match &mut self.data[index.slot()] {
+            Entry::Occupied { item, .. } => Some(item),
+            _ => unreachable!(),
+        }
     }
 
-    /// # Panic
+    /// Return a reference to the item at the given `index`, without
+    /// checking its bounds or generation.
     ///
-    /// Panics if the two indices point to the same slot.
-    pub fn get2_mut(&mut self, a: Index, b: Index) -> (Option<&mut T>, Option<&mut T>) {
-        assert_ne!(a.slot, b.slot);
-
-        // SAFETY: The indices are checked so they don't return
-        //         mutable references to the same item.
-        //         The returned values' lifetimes are bound to the
-        //         Arena's lifetime via &self, so no further mutations
-        //         can occur on the internal data storage.
-        unsafe {
-            // erase lifetime
-            let this = self as *mut Self;
+    /// Watchpoints and strict mode are skipped too. For checked access
+    /// that avoids repeated validation, see [`Arena::scope`].
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// arena.insert(1);
+    /// arena.insert(2);
+    ///
+    /// let indices: Vec<_> = arena.keys().collect();
+    /// // SAFETY: The indices were just yielded by the arena, and nothing was removed since.
+    /// let sum: i32 = indices.iter().map(|&index| unsafe { arena.get_unchecked(index) }).sum();
+    /// assert_eq!(sum, 3);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `index` must point to an item in this arena, that is, it must be in
+    /// bounds, and its generation must match the item in the slot. Calling
+    /// this with any other index is undefined behavior, even if the result
+    /// is unused.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, index: Index) -> &T {
+        debug_assert!(self.is_live(index), "get_unchecked with invalid index {:?}", index);
 
-            ((*this).get_mut(a), (*this).get_mut(b))
+        match self.data.get_unchecked(index.slot()) {
+            Entry::Occupied { item, .. } => item,
+            _ => core::hint::unreachable_unchecked(),
         }
     }
 
-    pub fn len(&self) -> usize {
-        self.count
+    /// Return a mutable reference to the item at the given `index`,
+    /// without checking its bounds or generation.
+    ///
+    /// # Safety
+    ///
+    /// See [`Arena::get_unchecked`].
+    #[inline]
+    pub unsafe fn get_unchecked_mut(&mut self, index: Index) -> &mut T {
+        debug_assert!(self.is_live(index), "get_unchecked_mut with invalid index {:?}", index);
+
+        self.touch(index.slot());
+        match self.data.get_unchecked_mut(index.slot()) {
+            Entry::Occupied { item, .. } => item,
+            _ => core::hint::unreachable_unchecked(),
+        }
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.count == 0
+    /// Call `f` with a reference to the item at the given `index`,
+    /// returning its result.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// assert_eq!(arena.with(index, |item| item.len()), Some(3));
+    /// ```
+    pub fn with<R, F>(&self, index: Index, f: F) -> Option<R>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.get(index).map(f)
     }
 
-    pub fn iter(&self) -> Iter<T> {
-        Iter {
-            inner: self.data.iter(),
-        }
+    /// Call `f` with a mutable reference to the item at the given `index`,
+    /// returning its result.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert(vec![1, 2]);
+    /// let len = arena.with_mut(index, |item| {
+    ///     item.push(3);
+    ///     item.len()
+    /// });
+    /// assert_eq!(len, Some(3));
+    /// ```
+    pub fn with_mut<R, F>(&mut self, index: Index, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        self.get_mut(index).map(f)
     }
 
-    /// Iterate the items in the arena mutably.
+    /// Return references to the items at each of the given `indices`.
+    ///
+    /// Unlike the mutable accessors, the indices may refer to the same slot.
     ///
     /// ```
     /// # use arena::Arena;
@@ -289,73 +1065,1107 @@ impl<T> Arena<T> {
     /// let index0 = arena.insert("Foo");
     /// let index1 = arena.insert("Bar");
     ///
-    /// for item in arena.iter_mut() {
-    ///     *item = "Baz";
+    /// let [a, b, c] = arena.get_many([index0, index1, index0]);
+    /// # assert_eq!(a, Some(&"Foo"));
+    /// # assert_eq!(b, Some(&"Bar"));
+    /// # assert_eq!(c, Some(&"Foo"));
+    /// ```
+    pub fn get_many<const N: usize>(&self, indices: [Index; N]) -> [Option<&T>; N] {
+        indices.map(|index| self.get(index))
+    }
+
+    /// Return mutable references to the items at two indices.
+    ///
+    /// See [`Arena::get_disjoint_mut`].
+    ///
+    /// # Panic
+    ///
+    /// Panics if the two indices point to the same slot.
+    pub fn get2_mut(&mut self, a: Index, b: Index) -> (Option<&mut T>, Option<&mut T>) {
+        let [a, b] = self.get_disjoint_mut([a, b]);
+        (a, b)
+    }
+
+    /// Return mutable references to the items at two indices, or an
+    /// error if they point to the same slot.
+    ///
+    /// ```
+    /// # use arena::{AliasError, Arena};
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// assert!(arena.try_get2_mut(index0, index1).is_ok());
+    /// assert_eq!(arena.try_get2_mut(index0, index0), Err(AliasError { slot: 0 }));
+    /// ```
+    pub fn try_get2_mut(&mut self, a: Index, b: Index) -> Result<(Option<&mut T>, Option<&mut T>), AliasError> {
+        let [a, b] = self.try_get_disjoint_mut([a, b])?;
+        Ok((a, b))
+    }
+
+    /// Return mutable references to the items at each of the given `indices`.
+    ///
+    /// Stale indices resolve to `None`.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(1);
+    /// let index1 = arena.insert(2);
+    /// let index2 = arena.insert(3);
+    ///
+    /// if let [Some(a), Some(b), Some(c)] = arena.get_disjoint_mut([index2, index0, index1]) {
+    ///     *a += *b + *c;
+    /// }
+    /// # assert_eq!(arena.get(index2), Some(&6));
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if two of the indices point to the same slot.
+    pub fn get_disjoint_mut<const N: usize>(&mut self, indices: [Index; N]) -> [Option<&mut T>; N] {
+        self.try_get_disjoint_mut(indices)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Exchange the items at `a` and `b`.
+    ///
+    /// Both indices stay valid, and resolve to each other's item afterwards.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let back = arena.insert("Foo");
+    /// let front = arena.insert("Bar");
+    ///
+    /// arena.swap(back, front);
+    /// assert_eq!(arena.get(back), Some(&"Bar"));
+    /// assert_eq!(arena.get(front), Some(&"Foo"));
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if either index doesn't resolve. See [`Arena::try_swap`].
+    pub fn swap(&mut self, a: Index, b: Index) {
+        self.try_swap(a, b).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Return mutable references to the items at each of the given
+    /// `indices`, or an error if two of them point to the same slot.
+    ///
+    /// See [`Arena::get_disjoint_mut`].
+    pub fn try_get_disjoint_mut<const N: usize>(
+        &mut self,
+        indices: [Index; N],
+    ) -> Result<[Option<&mut T>; N], AliasError> {
+        let mut order: [(usize, usize); N] = core::array::from_fn(|pos| (indices[pos].slot(), pos));
+        order.sort_unstable();
+
+        let mut items = core::array::from_fn(|_| None);
+        self.resolve_sorted_mut(&order, |pos| indices[pos], true, &mut items)?;
+        Ok(items)
+    }
+
+    /// Resolve a sequence of optional indices, yielding the item
+    /// for each live index and `None` otherwise.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index1);
+    ///
+    /// let children = [Some(index0), None, Some(index1)];
+    /// let items: Vec<_> = arena.resolve_many(children).collect();
+    /// assert_eq!(items, [Some(&"Foo"), None, None]);
+    /// ```
+    pub fn resolve_many<I>(&self, iter: I) -> ResolveMany<'_, T, I::IntoIter>
+    where
+        I: IntoIterator<Item = Option<Index>>,
+    {
+        ResolveMany {
+            arena: self,
+            inner: iter.into_iter(),
+        }
+    }
+
+    /// Mutably resolve a sequence of optional indices, returning the item
+    /// for each live index and `None` otherwise.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(1);
+    /// let index1 = arena.insert(2);
+    ///
+    /// for item in arena.resolve_many_mut([Some(index1), None, Some(index0)]).into_iter().flatten() {
+    ///     *item *= 10;
+    /// }
+    /// # assert_eq!(arena.get(index0), Some(&10));
+    /// # assert_eq!(arena.get(index1), Some(&20));
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if two of the indices point to the same slot.
+    pub fn resolve_many_mut<I>(&mut self, iter: I) -> Vec<Option<&mut T>>
+    where
+        I: IntoIterator<Item = Option<Index>>,
+    {
+        let indices: Vec<Option<Index>> = iter.into_iter().collect();
+
+        // Visit the slots in ascending order, so the entries can be
+        // handed out in a single pass over the storage.
+        let mut order: Vec<(usize, usize)> = indices
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, index)| index.map(|index| (index.slot(), pos)))
+            .collect();
+        order.sort_unstable();
+
+        let mut items: Vec<Option<&mut T>> = indices.iter().map(|_| None).collect();
+        if let Err(err) = self.resolve_sorted_mut(&order, |pos| indices[pos].unwrap(), false, &mut items) {
+            panic!("{}", err);
+        }
+        items
+    }
+
+    /// Resolve the indices at the positions in `order` into `items`,
+    /// in a single pass over the storage.
+    ///
+    /// `order` holds `(slot, position)` pairs sorted by slot.
+    fn resolve_sorted_mut<'a, F>(
+        &'a mut self,
+        order: &[(usize, usize)],
+        index_at: F,
+        strict: bool,
+        items: &mut [Option<&'a mut T>],
+    ) -> Result<(), AliasError>
+    where
+        F: Fn(usize) -> Index,
+    {
+        if let Some(pair) = order.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(AliasError { slot: pair[0].0 });
+        }
+
+        for &(_, pos) in order {
+            let index = index_at(pos);
+            self.watchpoints.notify(index.slot(), Access::GetMut, index);
+            if self.is_live(index) {
+                self.touch(index.slot());
+            } else if strict {
+                self.report_stale(Access::GetMut, index);
+            }
+        }
+
+        let checker = self.tag_checker;
+        let mut entries = self.data.iter_mut().enumerate();
+
+        for &(slot, pos) in order {
+            let index = index_at(pos);
+            let entry = entries.by_ref().find(|(other, _)| *other == slot);
+
+            match entry {
+                Some((_, Entry::Occupied { generation, item })) => {
+                    if index.generation == *generation && checker.is_none_or(|check| check(index.tag(), item)) {
+                        items[pos] = Some(item);
+                    }
+                }
+                Some((_, Entry::Vacant { .. } | Entry::Reserved { .. })) => {}
+                // Out of bounds, and so is every slot after it.
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Shrink the capacity of the backing storage with a lower bound.
+    ///
+    /// The capacity remains at least as large as both the number of slots
+    /// and `min_capacity`. Occupied slots are never moved, so all indices
+    /// remain valid. Mirrors [`Vec::shrink_to`].
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.data.shrink_to(min_capacity);
+    }
+
+    /// Drop the vacant slots at the end of storage, and shrink the
+    /// capacity of the backing storage as much as possible.
+    ///
+    /// Occupied slots are never moved, so all indices remain valid. Call
+    /// [`Arena::compact`] first to also drop the vacant slots in between.
+    /// See [`Arena::truncate_vacant_tail`] and [`Vec::shrink_to_fit`].
+    pub fn shrink_to_fit(&mut self) {
+        self.truncate_vacant_tail();
+        self.data.shrink_to_fit();
+    }
+
+    /// Number of slots the arena can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Reserve capacity so at least `additional` more items can be
+    /// inserted without reallocating.
+    ///
+    /// Vacant slots count towards the room available. Like [`Vec::reserve`],
+    /// more may be reserved to amortize future growth.
+    ///
+    /// Reserved capacity counts towards the arena's
+    /// [memory usage](Arena::memory_usage), even if it is never filled.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// arena.insert("Foo");
+    /// arena.reserve(10);
+    /// assert!(arena.capacity() >= 11);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let appends = self.appends_needed(additional);
+        let capacity = self.data.capacity();
+        self.data.reserve(appends);
+        self.metric_grew(capacity);
+    }
+
+    /// Reserve the minimum capacity so at least `additional` more items
+    /// can be inserted without reallocating.
+    ///
+    /// See [`Arena::reserve`] and [`Vec::reserve_exact`].
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let appends = self.appends_needed(additional);
+        let capacity = self.data.capacity();
+        self.data.reserve_exact(appends);
+        self.metric_grew(capacity);
+    }
+
+    /// Number of slots that must be appended to fit `additional` items,
+    /// after filling the vacant ones.
+    #[inline]
+    fn appends_needed(&self, additional: usize) -> usize {
+        additional.saturating_sub(self.data.len() - self.count)
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Iterate the items in the arena, skipping vacant slots.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    ///
+    /// let items: Vec<_> = arena.iter().collect();
+    /// assert_eq!(items, [&"Bar"]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.data.iter(),
+            remaining: self.count,
+        }
+    }
+
+    /// Iterate the items in the arena together with their indices.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// let items: Vec<_> = arena.iter_indexed().collect();
+    /// assert_eq!(items, [(index0, &"Foo"), (index1, &"Bar")]);
+    /// ```
+    pub fn iter_indexed(&self) -> IterIndexed<'_, T> {
+        IterIndexed {
+            inner: self.data.iter().enumerate(),
+            remaining: self.count,
+            stamp: self.stamper(),
+        }
+    }
+
+    /// Iterate the indices of the items in the arena.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// let indices: Vec<_> = arena.keys().rev().collect();
+    /// assert_eq!(indices, [index1, index0]);
+    /// ```
+    pub fn keys(&self) -> Keys<'_, T> {
+        Keys {
+            inner: self.iter_indexed(),
+        }
+    }
+
+    /// Iterate the items in the arena. The same as [`Arena::iter`].
+    pub fn values(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    /// Mutably iterate the items in the arena. The same as [`Arena::iter_mut`].
+    pub fn values_mut(&mut self) -> IterMut<'_, T> {
+        self.iter_mut()
+    }
+
+    /// Mutably iterate the items in the arena together with their indices.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(None);
+    /// let index1 = arena.insert(None);
+    ///
+    /// for (index, item) in arena.iter_indexed_mut() {
+    ///     *item = Some(index);
+    /// }
+    /// # assert_eq!(arena.get(index1), Some(&Some(index1)));
+    /// ```
+    pub fn iter_indexed_mut(&mut self) -> IterIndexedMut<'_, T> {
+        self.touch_occupied();
+
+        let stamp = self.stamper();
+        IterIndexedMut {
+            inner: self.data.iter_mut().enumerate(),
+            remaining: self.count,
+            stamp,
+        }
+    }
+
+    /// Collect the indices of all live items.
+    ///
+    /// The snapshot doesn't borrow the arena, so items can be freely
+    /// inserted and removed while walking it. Indices removed in the
+    /// meantime simply fail to resolve.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// arena.insert(1);
+    /// arena.insert(-2);
+    ///
+    /// for index in arena.keys_snapshot() {
+    ///     if arena.get(index).is_some_and(|item| *item < 0) {
+    ///         arena.remove(index);
+    ///         arena.insert(0);
+    ///     }
+    /// }
+    /// # assert_eq!(arena.len(), 2);
+    /// ```
+    pub fn keys_snapshot(&self) -> Vec<Index> {
+        let mut keys = Vec::with_capacity(self.count);
+
+        for (slot, entry) in self.data.iter().enumerate() {
+            if let Entry::Occupied { generation, .. } = entry {
+                keys.push(self.stamp(Index::new(slot, *generation)));
+            }
+        }
+
+        keys
+    }
+
+    /// Index of the first item, in slot order, for which the predicate
+    /// returns `true`.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// arena.insert("Foo");
+    /// let index = arena.insert("Bar");
+    ///
+    /// assert_eq!(arena.find(|item| item.starts_with('B')), Some(index));
+    /// assert_eq!(arena.find(|item| item.is_empty()), None);
+    /// ```
+    pub fn find<F>(&self, mut f: F) -> Option<Index>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter_indexed().find(|(_, item)| f(item)).map(|(index, _)| index)
+    }
+
+    /// Apply `f` to the items in slot order, and return the first
+    /// result that isn't `None`.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// arena.insert("Foo");
+    /// let index = arena.insert("42");
+    ///
+    /// let found = arena.find_map(|index, item| item.parse::<i32>().ok().map(|n| (index, n)));
+    /// assert_eq!(found, Some((index, 42)));
+    /// ```
+    pub fn find_map<R, F>(&self, mut f: F) -> Option<R>
+    where
+        F: FnMut(Index, &T) -> Option<R>,
+    {
+        self.iter_indexed().find_map(|(index, item)| f(index, item))
+    }
+
+    /// The item in the lowest occupied slot, together with its index.
+    pub fn first(&self) -> Option<(Index, &T)> {
+        self.iter_indexed().next()
+    }
+
+    /// The item in the highest occupied slot, together with its index.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index1);
+    ///
+    /// assert_eq!(arena.first(), Some((index0, &"Foo")));
+    /// assert_eq!(arena.last(), Some((index0, &"Foo")));
+    /// ```
+    pub fn last(&self) -> Option<(Index, &T)> {
+        self.iter_indexed().next_back()
+    }
+
+    /// Iterate the items in the arena mutably.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// for item in arena.iter_mut() {
+    ///     *item = "Baz";
     /// }
     /// # assert_eq!(arena.get(index0), Some(&"Baz"));
     /// # assert_eq!(arena.get(index1), Some(&"Baz"));
     /// ```
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.touch_occupied();
+
         IterMut {
             inner: self.data.iter_mut(),
+            remaining: self.count,
+        }
+    }
+
+    /// Remove all items from the arena, keeping its capacity.
+    ///
+    /// Every previously issued index becomes stale, pending reservations
+    /// are released, and vacated slots are reused from the front.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// arena.clear();
+    ///
+    /// assert!(arena.is_empty());
+    /// assert_eq!(arena.get(index0), None);
+    ///
+    /// let index1 = arena.insert("Bar");
+    /// assert_eq!(arena.get(index0), None);
+    /// # assert_eq!(arena.get(index1), Some(&"Bar"));
+    /// ```
+    pub fn clear(&mut self) {
+        for slot in 0..self.data.len() {
+            if let Entry::Reserved { generation } = self.data[slot] {
+                self.data[slot] = Entry::Vacant {
+                    generation: self.next_generation(slot, generation),
+                    next: None,
+                };
+            }
+        }
+
+        self.drain().for_each(drop);
+    }
+
+    /// Remove all items from the arena, yielding them with their indices.
+    ///
+    /// The arena keeps its capacity, and all existing indices become stale.
+    /// Items that are not consumed are dropped when the iterator is.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// let items: Vec<_> = arena.drain().collect();
+    /// assert_eq!(items, [(index0, "Foo"), (index1, "Bar")]);
+    /// assert!(arena.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain { arena: self, slot: 0 }
+    }
+
+    /// Create an iterator that removes and yields the items for which
+    /// the predicate returns `true`, in slot order.
+    ///
+    /// Items are only removed as the iterator is advanced. If it is dropped
+    /// early, the remaining items are kept, whether they match or not.
+    /// Like [`Arena::retain_mut`], the predicate may mutate the items it keeps.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(3);
+    /// let index1 = arena.insert(-1);
+    /// let index2 = arena.insert(-4);
+    ///
+    /// let dead: Vec<_> = arena.extract_if(|_, health| *health <= 0).collect();
+    /// assert_eq!(dead, [(index1, -1), (index2, -4)]);
+    /// assert_eq!(arena.get(index0), Some(&3));
+    /// # assert_eq!(arena.len(), 1);
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(Index, &mut T) -> bool,
+    {
+        ExtractIf {
+            arena: self,
+            slot: 0,
+            pred,
+        }
+    }
+
+    /// Iterate the items in the arena, continuing from where the
+    /// `cursor` was left by a previous call.
+    ///
+    /// The cursor is advanced past each yielded item, so iteration can be
+    /// abandoned at any point and resumed later. Once the end of the arena
+    /// is reached the cursor wraps back to the start, and the next call
+    /// begins a new sweep.
+    ///
+    /// Items inserted in slots behind the cursor are visited on the next
+    /// sweep. Items removed before the cursor reaches them are skipped.
+    ///
+    /// ```
+    /// # use arena::{Arena, Cursor};
+    /// # let mut arena = Arena::new();
+    /// arena.insert("Foo");
+    /// arena.insert("Bar");
+    /// arena.insert("Baz");
+    ///
+    /// let mut cursor = Cursor::new();
+    ///
+    /// // Process a budget of two items per frame.
+    /// let first: Vec<_> = arena.iter_resumable(&mut cursor).take(2).map(|(_, item)| *item).collect();
+    /// let second: Vec<_> = arena.iter_resumable(&mut cursor).take(2).map(|(_, item)| *item).collect();
+    /// # assert_eq!(first, ["Foo", "Bar"]);
+    /// # assert_eq!(second, ["Baz"]);
+    /// ```
+    pub fn iter_resumable<'a, 'c>(&'a self, cursor: &'c mut Cursor) -> Resumable<'a, 'c, T> {
+        Resumable {
+            data: &self.data,
+            cursor,
+            stamp: self.stamper(),
         }
     }
+
+    /// Create a read-only view restricted to the indices in `selection`.
+    ///
+    /// ```
+    /// # use arena::{Arena, IndexSet};
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// let mut selection = IndexSet::new();
+    /// selection.insert(index1);
+    ///
+    /// let view = arena.view_of(&selection);
+    /// assert_eq!(view.get(index0), None);
+    /// assert_eq!(view.get(index1), Some(&"Bar"));
+    /// # assert_eq!(view.len(), 1);
+    /// ```
+    pub fn view_of<'a>(&'a self, selection: &'a IndexSet) -> ArenaView<'a, T> {
+        ArenaView::new(self, selection)
+    }
+
+    /// Mutably iterate the items in the arena, continuing from where the
+    /// `cursor` was left by a previous call.
+    ///
+    /// See [`Arena::iter_resumable`] for the cursor semantics.
+    pub fn iter_resumable_mut<'a, 'c>(&'a mut self, cursor: &'c mut Cursor) -> ResumableMut<'a, 'c, T> {
+        let start = cursor.slot.min(self.data.len());
+        let stamp = self.stamper();
+        ResumableMut {
+            data: &mut self.data[start..],
+            revisions: self.revisions.as_mut(),
+            cursor,
+            stamp,
+        }
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena::new()
+    }
+}
+
+impl<T: Clone> Clone for Arena<T> {
+    fn clone(&self) -> Self {
+        let mut arena = Arena::new();
+        arena.clone_from(self);
+        arena
+    }
+
+    /// Reuses the storage of this arena, and the resources of items in
+    /// slots occupied in both arenas, such as the buffers of strings.
+    ///
+    /// See [`Arena::copy_from`] for copying the items but keeping this
+    /// arena's settings.
+    fn clone_from(&mut self, source: &Self) {
+        let Arena {
+            data,
+            generation,
+            free_head,
+            free_tail,
+            count,
+            tag_checker,
+            watchpoints,
+            compaction,
+            links,
+            revisions,
+            journal,
+            recent,
+            stale_handler,
+            budget,
+            remove_hook,
+            #[cfg(feature = "metrics")]
+            metrics,
+            limit,
+            overflow,
+            reuse,
+            #[cfg(feature = "debug-checks")]
+            id,
+        } = source;
+
+        self.data.clone_from(data);
+        self.generation = *generation;
+        self.free_head = *free_head;
+        self.free_tail = *free_tail;
+        self.count = *count;
+        self.tag_checker = *tag_checker;
+        self.watchpoints.clone_from(watchpoints);
+        self.compaction = *compaction;
+        self.links.clone_from(links);
+        self.revisions.clone_from(revisions);
+        self.journal.clone_from(journal);
+        self.recent.clone_from(recent);
+        self.stale_handler = *stale_handler;
+        self.budget.clone_from(budget);
+        self.remove_hook = *remove_hook;
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics = *metrics;
+        }
+        self.limit = *limit;
+        self.overflow = *overflow;
+        self.reuse = *reuse;
+        #[cfg(feature = "debug-checks")]
+        {
+            self.id = *id;
+        }
+    }
+}
+
+impl<T: Clone> Arena<T> {
+    /// Replace the items and slot layout with copies of those in `source`,
+    /// keeping this arena's settings, callbacks and tracking state.
+    ///
+    /// Storage is reused, and items in slots occupied in both arenas are
+    /// updated with [`Clone::clone_from`], so copying state every frame
+    /// doesn't reallocate once capacities settle.
+    ///
+    /// Indices resolve as they do in `source` afterwards, as if the arena
+    /// were assigned a clone of it. Linked [secondary maps](crate::SecondaryMap),
+    /// the [journal](Arena::track_changes) and the [remove hook](Arena::set_remove_hook)
+    /// aren't told about the items replaced, see [`Arena::restore`] for
+    /// that. With [revision tracking](Arena::track_revisions) every copied
+    /// item counts as changed.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let mut confirmed = Arena::new();
+    /// let index = confirmed.insert(String::from("Foo"));
+    ///
+    /// let mut predicted = Arena::new();
+    /// predicted.copy_from(&confirmed);
+    /// predicted[index].push_str("Bar");
+    ///
+    /// predicted.copy_from(&confirmed);
+    /// assert_eq!(predicted[index], "Foo");
+    /// ```
+    pub fn copy_from(&mut self, source: &Arena<T>) {
+        self.inherit_id(source);
+        self.data.clone_from(&source.data);
+        self.generation = source.generation;
+        self.free_head = source.free_head;
+        self.free_tail = source.free_tail;
+        self.count = source.count;
+
+        self.recount_item_bytes();
+        for slot in 0..self.data.len() {
+            if self.data[slot].is_occupied() {
+                self.recent.stamp(slot);
+            }
+        }
+        self.touch_occupied();
+    }
+}
+
+impl<T> core::ops::Index<Index> for Arena<T> {
+    type Output = T;
+
+    /// Return a reference to the item at `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is stale or out of bounds. Use [`Arena::get`]
+    /// for a non-panicking alternative.
+    fn index(&self, index: Index) -> &T {
+        self.get(index).expect("index is stale or out of bounds")
+    }
+}
+
+impl<T> core::ops::IndexMut<Index> for Arena<T> {
+    /// Return a mutable reference to the item at `index`.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// arena[index] = "Bar";
+    /// assert_eq!(arena[index], "Bar");
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is stale or out of bounds. Use [`Arena::get_mut`]
+    /// for a non-panicking alternative.
+    fn index_mut(&mut self, index: Index) -> &mut T {
+        self.get_mut(index).expect("index is stale or out of bounds")
+    }
+}
+
+impl<T> IntoIterator for Arena<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consume the arena, yielding its items in slot order.
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            remaining: self.count,
+            inner: self.data.into_iter(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Arena<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Arena<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Entry<T> {
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn is_occupied(&self) -> bool {
+        matches!(self, Entry::Occupied { .. })
+    }
+
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn is_vacant(&self) -> bool {
+        matches!(self, Entry::Vacant { .. })
+    }
+
+    /// Generation of the item in the slot, or of the next item if vacant.
+    #[inline(always)]
+    fn generation(&self) -> NonZeroUsize {
+        match self {
+            Entry::Vacant { generation, .. } | Entry::Reserved { generation } | Entry::Occupied { generation, .. } => {
+                *generation
+            }
+        }
+    }
+
+    #[inline]
+    fn next_free(&self) -> Option<usize> {
+        match self {
+            Entry::Vacant { next, .. } => *next,
+            _ => unreachable!("free list points to an occupied slot"),
+        }
+    }
+
+    #[inline]
+    fn set_next_free(&mut self, slot: Option<usize>) {
+        match self {
+            Entry::Vacant { next, .. } => *next = slot,
+            _ => unreachable!("free list points to an occupied slot"),
+        }
+    }
+
+    fn unwrap_occupied(self) -> (NonZeroUsize, T) {
+        if let Entry::Occupied { generation, item } = self {
+            (generation, item)
+        } else {
+            panic!("called `Entry::unwrap_occupied()` on a `Vacant` value")
+        }
+    }
+}
+
+impl Index {
+    /// Number of high bits of the slot reserved for the user tag.
+    ///
+    /// The tag shares its word with the slot, which keeps the index two
+    /// words wide at the cost of the slot range: an arena holds at most
+    /// 2<sup>24</sup> − 1 slots on 32-bit targets, and 2<sup>56</sup> − 1
+    /// on 64-bit ones. Growing past that panics, like running out of
+    /// memory would. The bits are reserved whether or not tags are used.
+    pub const TAG_BITS: u32 = 8;
+
+    const TAG_SHIFT: u32 = usize::BITS - Self::TAG_BITS;
+    /// Highest slot an index can address, which is kept for [`Index::DANGLING`].
+    const SLOT_MASK: usize = usize::MAX >> Self::TAG_BITS;
+
+    /// Placeholder index that never resolves, in any arena.
+    ///
+    /// Arenas never grow to its slot, so it's safe to use for initializing
+    /// handles before the real items exist. It's also the [default](Default)
+    /// index. Like any index beyond the arena's storage, it makes
+    /// [`Arena::remove`] panic.
+    ///
+    /// ```
+    /// # use arena::{Arena, Index};
+    /// #[derive(Default)]
+    /// struct Sprite {
+    ///     texture: Index,
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// let mut sprite = Sprite::default();
+    /// assert!(sprite.texture.is_dangling());
+    /// assert_eq!(arena.get(sprite.texture), None);
+    ///
+    /// sprite.texture = arena.insert("Foo");
+    /// assert!(!sprite.texture.is_dangling());
+    /// ```
+    pub const DANGLING: Index = Index {
+        generation: NonZeroUsize::MAX,
+        slot: Self::SLOT_MASK,
+        #[cfg(feature = "debug-checks")]
+        arena: 0,
+    };
+
+    #[inline(always)]
+    fn new(slot: usize, generation: NonZeroUsize) -> Self {
+        Index {
+            generation,
+            slot,
+            #[cfg(feature = "debug-checks")]
+            arena: 0,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn from_parts(slot: usize, gen: usize) -> Self {
+        Index::new(slot, NonZeroUsize::new(gen).unwrap())
+    }
+
+    /// The user tag embedded in the index.
+    ///
+    /// Indices handed out by the arena start with a tag of `0`.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// assert_eq!(index.tag(), 0);
+    /// assert_eq!(index.with_tag(3).tag(), 3);
+    /// ```
+    #[inline]
+    pub fn tag(&self) -> u8 {
+        (self.slot >> Self::TAG_SHIFT) as u8
+    }
+
+    /// Return a copy of this index carrying the given user tag.
+    ///
+    /// The tag does not change which slot the index refers to.
+    #[inline]
+    pub fn with_tag(self, tag: u8) -> Self {
+        Index {
+            slot: self.slot() | ((tag as usize) << Self::TAG_SHIFT),
+            ..self
+        }
+    }
+
+    /// Position of the entry in the arena's storage, without the tag bits.
+    #[inline(always)]
+    pub fn slot(&self) -> usize {
+        self.slot & Self::SLOT_MASK
+    }
+
+    /// Generation of the slot when the index was handed out.
+    #[inline]
+    pub fn generation(&self) -> usize {
+        self.generation.get()
+    }
+
+    /// Check whether the index points to the slot of [`Index::DANGLING`],
+    /// whatever its generation and tag.
+    #[inline]
+    pub fn is_dangling(&self) -> bool {
+        self.slot() == Self::SLOT_MASK
+    }
+
+    /// Pack the index into an integer, with the generation in the high
+    /// 32 bits and the slot in the low 32 bits, the layout of a
+    /// [`CompactIndex`].
+    ///
+    /// Returns `None` if the slot or generation don't fit in 32 bits,
+    /// or the index carries a tag.
+    ///
+    /// ```
+    /// # use arena::{Arena, Index};
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// let bits = index.to_bits().unwrap();
+    /// assert_eq!(bits, 1 << 32);
+    /// assert_eq!(Index::from_bits(bits), Some(index));
+    /// ```
+    pub fn to_bits(self) -> Option<u64> {
+        CompactIndex::try_from(self).ok().map(CompactIndex::to_bits)
+    }
+
+    /// Unpack an index packed by [`Index::to_bits`].
+    ///
+    /// Returns `None` if the generation is zero.
+    pub fn from_bits(bits: u64) -> Option<Index> {
+        CompactIndex::from_bits(bits).map(Index::from)
+    }
 }
 
-impl<T> Default for Arena<T> {
+impl Default for Index {
+    /// The placeholder [`Index::DANGLING`].
     fn default() -> Self {
-        Arena::new()
+        Index::DANGLING
     }
 }
 
-impl<T> Entry<T> {
-    #[inline(always)]
-    #[allow(dead_code)]
-    fn is_occupied(&self) -> bool {
-        matches!(self, Entry::Occupied { .. })
+impl fmt::Debug for Index {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Index")
+            .field("slot", &self.slot())
+            .field("generation", &self.generation)
+            .field("tag", &self.tag())
+            .finish()
     }
+}
 
-    #[inline(always)]
-    #[allow(dead_code)]
-    fn is_vacant(&self) -> bool {
-        matches!(self, Entry::Vacant { .. })
+impl PartialEq for Index {
+    fn eq(&self, other: &Self) -> bool {
+        (self.generation, self.slot) == (other.generation, other.slot)
+    }
+}
+
+impl Eq for Index {}
+
+impl PartialOrd for Index {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    fn unwrap_occupied(self) -> (NonZeroUsize, T) {
-        if let Entry::Occupied { generation, item } = self {
-            (generation, item)
-        } else {
-            panic!("called `Entry::unwrap_occupied()` on a `Vacant` value")
-        }
+impl Ord for Index {
+    /// Indices are ordered by slot, then generation, then tag, so sorted
+    /// indices follow the arena's storage. See [`Index::cmp_slot_only`]
+    /// for ignoring the generation.
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (self.slot(), self.generation, self.tag()).cmp(&(other.slot(), other.generation, other.tag()))
     }
 }
 
-#[allow(dead_code)]
-impl Index {
-    pub(crate) fn from_parts(slot: usize, gen: usize) -> Self {
-        Index {
-            slot,
-            generation: NonZeroUsize::new(gen).unwrap(),
-        }
+impl core::hash::Hash for Index {
+    /// Hashes the slot and generation as `u64`, then the tag as `u8`, so
+    /// the hash of a saved index doesn't depend on the platform's pointer
+    /// width, given a hasher that is stable itself.
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.slot() as u64);
+        state.write_u64(self.generation() as u64);
+        state.write_u8(self.tag());
     }
 }
 
 // ----------------------------------------------------------------------------
 // Iterators
 
+// The item iterators know how many occupied slots are left, so they
+// report exact sizes, and stop scanning once every item was yielded.
+
 #[derive(Debug)]
 pub struct Iter<'a, T> {
     inner: SliceIter<'a, Entry<T>>,
+    remaining: usize,
 }
 
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
         for entry in self.inner.by_ref() {
-            match entry {
-                Entry::Vacant { .. } => continue,
-                Entry::Occupied { item, .. } => return Some(item),
+            if let Entry::Occupied { item, .. } = entry {
+                self.remaining -= 1;
+                return Some(item);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        while let Some(entry) = self.inner.next_back() {
+            if let Entry::Occupied { item, .. } = entry {
+                self.remaining -= 1;
+                return Some(item);
             }
         }
 
@@ -363,22 +2173,431 @@ impl<'a, T> Iterator for Iter<'a, T> {
     }
 }
 
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
 #[derive(Debug)]
 pub struct IterMut<'a, T> {
     inner: SliceIterMut<'a, Entry<T>>,
+    remaining: usize,
 }
 
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
         for entry in self.inner.by_ref() {
-            match entry {
-                Entry::Vacant { .. } => continue,
-                Entry::Occupied { item, .. } => return Some(item),
+            if let Entry::Occupied { item, .. } = entry {
+                self.remaining -= 1;
+                return Some(item);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        while let Some(entry) = self.inner.next_back() {
+            if let Entry::Occupied { item, .. } = entry {
+                self.remaining -= 1;
+                return Some(item);
+            }
+        }
+
+        None
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    inner: alloc::vec::IntoIter<Entry<T>>,
+    remaining: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        for entry in self.inner.by_ref() {
+            if let Entry::Occupied { item, .. } = entry {
+                self.remaining -= 1;
+                return Some(item);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        while let Some(entry) = self.inner.next_back() {
+            if let Entry::Occupied { item, .. } = entry {
+                self.remaining -= 1;
+                return Some(item);
+            }
+        }
+
+        None
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+#[derive(Debug)]
+pub struct Drain<'a, T> {
+    arena: &'a mut Arena<T>,
+    slot: usize,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = (Index, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.slot < self.arena.data.len() {
+            let slot = self.slot;
+            self.slot += 1;
+
+            if let Entry::Occupied { generation, .. } = self.arena.data[slot] {
+                let index = self.arena.stamp(Index::new(slot, generation));
+                self.arena.watchpoints.notify(slot, Access::Remove, index);
+                return Some((index, self.arena.remove_entry(index)));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.arena.count, Some(self.arena.count))
+    }
+}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+
+        // Hand out the vacated slots from the front again.
+        self.arena.rebuild_free_list();
+    }
+}
+
+pub struct ExtractIf<'a, T, F> {
+    arena: &'a mut Arena<T>,
+    slot: usize,
+    pred: F,
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    F: FnMut(Index, &mut T) -> bool,
+{
+    type Item = (Index, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.slot < self.arena.data.len() {
+            let slot = self.slot;
+            self.slot += 1;
+
+            let stamp = self.arena.stamper();
+            let (index, extract) = match &mut self.arena.data[slot] {
+                Entry::Occupied { generation, item } => {
+                    let index = stamp.index(slot, *generation);
+                    (index, (self.pred)(index, item))
+                }
+                Entry::Vacant { .. } | Entry::Reserved { .. } => continue,
+            };
+
+            if extract {
+                self.arena.watchpoints.notify(slot, Access::Remove, index);
+                return Some((index, self.arena.remove_entry(index)));
+            }
+
+            self.arena.touch(slot);
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.arena.count))
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for ExtractIf<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtractIf")
+            .field("arena", &self.arena)
+            .field("slot", &self.slot)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+pub struct IterIndexed<'a, T> {
+    inner: Enumerate<SliceIter<'a, Entry<T>>>,
+    remaining: usize,
+    stamp: Stamp,
+}
+
+impl<'a, T> Iterator for IterIndexed<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        for (slot, entry) in self.inner.by_ref() {
+            if let Entry::Occupied { generation, item } = entry {
+                self.remaining -= 1;
+                return Some((self.stamp.index(slot, *generation), item));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterIndexed<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        while let Some((slot, entry)) = self.inner.next_back() {
+            if let Entry::Occupied { generation, item } = entry {
+                self.remaining -= 1;
+                return Some((self.stamp.index(slot, *generation), item));
+            }
+        }
+
+        None
+    }
+}
+
+impl<T> ExactSizeIterator for IterIndexed<'_, T> {}
+
+impl<T> FusedIterator for IterIndexed<'_, T> {}
+
+#[derive(Debug)]
+pub struct IterIndexedMut<'a, T> {
+    inner: Enumerate<SliceIterMut<'a, Entry<T>>>,
+    remaining: usize,
+    stamp: Stamp,
+}
+
+impl<'a, T> Iterator for IterIndexedMut<'a, T> {
+    type Item = (Index, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        for (slot, entry) in self.inner.by_ref() {
+            if let Entry::Occupied { generation, item } = entry {
+                self.remaining -= 1;
+                return Some((self.stamp.index(slot, *generation), item));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterIndexedMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        while let Some((slot, entry)) = self.inner.next_back() {
+            if let Entry::Occupied { generation, item } = entry {
+                self.remaining -= 1;
+                return Some((self.stamp.index(slot, *generation), item));
+            }
+        }
+
+        None
+    }
+}
+
+impl<T> ExactSizeIterator for IterIndexedMut<'_, T> {}
+
+impl<T> FusedIterator for IterIndexedMut<'_, T> {}
+
+#[derive(Debug)]
+pub struct Keys<'a, T> {
+    inner: IterIndexed<'a, T>,
+}
+
+impl<T> Iterator for Keys<'_, T> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, _)| index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Keys<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, _)| index)
+    }
+}
+
+impl<T> ExactSizeIterator for Keys<'_, T> {}
+
+impl<T> FusedIterator for Keys<'_, T> {}
+
+/// Saved position of a resumable iteration.
+///
+/// See [`Arena::iter_resumable`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    slot: usize,
+}
+
+impl Cursor {
+    /// Create a cursor positioned at the start of the arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the cursor back to the start of the arena.
+    pub fn reset(&mut self) {
+        self.slot = 0;
+    }
+
+    /// True when the cursor is at the start of a sweep.
+    pub fn is_at_start(&self) -> bool {
+        self.slot == 0
+    }
+}
+
+#[derive(Debug)]
+pub struct Resumable<'a, 'c, T> {
+    data: &'a [Entry<T>],
+    cursor: &'c mut Cursor,
+    stamp: Stamp,
+}
+
+impl<'a, T> Iterator for Resumable<'a, '_, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = self.data;
+
+        while let Some(entry) = data.get(self.cursor.slot) {
+            let slot = self.cursor.slot;
+            self.cursor.slot += 1;
+
+            if let Entry::Occupied { generation, item } = entry {
+                return Some((self.stamp.index(slot, *generation), item));
+            }
+        }
+
+        self.cursor.reset();
+        None
+    }
+}
+
+#[derive(Debug)]
+pub struct ResumableMut<'a, 'c, T> {
+    data: &'a mut [Entry<T>],
+    revisions: Option<&'a mut revision::Revisions>,
+    cursor: &'c mut Cursor,
+    stamp: Stamp,
+}
+
+impl<'a, T> Iterator for ResumableMut<'a, '_, T> {
+    type Item = (Index, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The slice is kept starting at the cursor, so each
+        // entry is split off and handed out at most once.
+        while let Some((entry, rest)) = core::mem::take(&mut self.data).split_first_mut() {
+            let slot = self.cursor.slot;
+            self.cursor.slot += 1;
+            self.data = rest;
+
+            if let Entry::Occupied { generation, item } = entry {
+                if let Some(revisions) = self.revisions.as_deref_mut() {
+                    revisions.touch(slot);
+                }
+                return Some((self.stamp.index(slot, *generation), item));
             }
         }
 
+        self.cursor.reset();
         None
     }
 }
+
+#[derive(Debug)]
+pub struct ResolveMany<'a, T, I> {
+    arena: &'a Arena<T>,
+    inner: I,
+}
+
+impl<'a, T, I> Iterator for ResolveMany<'a, T, I>
+where
+    I: Iterator<Item = Option<Index>>,
+{
+    type Item = Option<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|index| index.and_then(|index| self.arena.get(index)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
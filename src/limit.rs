@@ -0,0 +1,52 @@
+//! Cap on the number of live items.
+use crate::Arena;
+
+impl<T> Arena<T> {
+    /// Create a new arena which holds at most `limit` items at a time.
+    ///
+    /// [`Arena::try_insert`] refuses items beyond the limit, while the other
+    /// insertion methods panic. Unlike the [memory budget](Arena::set_memory_budget),
+    /// only items count towards the limit, not storage or reservations.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let mut particles = Arena::with_limit(2);
+    /// let index = particles.insert("Spark");
+    /// particles.insert("Smoke");
+    ///
+    /// assert!(particles.is_full());
+    /// assert_eq!(particles.try_insert("Ember").unwrap_err().into_inner(), "Ember");
+    ///
+    /// particles.remove(index);
+    /// assert!(particles.try_insert("Ember").is_ok());
+    /// ```
+    pub fn with_limit(limit: usize) -> Self {
+        let mut arena = Self::new();
+        arena.limit = Some(limit);
+        arena
+    }
+
+    /// Maximum number of items the arena holds at a time, if limited.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Change the maximum number of items, or remove the limit with `None`.
+    ///
+    /// Items already in the arena are kept, even if there are more than
+    /// `limit` of them, but no more can be inserted until enough are removed.
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    /// Check whether the arena holds as many items as its limit allows.
+    pub fn is_full(&self) -> bool {
+        self.limit.is_some_and(|limit| self.count >= limit)
+    }
+
+    /// Panic if the arena has no room for another item under its limit.
+    #[inline]
+    pub(crate) fn assert_below_limit(&self) {
+        assert!(!self.is_full(), "arena is full");
+    }
+}
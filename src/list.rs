@@ -0,0 +1,286 @@
+//! Intrusive doubly linked lists threaded through arena items.
+use crate::{Arena, Index};
+
+/// Previous and next neighbours of an item in an [`ArenaList`], stored in
+/// the item itself.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Links {
+    prev: Option<Index>,
+    next: Option<Index>,
+    linked: bool,
+}
+
+impl Links {
+    /// Links of an item which isn't in a list.
+    pub const fn new() -> Self {
+        Self {
+            prev: None,
+            next: None,
+            linked: false,
+        }
+    }
+
+    pub fn prev(&self) -> Option<Index> {
+        self.prev
+    }
+
+    pub fn next(&self) -> Option<Index> {
+        self.next
+    }
+
+    /// Whether the item is in a list.
+    pub fn is_linked(&self) -> bool {
+        self.linked
+    }
+}
+
+/// Item which can be linked into an [`ArenaList`].
+pub trait Linked {
+    fn links(&self) -> &Links;
+    fn links_mut(&mut self) -> &mut Links;
+}
+
+/// Doubly linked list of items stored in an [`Arena`].
+///
+/// The list only holds its ends, and each item holds its neighbours in its
+/// [`Links`]. An item can be in one list at a time, and must be unlinked
+/// before it's removed from the arena.
+///
+/// # Panic
+///
+/// Operations panic when given a stale index, linking an item that is
+/// already in a list, or unlinking one that is an end of another list.
+///
+/// ```
+/// # use arena::Arena;
+/// # use arena::list::{ArenaList, Linked, Links};
+/// struct Job {
+///     name: &'static str,
+///     links: Links,
+/// }
+///
+/// impl Linked for Job {
+///     fn links(&self) -> &Links { &self.links }
+///     fn links_mut(&mut self) -> &mut Links { &mut self.links }
+/// }
+///
+/// let mut arena = Arena::new();
+/// let mut queue = ArenaList::new();
+/// for name in ["Foo", "Bar", "Baz"] {
+///     let index = arena.insert(Job { name, links: Links::new() });
+///     queue.push_back(&mut arena, index);
+/// }
+///
+/// let front = queue.pop_front(&mut arena).unwrap();
+/// queue.push_back(&mut arena, front);
+///
+/// let names: Vec<_> = queue.iter(&arena).map(|(_, job)| job.name).collect();
+/// assert_eq!(names, ["Bar", "Baz", "Foo"]);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ArenaList {
+    head: Option<Index>,
+    tail: Option<Index>,
+    len: usize,
+}
+
+impl ArenaList {
+    pub const fn new() -> Self {
+        Self {
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    /// Index of the first item.
+    pub fn head(&self) -> Option<Index> {
+        self.head
+    }
+
+    /// Index of the last item.
+    pub fn tail(&self) -> Option<Index> {
+        self.tail
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Link the item at `index` to the front of the list.
+    pub fn push_front<T: Linked>(&mut self, arena: &mut Arena<T>, index: Index) {
+        self.link(arena, index, None, self.head);
+    }
+
+    /// Link the item at `index` to the back of the list.
+    pub fn push_back<T: Linked>(&mut self, arena: &mut Arena<T>, index: Index) {
+        self.link(arena, index, self.tail, None);
+    }
+
+    /// Link the item at `index` right after the item at `after`,
+    /// which must be in this list.
+    pub fn insert_after<T: Linked>(&mut self, arena: &mut Arena<T>, after: Index, index: Index) {
+        let next = links(arena, after).next;
+        assert!(links(arena, after).linked, "index {:?} isn't linked", after);
+        self.link(arena, index, Some(after), next);
+    }
+
+    /// Link the item at `index` right before the item at `before`,
+    /// which must be in this list.
+    pub fn insert_before<T: Linked>(&mut self, arena: &mut Arena<T>, before: Index, index: Index) {
+        let prev = links(arena, before).prev;
+        assert!(links(arena, before).linked, "index {:?} isn't linked", before);
+        self.link(arena, index, prev, Some(before));
+    }
+
+    /// Unlink and return the first item's index.
+    pub fn pop_front<T: Linked>(&mut self, arena: &mut Arena<T>) -> Option<Index> {
+        let index = self.head?;
+        self.unlink(arena, index);
+        Some(index)
+    }
+
+    /// Unlink and return the last item's index.
+    pub fn pop_back<T: Linked>(&mut self, arena: &mut Arena<T>) -> Option<Index> {
+        let index = self.tail?;
+        self.unlink(arena, index);
+        Some(index)
+    }
+
+    /// Unlink the item at `index` from the list, which must be the list
+    /// it's in. The item stays in the arena.
+    ///
+    /// Returns `false` if the item isn't in a list.
+    pub fn unlink<T: Linked>(&mut self, arena: &mut Arena<T>, index: Index) -> bool {
+        let Links { prev, next, linked } = *links(arena, index);
+        if !linked {
+            return false;
+        }
+        assert!(
+            (prev.is_some() || self.head == Some(index)) && (next.is_some() || self.tail == Some(index)),
+            "index {:?} is in another list",
+            index
+        );
+
+        match prev {
+            Some(prev) => links_mut(arena, prev).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => links_mut(arena, next).prev = prev,
+            None => self.tail = prev,
+        }
+        *links_mut(arena, index) = Links::new();
+        self.len -= 1;
+        true
+    }
+
+    /// Move the item at `index`, which must be in this list, to the front.
+    pub fn move_to_front<T: Linked>(&mut self, arena: &mut Arena<T>, index: Index) {
+        self.unlink(arena, index);
+        self.push_front(arena, index);
+    }
+
+    /// Move the item at `index`, which must be in this list, to the back.
+    pub fn move_to_back<T: Linked>(&mut self, arena: &mut Arena<T>, index: Index) {
+        self.unlink(arena, index);
+        self.push_back(arena, index);
+    }
+
+    /// Unlink every item, leaving them in the arena.
+    pub fn clear<T: Linked>(&mut self, arena: &mut Arena<T>) {
+        while self.pop_front(arena).is_some() {}
+    }
+
+    /// Iterate the items in list order, together with their indices.
+    pub fn iter<'a, T: Linked>(&self, arena: &'a Arena<T>) -> Iter<'a, T> {
+        Iter {
+            arena,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len,
+        }
+    }
+
+    fn link<T: Linked>(&mut self, arena: &mut Arena<T>, index: Index, prev: Option<Index>, next: Option<Index>) {
+        let links = links_mut(arena, index);
+        assert!(!links.linked, "index {:?} is already linked", index);
+        *links = Links {
+            prev,
+            next,
+            linked: true,
+        };
+
+        match prev {
+            Some(prev) => links_mut(arena, prev).next = Some(index),
+            None => self.head = Some(index),
+        }
+        match next {
+            Some(next) => links_mut(arena, next).prev = Some(index),
+            None => self.tail = Some(index),
+        }
+        self.len += 1;
+    }
+}
+
+#[inline]
+fn links<T: Linked>(arena: &Arena<T>, index: Index) -> &Links {
+    match arena.get(index) {
+        Some(item) => item.links(),
+        None => panic!("index {:?} is stale", index),
+    }
+}
+
+#[inline]
+fn links_mut<T: Linked>(arena: &mut Arena<T>, index: Index) -> &mut Links {
+    match arena.get_mut(index) {
+        Some(item) => item.links_mut(),
+        None => panic!("index {:?} is stale", index),
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    arena: &'a Arena<T>,
+    front: Option<Index>,
+    back: Option<Index>,
+    remaining: usize,
+}
+
+impl<'a, T: Linked> Iterator for Iter<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let index = self.front?;
+        let item = &self.arena[index];
+        self.front = item.links().next;
+        self.remaining -= 1;
+        Some((index, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: Linked> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let index = self.back?;
+        let item = &self.arena[index];
+        self.back = item.links().prev;
+        self.remaining -= 1;
+        Some((index, item))
+    }
+}
+
+impl<T: Linked> ExactSizeIterator for Iter<'_, T> {}
@@ -0,0 +1,74 @@
+//! Moving items between arenas.
+use crate::{Arena, Entry, Index, IndexRemap};
+
+impl<T> Arena<T> {
+    /// Move every item of `other` into this arena, and return the
+    /// translation of their indices in `other` to the new ones.
+    ///
+    /// Items are inserted in slot order, like [`Arena::insert`], reusing
+    /// free slots first. Slots [reserved](Arena::reserve_index) in `other`
+    /// are dropped, and its callbacks aren't called.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// let mut world = Arena::new();
+    /// world.insert("Foo");
+    ///
+    /// let mut chunk = Arena::new();
+    /// let mut handle = chunk.insert("Bar");
+    ///
+    /// let remap = world.append(chunk);
+    /// assert!(remap.patch(&mut handle));
+    /// assert_eq!(world.get(handle), Some(&"Bar"));
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if an item would exceed the arena's [memory budget](Arena::set_memory_budget)
+    /// or [limit](Arena::with_limit). Items moved before that remain in this arena.
+    pub fn append(&mut self, mut other: Arena<T>) -> IndexRemap {
+        let mut remap = IndexRemap::new();
+        self.reserve(other.len());
+
+        for (slot, entry) in core::mem::take(&mut other.data).into_iter().enumerate() {
+            if let Entry::Occupied { generation, item } = entry {
+                let new = self.insert(item);
+                remap.insert(other.stamp(Index::new(slot, generation)), new);
+            }
+        }
+
+        remap
+    }
+
+    /// Move the items for which the predicate returns `true` into a new
+    /// arena, and return it with the translation of their old indices.
+    ///
+    /// Items are removed as if by [`Arena::remove`], and packed into the
+    /// new arena in slot order.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(("north", "Foo"));
+    /// let index1 = arena.insert(("south", "Bar"));
+    ///
+    /// let (south, remap) = arena.split_off_if(|_, (region, _)| *region == "south");
+    /// assert_eq!(arena.get(index1), None);
+    /// assert_eq!(south[remap.translate(index1).unwrap()], ("south", "Bar"));
+    /// # assert_eq!(remap.translate(index0), None);
+    /// ```
+    pub fn split_off_if<F>(&mut self, mut f: F) -> (Arena<T>, IndexRemap)
+    where
+        F: FnMut(Index, &T) -> bool,
+    {
+        let mut split = Arena::new();
+        let mut remap = IndexRemap::new();
+
+        for (old, item) in self.extract_if(|index, item| f(index, item)) {
+            let new = split.insert(item);
+            remap.insert(old, new);
+        }
+
+        (split, remap)
+    }
+}
@@ -0,0 +1,102 @@
+//! Instrumentation callbacks, for feeding growth and churn into metrics.
+use crate::Arena;
+#[cfg(feature = "metrics")]
+use crate::Index;
+
+/// Change in an arena reported to its [metrics hook](Arena::set_metrics_hook).
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaEvent {
+    /// An item was inserted at `index`, leaving `len` items.
+    Inserted { index: Index, len: usize },
+    /// The item at `index` was removed, leaving `len` items.
+    Removed { index: Index, len: usize },
+    /// The storage was reallocated from `previous` to `capacity` slots.
+    Grew { previous: usize, capacity: usize },
+    /// The arena was [compacted](Arena::compact), moving `relocated`
+    /// items and leaving `slots` slots in storage.
+    Compacted { relocated: usize, slots: usize },
+}
+
+/// Function invoked with every [`ArenaEvent`], see [`Arena::set_metrics_hook`].
+#[cfg(feature = "metrics")]
+pub type MetricsFn = fn(ArenaEvent);
+
+#[cfg(feature = "metrics")]
+impl<T> Arena<T> {
+    /// Register a function to call on every insertion, removal, growth of
+    /// storage and compaction, with the arena's counts and capacities.
+    ///
+    /// Replacing an item reports its removal and the new item's insertion.
+    /// Rolling back a [snapshot](Arena::restore) isn't reported. Replaces
+    /// any previous hook.
+    ///
+    /// ```
+    /// # use arena::{Arena, ArenaEvent};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// static INSERTED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// fn record(event: ArenaEvent) {
+    ///     if let ArenaEvent::Inserted { .. } = event {
+    ///         INSERTED.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.set_metrics_hook(record);
+    /// arena.insert("Foo");
+    /// arena.insert("Bar");
+    /// assert_eq!(INSERTED.load(Ordering::Relaxed), 2);
+    /// ```
+    pub fn set_metrics_hook(&mut self, hook: MetricsFn) {
+        self.metrics = Some(hook);
+    }
+
+    /// Remove the hook registered with [`Arena::set_metrics_hook`], if any.
+    pub fn clear_metrics_hook(&mut self) {
+        self.metrics = None;
+    }
+}
+
+#[allow(unused_variables)]
+impl<T> Arena<T> {
+    #[inline]
+    pub(crate) fn metric_inserted(&self, index: crate::Index) {
+        #[cfg(feature = "metrics")]
+        if let Some(hook) = self.metrics {
+            hook(ArenaEvent::Inserted { index, len: self.count });
+        }
+    }
+
+    #[inline]
+    pub(crate) fn metric_removed(&self, index: crate::Index) {
+        #[cfg(feature = "metrics")]
+        if let Some(hook) = self.metrics {
+            hook(ArenaEvent::Removed { index, len: self.count });
+        }
+    }
+
+    /// Report growth of storage, if its capacity changed from `previous`.
+    #[inline]
+    pub(crate) fn metric_grew(&self, previous: usize) {
+        #[cfg(feature = "metrics")]
+        if let Some(hook) = self.metrics {
+            let capacity = self.data.capacity();
+            if capacity != previous {
+                hook(ArenaEvent::Grew { previous, capacity });
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn metric_compacted(&self, relocated: usize) {
+        #[cfg(feature = "metrics")]
+        if let Some(hook) = self.metrics {
+            hook(ArenaEvent::Compacted {
+                relocated,
+                slots: self.data.len(),
+            });
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Several columns of items sharing one index space.
+use alloc::vec::Vec;
+use core::slice::{Iter as SliceIter, IterMut as SliceIterMut};
+
+use crate::{ids, IdAllocator, Index};
+
+/// Tuple of two to six column types, stored side by side in a [`MultiArena`].
+pub trait Columns: Sized {
+    /// Shared references to each column's item.
+    type Refs<'a>
+    where
+        Self: 'a;
+    /// Mutable references to each column's item.
+    type Muts<'a>
+    where
+        Self: 'a;
+    #[doc(hidden)]
+    type Storage: Default;
+    #[doc(hidden)]
+    type Iters<'a>
+    where
+        Self: 'a;
+    #[doc(hidden)]
+    type ItersMut<'a>
+    where
+        Self: 'a;
+
+    #[doc(hidden)]
+    fn put(storage: &mut Self::Storage, slot: usize, row: Self);
+    #[doc(hidden)]
+    fn take(storage: &mut Self::Storage, slot: usize) -> Option<Self>;
+    #[doc(hidden)]
+    fn get(storage: &Self::Storage, slot: usize) -> Option<Self::Refs<'_>>;
+    #[doc(hidden)]
+    fn get_mut(storage: &mut Self::Storage, slot: usize) -> Option<Self::Muts<'_>>;
+    #[doc(hidden)]
+    fn clear(storage: &mut Self::Storage);
+    #[doc(hidden)]
+    fn iters(storage: &Self::Storage) -> Self::Iters<'_>;
+    #[doc(hidden)]
+    fn iters_mut(storage: &mut Self::Storage) -> Self::ItersMut<'_>;
+    /// Next row of every column, skipping vacant slots.
+    #[doc(hidden)]
+    fn next<'a>(iters: &mut Self::Iters<'a>) -> Option<Self::Refs<'a>>;
+    #[doc(hidden)]
+    fn next_mut<'a>(iters: &mut Self::ItersMut<'a>) -> Option<Self::Muts<'a>>;
+}
+
+/// Parallel arenas that stay index-aligned, storing each element of a
+/// tuple in its own column.
+///
+/// Inserting a row hands out a single [`Index`], which resolves in every
+/// column, and removing it clears the row from every column. Each column
+/// is kept in its own `Vec`, so walking the rows touches the columns side
+/// by side.
+///
+/// ```
+/// # use arena::MultiArena;
+/// struct Position(f32);
+/// struct Velocity(f32);
+///
+/// let mut bodies = MultiArena::new();
+/// let index0 = bodies.insert((Position(0.0), Velocity(1.0)));
+/// let index1 = bodies.insert((Position(10.0), Velocity(-2.0)));
+///
+/// for (_, (position, velocity)) in bodies.iter_mut() {
+///     position.0 += velocity.0;
+/// }
+///
+/// let (position, _) = bodies.remove(index0).unwrap();
+/// assert_eq!(position.0, 1.0);
+/// assert!(bodies.get(index0).is_none());
+/// assert_eq!(bodies.get(index1).unwrap().0 .0, 8.0);
+/// ```
+pub struct MultiArena<C: Columns> {
+    ids: IdAllocator,
+    storage: C::Storage,
+}
+
+impl<C: Columns> MultiArena<C> {
+    pub fn new() -> Self {
+        Self {
+            ids: IdAllocator::new(),
+            storage: C::Storage::default(),
+        }
+    }
+
+    /// Insert a row, storing each of its elements in its column.
+    pub fn insert(&mut self, row: C) -> Index {
+        let index = self.ids.alloc();
+        C::put(&mut self.storage, index.slot(), row);
+        index
+    }
+
+    /// Remove the row at `index` from every column, and return it.
+    pub fn remove(&mut self, index: Index) -> Option<C> {
+        if !self.ids.free(index) {
+            return None;
+        }
+        C::take(&mut self.storage, index.slot())
+    }
+
+    /// References to the row's element in every column.
+    pub fn get(&self, index: Index) -> Option<C::Refs<'_>> {
+        if !self.ids.contains(index) {
+            return None;
+        }
+        C::get(&self.storage, index.slot())
+    }
+
+    /// Mutable references to the row's element in every column.
+    pub fn get_mut(&mut self, index: Index) -> Option<C::Muts<'_>> {
+        if !self.ids.contains(index) {
+            return None;
+        }
+        C::get_mut(&mut self.storage, index.slot())
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.ids.contains(index)
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Remove every row. Indices handed out before are invalidated.
+    pub fn clear(&mut self) {
+        self.ids.clear();
+        C::clear(&mut self.storage);
+    }
+
+    /// Iterate the rows in slot order, together with their indices.
+    pub fn iter(&self) -> Iter<'_, C> {
+        Iter {
+            ids: self.ids.iter(),
+            iters: C::iters(&self.storage),
+        }
+    }
+
+    /// Mutably iterate the rows in slot order, together with their indices.
+    pub fn iter_mut(&mut self) -> IterMut<'_, C> {
+        IterMut {
+            ids: self.ids.iter(),
+            iters: C::iters_mut(&mut self.storage),
+        }
+    }
+}
+
+impl<C: Columns> Default for MultiArena<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Columns> Clone for MultiArena<C>
+where
+    C::Storage: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            ids: self.ids.clone(),
+            storage: self.storage.clone(),
+        }
+    }
+}
+
+impl<C: Columns> core::fmt::Debug for MultiArena<C>
+where
+    C::Storage: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MultiArena")
+            .field("ids", &self.ids)
+            .field("storage", &self.storage)
+            .finish()
+    }
+}
+
+pub struct Iter<'a, C: Columns + 'a> {
+    ids: ids::Iter<'a>,
+    iters: C::Iters<'a>,
+}
+
+impl<'a, C: Columns + 'a> Iterator for Iter<'a, C> {
+    type Item = (Index, C::Refs<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.ids.next()?;
+        Some((index, C::next(&mut self.iters)?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<'a, C: Columns + 'a> ExactSizeIterator for Iter<'a, C> {}
+
+pub struct IterMut<'a, C: Columns + 'a> {
+    ids: ids::Iter<'a>,
+    iters: C::ItersMut<'a>,
+}
+
+impl<'a, C: Columns + 'a> Iterator for IterMut<'a, C> {
+    type Item = (Index, C::Muts<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.ids.next()?;
+        Some((index, C::next_mut(&mut self.iters)?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<'a, C: Columns + 'a> ExactSizeIterator for IterMut<'a, C> {}
+
+macro_rules! impl_columns {
+    ($($name:ident $field:tt $column:ident),+) => {
+        impl<$($name),+> Columns for ($($name,)+) {
+            type Refs<'a> = ($(&'a $name,)+) where Self: 'a;
+            type Muts<'a> = ($(&'a mut $name,)+) where Self: 'a;
+            type Storage = ($(Vec<Option<$name>>,)+);
+            type Iters<'a> = ($(SliceIter<'a, Option<$name>>,)+) where Self: 'a;
+            type ItersMut<'a> = ($(SliceIterMut<'a, Option<$name>>,)+) where Self: 'a;
+
+            fn put(storage: &mut Self::Storage, slot: usize, row: Self) {
+                let ($($column,)+) = storage;
+                $(
+                    if $column.len() <= slot {
+                        $column.resize_with(slot + 1, || None);
+                    }
+                    $column[slot] = Some(row.$field);
+                )+
+            }
+
+            fn take(storage: &mut Self::Storage, slot: usize) -> Option<Self> {
+                let ($($column,)+) = storage;
+                Some(($($column.get_mut(slot)?.take()?,)+))
+            }
+
+            fn get(storage: &Self::Storage, slot: usize) -> Option<Self::Refs<'_>> {
+                let ($($column,)+) = storage;
+                Some(($($column.get(slot)?.as_ref()?,)+))
+            }
+
+            fn get_mut(storage: &mut Self::Storage, slot: usize) -> Option<Self::Muts<'_>> {
+                let ($($column,)+) = storage;
+                Some(($($column.get_mut(slot)?.as_mut()?,)+))
+            }
+
+            fn clear(storage: &mut Self::Storage) {
+                let ($($column,)+) = storage;
+                $($column.clear();)+
+            }
+
+            fn iters(storage: &Self::Storage) -> Self::Iters<'_> {
+                let ($($column,)+) = storage;
+                ($($column.iter(),)+)
+            }
+
+            fn iters_mut(storage: &mut Self::Storage) -> Self::ItersMut<'_> {
+                let ($($column,)+) = storage;
+                ($($column.iter_mut(),)+)
+            }
+
+            fn next<'a>(iters: &mut Self::Iters<'a>) -> Option<Self::Refs<'a>> {
+                let ($($column,)+) = iters;
+                loop {
+                    if let ($(Some($column),)+) = ($($column.next()?.as_ref(),)+) {
+                        return Some(($($column,)+));
+                    }
+                }
+            }
+
+            fn next_mut<'a>(iters: &mut Self::ItersMut<'a>) -> Option<Self::Muts<'a>> {
+                let ($($column,)+) = iters;
+                loop {
+                    if let ($(Some($column),)+) = ($($column.next()?.as_mut(),)+) {
+                        return Some(($($column,)+));
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_columns!(A 0 a, B 1 b);
+impl_columns!(A 0 a, B 1 b, C 2 c);
+impl_columns!(A 0 a, B 1 b, C 2 c, D 3 d);
+impl_columns!(A 0 a, B 1 b, C 2 c, D 3 d, E 4 e);
+impl_columns!(A 0 a, B 1 b, C 2 c, D 3 d, E 4 e, F 5 f);
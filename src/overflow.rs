@@ -0,0 +1,78 @@
+//! What happens when a slot runs out of generations.
+use core::num::NonZeroUsize;
+
+use crate::{Arena, Entry};
+
+/// Policy for a slot whose generation would advance past `usize::MAX`,
+/// chosen with [`Arena::with_overflow_policy`].
+///
+/// With 64-bit generations a slot has to be reused billions of times per
+/// second for centuries to get there, but smaller targets or generations
+/// seeded from untrusted data can exhaust them much sooner.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GenerationOverflow {
+    /// Keep the slot at `usize::MAX`. Stale indices with the final
+    /// generation resolve to whichever item reuses the slot.
+    #[default]
+    Saturate,
+    /// Start over at generation `1`. Stale indices from the previous round
+    /// resolve to the item reusing the slot once it gets to their generation.
+    Wrap,
+    /// Panic, leaving the slot as it was.
+    Panic,
+    /// Retire the slot, so it's never reused. A vacant slot reaching
+    /// `usize::MAX` stays in storage as a tombstone, and is skipped by
+    /// insertion, compaction and truncation.
+    Retire,
+}
+
+impl<T> Arena<T> {
+    /// Create a new arena with the given generation overflow policy.
+    ///
+    /// The policy can't be changed later, since slots saturated under one
+    /// policy can't be retired by another after the fact.
+    ///
+    /// ```
+    /// # use arena::{Arena, GenerationOverflow};
+    /// let arena = Arena::<&str>::with_overflow_policy(GenerationOverflow::Retire);
+    /// assert_eq!(arena.overflow_policy(), GenerationOverflow::Retire);
+    /// ```
+    pub fn with_overflow_policy(policy: GenerationOverflow) -> Self {
+        let mut arena = Self::new();
+        arena.overflow = policy;
+        arena
+    }
+
+    pub fn overflow_policy(&self) -> GenerationOverflow {
+        self.overflow
+    }
+
+    /// Generation following `generation` in `slot`, according to the overflow policy.
+    pub(crate) fn next_generation(&self, slot: usize, generation: NonZeroUsize) -> NonZeroUsize {
+        match (generation.checked_add(1), self.overflow) {
+            (Some(next), _) => next,
+            (None, GenerationOverflow::Saturate | GenerationOverflow::Retire) => generation,
+            (None, GenerationOverflow::Wrap) => NonZeroUsize::MIN,
+            (None, GenerationOverflow::Panic) => panic!("generation of slot {} overflowed", slot),
+        }
+    }
+
+    /// Generation for an item replacing the one with `generation` in
+    /// `slot`, which keeps the slot occupied.
+    ///
+    /// A retiring slot can't be given its final generation without
+    /// being vacated, so this panics instead.
+    pub(crate) fn regenerate(&self, slot: usize, generation: NonZeroUsize) -> NonZeroUsize {
+        if self.overflow == GenerationOverflow::Retire && generation == NonZeroUsize::MAX {
+            panic!("generation of slot {} overflowed", slot);
+        }
+        self.next_generation(slot, generation)
+    }
+
+    /// Check whether the entry is a tombstone left by [`GenerationOverflow::Retire`].
+    #[inline]
+    pub(crate) fn is_retired(&self, entry: &Entry<T>) -> bool {
+        self.overflow == GenerationOverflow::Retire
+            && matches!(entry, Entry::Vacant { generation, .. } if *generation == NonZeroUsize::MAX)
+    }
+}
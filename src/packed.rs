@@ -0,0 +1,190 @@
+//! Indices with a narrower slot and generation width.
+use core::fmt;
+use core::hash::Hash;
+use core::num::{NonZeroU16, NonZeroU32, NonZeroUsize};
+
+use crate::Index;
+
+/// Unsigned integer usable as the slot and generation width of a [`PackedIndex`].
+///
+/// Implemented for `u16`, `u32` and `usize`.
+pub trait SlotInt: sealed::Sealed + Copy + Eq + Ord + Hash + fmt::Debug {
+    /// Non-zero counterpart, used for the generation to keep
+    /// the niche in `Option<PackedIndex<_>>`.
+    type NonZero: Copy + Eq + Ord + Hash + fmt::Debug;
+
+    fn from_usize(value: usize) -> Option<Self>;
+    fn to_usize(self) -> usize;
+    fn non_zero_from_usize(value: NonZeroUsize) -> Option<Self::NonZero>;
+    fn non_zero_to_usize(value: Self::NonZero) -> NonZeroUsize;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for usize {}
+}
+
+macro_rules! impl_slot_int {
+    ($int:ty, $non_zero:ty) => {
+        impl SlotInt for $int {
+            type NonZero = $non_zero;
+
+            #[inline]
+            fn from_usize(value: usize) -> Option<Self> {
+                <$int>::try_from(value).ok()
+            }
+
+            #[inline]
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+
+            #[inline]
+            fn non_zero_from_usize(value: NonZeroUsize) -> Option<Self::NonZero> {
+                <$non_zero>::try_from(value).ok()
+            }
+
+            #[inline]
+            fn non_zero_to_usize(value: Self::NonZero) -> NonZeroUsize {
+                // Widening is lossless, all supported widths fit in a usize.
+                NonZeroUsize::new(value.get() as usize).unwrap()
+            }
+        }
+    };
+}
+
+impl_slot_int!(u16, NonZeroU16);
+impl_slot_int!(u32, NonZeroU32);
+impl_slot_int!(usize, NonZeroUsize);
+
+/// An [`Index`] stored with a slot and generation of width `S`.
+///
+/// Arenas hand out and accept [`Index`] values. Packed indices are a
+/// storage format for handles, for example in messages or lookup tables,
+/// and convert back with [`From`].
+///
+/// ```
+/// # use arena::{Arena, PackedIndex};
+/// # let mut arena = Arena::new();
+/// let index = arena.insert("Foo");
+///
+/// let packed = PackedIndex::<u16>::try_from(index).unwrap();
+/// assert_eq!(std::mem::size_of_val(&packed), 4);
+/// assert_eq!(std::mem::size_of::<Option<PackedIndex<u16>>>(), 4);
+///
+/// assert_eq!(arena.get(packed.into()), Some(&"Foo"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedIndex<S: SlotInt> {
+    generation: S::NonZero,
+    slot: S,
+}
+
+impl<S: SlotInt> PackedIndex<S> {
+    pub fn slot(&self) -> usize {
+        self.slot.to_usize()
+    }
+
+    pub fn generation(&self) -> usize {
+        S::non_zero_to_usize(self.generation).get()
+    }
+}
+
+impl<S: SlotInt> PartialOrd for PackedIndex<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: SlotInt> Ord for PackedIndex<S> {
+    /// Packed indices are ordered by slot, then generation, like the
+    /// [`Index`] they convert to.
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (self.slot, self.generation).cmp(&(other.slot, other.generation))
+    }
+}
+
+impl<S: SlotInt> TryFrom<Index> for PackedIndex<S> {
+    type Error = PackIndexError;
+
+    /// Fails if the slot or generation don't fit in `S`, or the index carries a tag.
+    fn try_from(index: Index) -> Result<Self, Self::Error> {
+        if index.tag() != 0 {
+            return Err(PackIndexError::Tagged);
+        }
+
+        Ok(PackedIndex {
+            generation: S::non_zero_from_usize(index.generation).ok_or(PackIndexError::GenerationOverflow)?,
+            slot: S::from_usize(index.slot()).ok_or(PackIndexError::SlotOverflow)?,
+        })
+    }
+}
+
+impl<S: SlotInt> From<PackedIndex<S>> for Index {
+    fn from(packed: PackedIndex<S>) -> Self {
+        Index::new(packed.slot.to_usize(), S::non_zero_to_usize(packed.generation))
+    }
+}
+
+/// An 8 byte index with a `u32` slot and generation.
+///
+/// Half the size of [`Index`] on 64-bit targets, and `Option<CompactIndex>`
+/// is the same size thanks to the non-zero generation. Useful for handles
+/// stored in large numbers, such as in components.
+///
+/// ```
+/// # use arena::{Arena, CompactIndex};
+/// # let mut arena = Arena::new();
+/// struct Sprite {
+///     texture: CompactIndex,
+/// }
+///
+/// let texture = arena.insert("grass.png");
+/// let sprite = Sprite { texture: texture.try_into().unwrap() };
+///
+/// assert_eq!(std::mem::size_of::<Option<CompactIndex>>(), 8);
+/// assert_eq!(arena.get(sprite.texture.into()), Some(&"grass.png"));
+/// ```
+pub type CompactIndex = PackedIndex<u32>;
+
+impl CompactIndex {
+    /// Pack the index into an integer, with the generation in the high
+    /// 32 bits and the slot in the low 32 bits.
+    pub fn to_bits(self) -> u64 {
+        (u64::from(self.generation.get()) << 32) | u64::from(self.slot)
+    }
+
+    /// Unpack an index packed by [`CompactIndex::to_bits`].
+    ///
+    /// Returns `None` if the generation is zero.
+    pub fn from_bits(bits: u64) -> Option<CompactIndex> {
+        Some(PackedIndex {
+            generation: NonZeroU32::new((bits >> 32) as u32)?,
+            slot: bits as u32,
+        })
+    }
+}
+
+/// Error returned when an [`Index`] can't be converted to a [`PackedIndex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackIndexError {
+    SlotOverflow,
+    GenerationOverflow,
+    /// Tags are not preserved by packed indices.
+    Tagged,
+}
+
+impl fmt::Display for PackIndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackIndexError::SlotOverflow => write!(f, "slot does not fit in the packed index"),
+            PackIndexError::GenerationOverflow => write!(f, "generation does not fit in the packed index"),
+            PackIndexError::Tagged => write!(f, "tagged indices can't be packed"),
+        }
+    }
+}
+
+impl core::error::Error for PackIndexError {}
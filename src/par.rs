@@ -0,0 +1,158 @@
+//! Parallel operations, behind the `rayon` feature.
+use rayon::iter::plumbing::UnindexedConsumer;
+use rayon::prelude::*;
+
+use crate::checks::Stamp;
+use crate::{Arena, Entry, Index};
+
+impl<T: Sync> Arena<T> {
+    /// Iterate the items in the arena in parallel.
+    ///
+    /// The iterator isn't an [`IndexedParallelIterator`], because the
+    /// position of an item among the occupied slots isn't known without
+    /// scanning the slots before it.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # use rayon::prelude::*;
+    /// # let mut arena = Arena::new();
+    /// arena.insert(1);
+    /// arena.insert(2);
+    /// assert_eq!(arena.par_iter().sum::<i32>(), 3);
+    /// ```
+    pub fn par_iter(&self) -> ParIter<'_, T> {
+        ParIter { data: &self.data }
+    }
+
+    /// Build a new arena by transforming every item in parallel.
+    ///
+    /// The new arena has exactly the same slot layout and generations,
+    /// so indices into this arena are also valid for the returned one.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert(3);
+    /// let squares = arena.par_map(|_, item| item * item);
+    /// assert_eq!(squares.get(index), Some(&9));
+    /// ```
+    pub fn par_map<U, F>(&self, f: F) -> Arena<U>
+    where
+        U: Send,
+        F: Fn(Index, &T) -> U + Sync,
+    {
+        let stamp = self.stamper();
+        let data = self
+            .data
+            .par_iter()
+            .enumerate()
+            .map(|(slot, entry)| match entry {
+                Entry::Vacant { generation, next } => Entry::Vacant {
+                    generation: *generation,
+                    next: *next,
+                },
+                Entry::Reserved { generation } => Entry::Reserved {
+                    generation: *generation,
+                },
+                Entry::Occupied { generation, item } => Entry::Occupied {
+                    generation: *generation,
+                    item: f(stamp.index(slot, *generation), item),
+                },
+            })
+            .collect();
+
+        self.with_layout(data)
+    }
+}
+
+impl<T: Send> Arena<T> {
+    /// Mutably iterate the items in the arena in parallel.
+    pub fn par_iter_mut(&mut self) -> ParIterMut<'_, T> {
+        self.touch_occupied();
+        ParIterMut { data: &mut self.data }
+    }
+
+    /// Mutably iterate the items in the arena in parallel, together
+    /// with their indices.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # use rayon::prelude::*;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert(None);
+    /// arena.par_iter_indexed_mut().for_each(|(index, item)| *item = Some(index));
+    /// assert_eq!(arena.get(index), Some(&Some(index)));
+    /// ```
+    pub fn par_iter_indexed_mut(&mut self) -> ParIterIndexedMut<'_, T> {
+        self.touch_occupied();
+        ParIterIndexedMut {
+            stamp: self.stamper(),
+            data: &mut self.data,
+        }
+    }
+}
+
+/// Parallel iterator over the items of an [`Arena`], created by [`Arena::par_iter`].
+#[derive(Debug)]
+pub struct ParIter<'a, T> {
+    data: &'a [Entry<T>],
+}
+
+impl<'a, T: Sync> ParallelIterator for ParIter<'a, T> {
+    type Item = &'a T;
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.data
+            .par_iter()
+            .filter_map(|entry| match entry {
+                Entry::Vacant { .. } | Entry::Reserved { .. } => None,
+                Entry::Occupied { item, .. } => Some(item),
+            })
+            .drive_unindexed(consumer)
+    }
+}
+
+/// Parallel iterator over mutable references to the items of an
+/// [`Arena`], created by [`Arena::par_iter_mut`].
+#[derive(Debug)]
+pub struct ParIterMut<'a, T> {
+    data: &'a mut [Entry<T>],
+}
+
+impl<'a, T: Send> ParallelIterator for ParIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.data
+            .par_iter_mut()
+            .filter_map(|entry| match entry {
+                Entry::Vacant { .. } | Entry::Reserved { .. } => None,
+                Entry::Occupied { item, .. } => Some(item),
+            })
+            .drive_unindexed(consumer)
+    }
+}
+
+/// Parallel iterator over the indices and mutable references to the
+/// items of an [`Arena`], created by [`Arena::par_iter_indexed_mut`].
+#[derive(Debug)]
+pub struct ParIterIndexedMut<'a, T> {
+    data: &'a mut [Entry<T>],
+    stamp: Stamp,
+}
+
+impl<'a, T: Send> ParallelIterator for ParIterIndexedMut<'a, T> {
+    type Item = (Index, &'a mut T);
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        let stamp = self.stamp;
+        self.data
+            .par_iter_mut()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Entry::Vacant { .. } | Entry::Reserved { .. } => None,
+                Entry::Occupied { generation, item } => Some((stamp.index(slot, *generation), item)),
+            })
+            .drive_unindexed(consumer)
+    }
+}
@@ -0,0 +1,257 @@
+//! Copy-on-write arena, for keeping cheap historical versions.
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::iter::Enumerate;
+use core::num::NonZeroUsize;
+use core::slice::Iter as SliceIter;
+
+use crate::Index;
+
+/// Number of slots in each shared chunk.
+const CHUNK_LEN: usize = 64;
+
+/// Arena whose clones share storage, copying only the chunks of slots
+/// that are modified afterwards.
+///
+/// Cloning is O(1), so every version of the arena can be kept around, for
+/// example for rollback. A mutation copies the chunk of slots it touches,
+/// if another version still shares it, and the table of chunks.
+///
+/// ```
+/// # use arena::PersistentArena;
+/// let mut world = PersistentArena::new();
+/// let player = world.insert(10);
+///
+/// let saved = world.clone();
+/// *world.get_mut(player).unwrap() -= 3;
+/// world.insert(20);
+///
+/// assert_eq!(world.get(player), Some(&7));
+/// assert_eq!(saved.get(player), Some(&10));
+/// assert_eq!(saved.len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct PersistentArena<T> {
+    chunks: Arc<Vec<Arc<Vec<Slot<T>>>>>,
+    free_head: Option<usize>,
+    slots: usize,
+    count: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Slot<T> {
+    Vacant {
+        generation: NonZeroUsize,
+        next: Option<usize>,
+    },
+    Occupied {
+        generation: NonZeroUsize,
+        item: T,
+    },
+}
+
+impl<T> PersistentArena<T> {
+    pub fn new() -> Self {
+        Self {
+            chunks: Arc::new(Vec::new()),
+            free_head: None,
+            slots: 0,
+            count: 0,
+        }
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        let slot = index.slot();
+        match self.chunks.get(slot / CHUNK_LEN)?.get(slot % CHUNK_LEN)? {
+            Slot::Occupied { generation, item } if *generation == index.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Check whether both arenas still share all their storage, like a
+    /// clone that neither was modified since.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.chunks, &other.chunks)
+    }
+
+    /// Iterate the items together with their indices, in slot order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            chunks: self.chunks.iter(),
+            current: [].iter().enumerate(),
+            base: 0,
+            next_base: 0,
+            remaining: self.count,
+        }
+    }
+}
+
+impl<T: Clone> PersistentArena<T> {
+    /// Insert the item, reusing a vacant slot if there is one.
+    pub fn insert(&mut self, item: T) -> Index {
+        match self.free_head {
+            Some(slot) => {
+                let entry = self.slot_mut(slot);
+                let generation = match *entry {
+                    Slot::Vacant { generation, next } => {
+                        self.free_head = next;
+                        generation
+                    }
+                    Slot::Occupied { .. } => unreachable!("free list points to an occupied slot"),
+                };
+                *self.slot_mut(slot) = Slot::Occupied { generation, item };
+                self.count += 1;
+                Index::new(slot, generation)
+            }
+            None => {
+                let slot = self.slots;
+                assert!(slot < Index::SLOT_MASK, "arena exceeded the maximum number of slots");
+
+                let chunks = Arc::make_mut(&mut self.chunks);
+                if slot == chunks.len() * CHUNK_LEN {
+                    chunks.push(Arc::new(Vec::with_capacity(CHUNK_LEN)));
+                }
+                let generation = NonZeroUsize::MIN;
+                let chunk = chunks.last_mut().unwrap();
+                Arc::make_mut(chunk).push(Slot::Occupied { generation, item });
+
+                self.slots += 1;
+                self.count += 1;
+                Index::new(slot, generation)
+            }
+        }
+    }
+
+    /// Remove the item at the given `index`.
+    pub fn remove(&mut self, index: Index) {
+        self.take(index);
+    }
+
+    /// Remove and return the item at the given `index`.
+    pub fn take(&mut self, index: Index) -> Option<T> {
+        if !self.contains(index) {
+            return None;
+        }
+
+        let slot = index.slot();
+        let vacant = Slot::Vacant {
+            generation: index.generation.saturating_add(1),
+            next: self.free_head,
+        };
+        self.free_head = Some(slot);
+        self.count -= 1;
+
+        match core::mem::replace(self.slot_mut(slot), vacant) {
+            Slot::Occupied { item, .. } => Some(item),
+            Slot::Vacant { .. } => unreachable!(),
+        }
+    }
+
+    /// Return a mutable reference to the item, copying its chunk first if
+    /// it's shared with another version.
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        if !self.contains(index) {
+            return None;
+        }
+
+        match self.slot_mut(index.slot()) {
+            Slot::Occupied { item, .. } => Some(item),
+            Slot::Vacant { .. } => unreachable!(),
+        }
+    }
+
+    /// Remove every item. Indices handed out before are invalidated.
+    pub fn clear(&mut self) {
+        let indices: Vec<Index> = self.iter().map(|(index, _)| index).collect();
+        for index in indices {
+            self.remove(index);
+        }
+    }
+
+    /// Unshare the chunk holding `slot`, and return the slot.
+    fn slot_mut(&mut self, slot: usize) -> &mut Slot<T> {
+        let chunk = &mut Arc::make_mut(&mut self.chunks)[slot / CHUNK_LEN];
+        &mut Arc::make_mut(chunk)[slot % CHUNK_LEN]
+    }
+}
+
+impl<T> Clone for PersistentArena<T> {
+    /// Create a new version sharing all storage with this one.
+    fn clone(&self) -> Self {
+        Self {
+            chunks: Arc::clone(&self.chunks),
+            free_head: self.free_head,
+            slots: self.slots,
+            count: self.count,
+        }
+    }
+}
+
+impl<T> Default for PersistentArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> core::ops::Index<Index> for PersistentArena<T> {
+    type Output = T;
+
+    /// # Panic
+    ///
+    /// Panics if `index` is stale or out of bounds.
+    fn index(&self, index: Index) -> &T {
+        self.get(index).expect("index is stale or out of bounds")
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    chunks: SliceIter<'a, Arc<Vec<Slot<T>>>>,
+    current: Enumerate<SliceIter<'a, Slot<T>>>,
+    /// First slot of the current chunk.
+    base: usize,
+    next_base: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            match self.current.next() {
+                Some((offset, Slot::Occupied { generation, item })) => {
+                    self.remaining -= 1;
+                    return Some((Index::new(self.base + offset, *generation), item));
+                }
+                Some((_, Slot::Vacant { .. })) => {}
+                None => {
+                    let chunk = self.chunks.next()?;
+                    self.base = self.next_base;
+                    self.next_base += CHUNK_LEN;
+                    self.current = chunk.iter().enumerate();
+                }
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
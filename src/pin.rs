@@ -0,0 +1,662 @@
+//! Arenas stored in fixed-size chunks, whose items never move.
+use alloc::vec::Vec;
+use core::iter::{Enumerate, Flatten};
+#[cfg(not(feature = "allocator-api2"))]
+use core::marker::PhantomData;
+use core::num::NonZeroUsize;
+use core::pin::Pin;
+use core::slice::{Iter as SliceIter, IterMut as SliceIterMut};
+
+#[cfg(feature = "allocator-api2")]
+pub use allocator_api2::alloc::{Allocator, Global};
+
+use crate::{Entry, Index};
+
+/// Allocator the chunks of a [`PinArena`] can come from.
+///
+/// With the `allocator-api2` feature, this is any [`Allocator`] that can be
+/// cloned, such as a reference to a bump allocator. Without it, chunks
+/// always come from the global allocator.
+///
+/// [`PinArena`] is the only arena with an allocator parameter.
+/// [`Arena`](crate::Arena) and the arenas built on it allocate from the
+/// global heap.
+#[cfg(feature = "allocator-api2")]
+pub trait ChunkAllocator: Allocator + Clone {}
+
+#[cfg(feature = "allocator-api2")]
+impl<A: Allocator + Clone> ChunkAllocator for A {}
+
+/// Allocator the chunks of a [`PinArena`] can come from.
+///
+/// With the `allocator-api2` feature, this is any `Allocator` that can be
+/// cloned, such as a reference to a bump allocator. Without it, chunks
+/// always come from the global allocator.
+///
+/// [`PinArena`] is the only arena with an allocator parameter.
+/// [`Arena`](crate::Arena) and the arenas built on it allocate from the
+/// global heap.
+#[cfg(not(feature = "allocator-api2"))]
+pub trait ChunkAllocator: Clone {}
+
+/// The global memory allocator.
+#[cfg(not(feature = "allocator-api2"))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Global;
+
+#[cfg(not(feature = "allocator-api2"))]
+impl ChunkAllocator for Global {}
+
+/// Slots of a single chunk, allocated with room for all of them.
+#[derive(Debug)]
+struct Chunk<T, A: ChunkAllocator> {
+    #[cfg(feature = "allocator-api2")]
+    entries: allocator_api2::vec::Vec<Entry<T>, A>,
+    #[cfg(not(feature = "allocator-api2"))]
+    entries: Vec<Entry<T>>,
+    #[cfg(not(feature = "allocator-api2"))]
+    alloc: PhantomData<A>,
+}
+
+impl<T, A: ChunkAllocator> Chunk<T, A> {
+    #[cfg(feature = "allocator-api2")]
+    fn new(capacity: usize, alloc: &A) -> Self {
+        Self {
+            entries: allocator_api2::vec::Vec::with_capacity_in(capacity, alloc.clone()),
+        }
+    }
+
+    #[cfg(not(feature = "allocator-api2"))]
+    fn new(capacity: usize, _: &A) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            alloc: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    fn get(&self, offset: usize) -> Option<&Entry<T>> {
+        self.entries.get(offset)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, offset: usize) -> Option<&mut Entry<T>> {
+        self.entries.get_mut(offset)
+    }
+
+    /// Append an entry. Callers make sure the chunk isn't full, so this never reallocates.
+    #[inline]
+    fn push(&mut self, entry: Entry<T>) {
+        debug_assert!(self.entries.len() < self.entries.capacity());
+        self.entries.push(entry);
+    }
+}
+
+impl<'a, T, A: ChunkAllocator> IntoIterator for &'a Chunk<T, A> {
+    type Item = &'a Entry<T>;
+    type IntoIter = SliceIter<'a, Entry<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+impl<'a, T, A: ChunkAllocator> IntoIterator for &'a mut Chunk<T, A> {
+    type Item = &'a mut Entry<T>;
+    type IntoIter = SliceIterMut<'a, Entry<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter_mut()
+    }
+}
+
+/// Arena keeping each item at the same address from insertion until
+/// it's removed.
+///
+/// Storage is a list of chunks of `CHUNK` slots, which are allocated as
+/// the arena grows but never reallocated, so growing doesn't move any
+/// items. That also keeps growth cheap, without copying all items or
+/// allocating one large block, at the cost of an extra indirection on
+/// every lookup. See [`ChunkedArena`] for the same storage without the
+/// pinning guarantees, and the `Unpin` bounds that come with them.
+///
+/// Chunks can come from a custom allocator with the `allocator-api2`
+/// feature, see [`PinArena::new_in`]. Unlike [`Arena`](crate::Arena),
+/// whose storage always comes from the global heap, this makes the
+/// arena's memory attributable to a subsystem's allocator.
+///
+/// Items are dropped in place when removed, which makes the arena uphold
+/// the guarantees of [`Pin`]: [`PinArena::get_pin_mut`] gives pinned access
+/// to items that aren't [`Unpin`], such as self-referential ones.
+///
+/// Indices have the same generation semantics as in [`Arena`](crate::Arena),
+/// but are only meaningful in the arena that handed them out.
+///
+/// ```
+/// # use arena::PinArena;
+/// let mut arena: PinArena<_> = PinArena::new();
+/// let index0 = arena.insert(1);
+/// let address = arena.get(index0).unwrap() as *const i32;
+///
+/// for n in 0..1000 {
+///     arena.insert(n);
+/// }
+/// assert_eq!(arena.get(index0).unwrap() as *const i32, address);
+/// ```
+#[derive(Debug)]
+pub struct PinArena<T, const CHUNK: usize = 64, A: ChunkAllocator = Global> {
+    /// Chunks of `CHUNK` slots. Each is allocated with its full
+    /// capacity up front, and never pushed to beyond it.
+    chunks: Vec<Chunk<T, A>>,
+    free_head: Option<usize>,
+    count: usize,
+    alloc: A,
+}
+
+impl<T, const CHUNK: usize> PinArena<T, CHUNK> {
+    /// Create an empty arena. No chunk is allocated until the first insertion.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `CHUNK` is zero.
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, const CHUNK: usize, A: ChunkAllocator> PinArena<T, CHUNK, A> {
+    /// Create an empty arena, whose chunks come from `alloc`.
+    ///
+    /// Only the chunks are allocated by `alloc`, the short list of them
+    /// lives on the global heap. No chunk is allocated until the first insertion.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `CHUNK` is zero.
+    pub fn new_in(alloc: A) -> Self {
+        assert!(CHUNK > 0, "chunks must hold at least one slot");
+
+        Self {
+            chunks: Vec::new(),
+            free_head: None,
+            count: 0,
+            alloc,
+        }
+    }
+
+    /// The allocator the chunks come from.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Number of slots in the chunks allocated so far.
+    pub fn capacity(&self) -> usize {
+        self.chunks.len() * CHUNK
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Insert the item into the first free slot, allocating a new chunk if there is none.
+    pub fn insert(&mut self, item: T) -> Index {
+        if let Some(slot) = self.free_head {
+            let entry = self.entry_mut(slot).unwrap();
+            let (generation, next) = match *entry {
+                Entry::Vacant { generation, next } => (generation, next),
+                _ => unreachable!("free list points to an occupied slot"),
+            };
+            *entry = Entry::Occupied { generation, item };
+            self.free_head = next;
+            self.count += 1;
+            return Index::new(slot, generation);
+        }
+
+        if self.chunks.last().is_none_or(|chunk| chunk.len() == CHUNK) {
+            self.chunks.push(Chunk::new(CHUNK, &self.alloc));
+        }
+        let slot = (self.chunks.len() - 1) * CHUNK + self.chunks.last().unwrap().len();
+        assert!(slot < Index::SLOT_MASK, "arena exceeded the maximum number of slots");
+
+        let generation = NonZeroUsize::MIN;
+        self.chunks
+            .last_mut()
+            .unwrap()
+            .push(Entry::Occupied { generation, item });
+        self.count += 1;
+        Index::new(slot, generation)
+    }
+
+    /// Remove the item at the given `index`, dropping it in place.
+    ///
+    /// Returns `false` if `index` is stale.
+    pub fn remove(&mut self, index: Index) -> bool {
+        if !self.contains(index) {
+            return false;
+        }
+
+        let slot = index.slot();
+        let free_head = self.free_head;
+        // Assigning drops the item where it is, so pinned items don't move.
+        *self.entry_mut(slot).unwrap() = Entry::Vacant {
+            generation: index.generation.saturating_add(1),
+            next: free_head,
+        };
+        self.free_head = Some(slot);
+        self.count -= 1;
+        true
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        match self.entry(index.slot()) {
+            Some(Entry::Occupied { generation, item }) if *generation == index.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    /// Return a pinned reference to the item at the given `index`.
+    pub fn get_pin(&self, index: Index) -> Option<Pin<&T>> {
+        // SAFETY: Items are never moved until they're dropped in place.
+        self.get(index).map(|item| unsafe { Pin::new_unchecked(item) })
+    }
+
+    /// Return a pinned mutable reference to the item at the given `index`.
+    ///
+    /// ```
+    /// # use arena::PinArena;
+    /// use core::marker::PhantomPinned;
+    ///
+    /// struct Node {
+    ///     value: i32,
+    ///     _pinned: PhantomPinned,
+    /// }
+    ///
+    /// let mut arena: PinArena<_> = PinArena::new();
+    /// let index = arena.insert(Node { value: 1, _pinned: PhantomPinned });
+    ///
+    /// let node = arena.get_pin_mut(index).unwrap();
+    /// // SAFETY: `value` isn't structurally pinned.
+    /// unsafe { node.get_unchecked_mut().value += 1 };
+    /// # assert_eq!(arena.get(index).unwrap().value, 2);
+    /// ```
+    pub fn get_pin_mut(&mut self, index: Index) -> Option<Pin<&mut T>> {
+        match self.entry_mut(index.slot()) {
+            // SAFETY: Items are never moved until they're dropped in place.
+            Some(Entry::Occupied { generation, item }) if *generation == index.generation => {
+                Some(unsafe { Pin::new_unchecked(item) })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Remove every item, dropping them in place.
+    ///
+    /// Allocated chunks are kept, and indices handed out before are invalidated.
+    pub fn clear(&mut self) {
+        self.free_head = None;
+        for slot in (0..self.slots()).rev() {
+            let free_head = self.free_head;
+            let entry = self.entry_mut(slot).unwrap();
+            let generation = match &*entry {
+                Entry::Occupied { generation, .. } | Entry::Reserved { generation } => generation.saturating_add(1),
+                Entry::Vacant { generation, .. } => *generation,
+            };
+            *entry = Entry::Vacant {
+                generation,
+                next: free_head,
+            };
+            self.free_head = Some(slot);
+        }
+        self.count = 0;
+    }
+
+    /// Iterate the items in slot order.
+    pub fn iter(&self) -> Iter<'_, T, A> {
+        Iter {
+            inner: self.iter_indexed(),
+        }
+    }
+
+    /// Iterate the items in slot order, together with their indices.
+    pub fn iter_indexed(&self) -> IterIndexed<'_, T, A> {
+        IterIndexed {
+            inner: self.chunks.iter().flatten().enumerate(),
+            remaining: self.count,
+        }
+    }
+
+    /// Number of slots handed out so far.
+    fn slots(&self) -> usize {
+        self.chunks
+            .last()
+            .map_or(0, |chunk| (self.chunks.len() - 1) * CHUNK + chunk.len())
+    }
+
+    #[inline]
+    fn entry(&self, slot: usize) -> Option<&Entry<T>> {
+        self.chunks.get(slot / CHUNK)?.get(slot % CHUNK)
+    }
+
+    #[inline]
+    fn entry_mut(&mut self, slot: usize) -> Option<&mut Entry<T>> {
+        self.chunks.get_mut(slot / CHUNK)?.get_mut(slot % CHUNK)
+    }
+}
+
+impl<T: Unpin, const CHUNK: usize, A: ChunkAllocator> PinArena<T, CHUNK, A> {
+    /// Return a mutable reference to the item at the given `index`.
+    ///
+    /// Only available for [`Unpin`] items, see [`PinArena::get_pin_mut`].
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        self.get_pin_mut(index).map(Pin::into_inner)
+    }
+
+    /// Remove and return the item at the given `index`.
+    ///
+    /// Only available for [`Unpin`] items, which may be moved out.
+    pub fn take(&mut self, index: Index) -> Option<T> {
+        self.take_unpinned(index)
+    }
+}
+
+impl<T, const CHUNK: usize, A: ChunkAllocator> PinArena<T, CHUNK, A> {
+    /// Mutable access to the item at `index`, for callers that never pin it.
+    fn get_unpinned_mut(&mut self, index: Index) -> Option<&mut T> {
+        match self.entry_mut(index.slot()) {
+            Some(Entry::Occupied { generation, item }) if *generation == index.generation => Some(item),
+            _ => None,
+        }
+    }
+
+    /// Move the item at `index` out, for callers that never pin it.
+    fn take_unpinned(&mut self, index: Index) -> Option<T> {
+        if !self.contains(index) {
+            return None;
+        }
+
+        let slot = index.slot();
+        let vacant = Entry::Vacant {
+            generation: index.generation.saturating_add(1),
+            next: self.free_head,
+        };
+        let item = core::mem::replace(self.entry_mut(slot).unwrap(), vacant);
+        self.free_head = Some(slot);
+        self.count -= 1;
+        Some(item.unwrap_occupied().1)
+    }
+}
+
+impl<T, const CHUNK: usize> Default for PinArena<T, CHUNK> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Arena stored in chunks of `CHUNK` slots, for arenas holding many items.
+///
+/// Growing allocates another chunk instead of reallocating the storage,
+/// so it never copies the items over, and doesn't cause the latency
+/// spike of reallocating one large block. Lookups go through an extra
+/// indirection to find the chunk.
+///
+/// It shares the storage of [`PinArena`], but makes no pinning promise,
+/// so items don't need to be [`Unpin`] to be mutated or taken out. The
+/// accessors have the same names and signatures as those of
+/// [`Arena`](crate::Arena), which makes switching between the two a
+/// change of type. Beyond them, it lacks the rest of the `Arena` API:
+/// compaction, secondary map links, watchpoints, strict mode, memory
+/// budgets, tag checkers, snapshots and serialization.
+///
+/// ```
+/// # use arena::ChunkedArena;
+/// let mut arena: ChunkedArena<_> = ChunkedArena::new();
+/// let indices: Vec<_> = (0..10_000).map(|n| arena.insert(n)).collect();
+/// assert_eq!(arena.capacity(), 3 * 4096);
+///
+/// arena.retain(|_, n| n % 2 == 0);
+/// for n in arena.iter_mut() {
+///     *n /= 2;
+/// }
+/// assert_eq!(arena.take(indices[9998]), Some(4999));
+/// # assert_eq!(arena.len(), 4999);
+/// ```
+#[derive(Debug)]
+pub struct ChunkedArena<T, const CHUNK: usize = 4096> {
+    /// Never hands out pinned references, so items are free to move.
+    inner: PinArena<T, CHUNK>,
+}
+
+impl<T, const CHUNK: usize> ChunkedArena<T, CHUNK> {
+    /// Create an empty arena. No chunk is allocated until the first insertion.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `CHUNK` is zero.
+    pub fn new() -> Self {
+        Self { inner: PinArena::new() }
+    }
+
+    /// Number of slots in the chunks allocated so far.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Insert the item into the first free slot, allocating a new chunk if there is none.
+    pub fn insert(&mut self, item: T) -> Index {
+        self.inner.insert(item)
+    }
+
+    /// Remove the item at the given `index`, if it's still live.
+    pub fn remove(&mut self, index: Index) {
+        self.inner.remove(index);
+    }
+
+    /// Remove and return the item at the given `index`.
+    pub fn take(&mut self, index: Index) -> Option<T> {
+        self.inner.take_unpinned(index)
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        self.inner.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        self.inner.get_unpinned_mut(index)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.inner.contains(index)
+    }
+
+    /// Remove every item. Allocated chunks are kept, and indices handed
+    /// out before are invalidated.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    /// Retain only the items for which the predicate returns `true`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Index, &T) -> bool,
+    {
+        self.retain_mut(|index, item| f(index, item));
+    }
+
+    /// Retain only the items for which the predicate returns `true`,
+    /// passing each item mutably.
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Index, &mut T) -> bool,
+    {
+        for slot in 0..self.inner.slots() {
+            let index = match self.inner.entry_mut(slot) {
+                Some(Entry::Occupied { generation, item }) => {
+                    let index = Index::new(slot, *generation);
+                    if f(index, item) {
+                        continue;
+                    }
+                    index
+                }
+                _ => continue,
+            };
+            self.inner.remove(index);
+        }
+    }
+
+    /// Iterate the items in slot order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.inner.iter()
+    }
+
+    /// Mutably iterate the items in slot order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.iter_indexed_mut(),
+        }
+    }
+
+    /// Iterate the items in slot order, together with their indices.
+    pub fn iter_indexed(&self) -> IterIndexed<'_, T> {
+        self.inner.iter_indexed()
+    }
+
+    /// Mutably iterate the items in slot order, together with their indices.
+    pub fn iter_indexed_mut(&mut self) -> IterIndexedMut<'_, T> {
+        IterIndexedMut {
+            remaining: self.inner.count,
+            inner: self.inner.chunks.iter_mut().flatten().enumerate(),
+        }
+    }
+
+    /// Iterate the indices of the items in slot order.
+    pub fn keys(&self) -> impl Iterator<Item = Index> + '_ {
+        self.iter_indexed().map(|(index, _)| index)
+    }
+}
+
+impl<T, const CHUNK: usize> Default for ChunkedArena<T, CHUNK> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a, T, A: ChunkAllocator = Global> {
+    inner: IterIndexed<'a, T, A>,
+}
+
+impl<'a, T, A: ChunkAllocator> Iterator for Iter<'a, T, A> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, item)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, A: ChunkAllocator> ExactSizeIterator for Iter<'_, T, A> {}
+
+#[derive(Debug)]
+pub struct IterIndexed<'a, T, A: ChunkAllocator = Global> {
+    inner: Enumerate<Flatten<SliceIter<'a, Chunk<T, A>>>>,
+    remaining: usize,
+}
+
+impl<'a, T, A: ChunkAllocator> Iterator for IterIndexed<'a, T, A> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        for (slot, entry) in self.inner.by_ref() {
+            if let Entry::Occupied { generation, item } = entry {
+                self.remaining -= 1;
+                return Some((Index::new(slot, *generation), item));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, A: ChunkAllocator> ExactSizeIterator for IterIndexed<'_, T, A> {}
+
+#[derive(Debug)]
+pub struct IterMut<'a, T> {
+    inner: IterIndexedMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, item)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+#[derive(Debug)]
+pub struct IterIndexedMut<'a, T> {
+    inner: Enumerate<Flatten<SliceIterMut<'a, Chunk<T, Global>>>>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for IterIndexedMut<'a, T> {
+    type Item = (Index, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        for (slot, entry) in self.inner.by_ref() {
+            if let Entry::Occupied { generation, item } = entry {
+                self.remaining -= 1;
+                return Some((Index::new(slot, *generation), item));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for IterIndexedMut<'_, T> {}
@@ -0,0 +1,276 @@
+//! Reference counted handles to items, freed when the last one is dropped.
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::{Cell, Ref, RefCell, RefMut};
+use core::fmt;
+
+use crate::{Arena, Index};
+
+/// Arena handing out reference counted [`Strong`] and [`Weak`] handles,
+/// in place of `Rc<RefCell<T>>`.
+///
+/// An item is removed when its last strong handle is dropped. Weak handles
+/// don't keep their item alive, and only [upgrade](Weak::upgrade) while
+/// it's still in the arena. Handles share the arena's storage, which lives
+/// until the arena and every handle are dropped.
+///
+/// Items are borrowed through their handles, and follow the rules of a
+/// single `RefCell` around the whole arena: any number of items can be
+/// borrowed at once, or a single item mutably. Handles can be cloned,
+/// upgraded and dropped while items are borrowed. An item whose last
+/// strong handle is dropped during a borrow is removed once the arena
+/// is no longer borrowed.
+///
+/// ```
+/// # use arena::RcArena;
+/// # use arena::rc::Strong;
+/// struct Node {
+///     name: &'static str,
+///     children: Vec<Strong<Node>>,
+/// }
+///
+/// let arena = RcArena::new();
+/// let leaf = arena.insert(Node { name: "leaf", children: vec![] });
+/// let root = arena.insert(Node { name: "root", children: vec![leaf.clone()] });
+///
+/// let weak = leaf.downgrade();
+/// drop(leaf);
+/// assert_eq!(weak.upgrade().unwrap().borrow().name, "leaf");
+///
+/// drop(root);
+/// assert!(weak.upgrade().is_none());
+/// assert!(arena.is_empty());
+/// ```
+pub struct RcArena<T> {
+    shared: Rc<Shared<T>>,
+}
+
+struct Shared<T> {
+    arena: RefCell<Arena<T>>,
+    /// Index counted in each slot, and its number of strong handles.
+    counts: RefCell<Vec<(Index, usize)>>,
+    /// Items without strong handles, waiting for the arena to be released.
+    pending: RefCell<Vec<Index>>,
+    live: Cell<usize>,
+}
+
+impl<T> RcArena<T> {
+    pub fn new() -> Self {
+        Self {
+            shared: Rc::new(Shared {
+                arena: RefCell::new(Arena::new()),
+                counts: RefCell::new(Vec::new()),
+                pending: RefCell::new(Vec::new()),
+                live: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Insert the item, and return the first strong handle to it.
+    ///
+    /// # Panic
+    ///
+    /// Panics if an item is borrowed.
+    pub fn insert(&self, item: T) -> Strong<T> {
+        self.shared.release_pending();
+        let index = self.shared.arena.borrow_mut().insert(item);
+
+        let mut counts = self.shared.counts.borrow_mut();
+        if index.slot() == counts.len() {
+            counts.push((index, 1));
+        } else {
+            counts[index.slot()] = (index, 1);
+        }
+        self.shared.live.set(self.shared.live.get() + 1);
+
+        Strong {
+            shared: self.shared.clone(),
+            index,
+        }
+    }
+
+    /// Number of items with strong handles.
+    pub fn len(&self) -> usize {
+        self.shared.live.get()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Shared<T> {
+    /// Number of strong handles to `index`, if it still has any.
+    fn strong_count(&self, index: Index) -> Option<usize> {
+        match self.counts.borrow().get(index.slot()) {
+            Some(&(counted, strong)) if counted == index && strong > 0 => Some(strong),
+            _ => None,
+        }
+    }
+
+    fn add_strong(&self, index: Index) {
+        self.counts.borrow_mut()[index.slot()].1 += 1;
+    }
+
+    /// Remove the items without strong handles, unless the arena is borrowed.
+    fn release_pending(&self) {
+        loop {
+            let Ok(mut arena) = self.arena.try_borrow_mut() else {
+                return;
+            };
+            let Some(index) = self.pending.borrow_mut().pop() else {
+                return;
+            };
+            let item = arena.take(index);
+
+            // The item may hold handles of its own, which release theirs.
+            drop(arena);
+            drop(item);
+        }
+    }
+}
+
+impl<T> Clone for RcArena<T> {
+    /// Another handle to the same arena.
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Default for RcArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RcArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.shared.arena.try_borrow() {
+            Ok(arena) => f.debug_tuple("RcArena").field(&*arena).finish(),
+            Err(_) => f.debug_tuple("RcArena").field(&"<borrowed>").finish(),
+        }
+    }
+}
+
+/// Handle keeping an item of an [`RcArena`] alive.
+pub struct Strong<T> {
+    shared: Rc<Shared<T>>,
+    index: Index,
+}
+
+impl<T> Strong<T> {
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    /// Immutably borrow the item.
+    ///
+    /// # Panic
+    ///
+    /// Panics if an item is mutably borrowed.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref::map(self.shared.arena.borrow(), |arena| &arena[self.index])
+    }
+
+    /// Mutably borrow the item.
+    ///
+    /// # Panic
+    ///
+    /// Panics if any item is borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        RefMut::map(self.shared.arena.borrow_mut(), |arena| &mut arena[self.index])
+    }
+
+    /// Create a weak handle to the item.
+    pub fn downgrade(&self) -> Weak<T> {
+        Weak {
+            shared: self.shared.clone(),
+            index: self.index,
+        }
+    }
+
+    /// Number of strong handles to the item, including this one.
+    pub fn strong_count(&self) -> usize {
+        self.shared.strong_count(self.index).unwrap_or(0)
+    }
+}
+
+impl<T> Clone for Strong<T> {
+    fn clone(&self) -> Self {
+        self.shared.add_strong(self.index);
+        Self {
+            shared: self.shared.clone(),
+            index: self.index,
+        }
+    }
+}
+
+impl<T> Drop for Strong<T> {
+    fn drop(&mut self) {
+        let mut counts = self.shared.counts.borrow_mut();
+        let strong = &mut counts[self.index.slot()].1;
+        *strong -= 1;
+        if *strong > 0 {
+            return;
+        }
+        drop(counts);
+
+        self.shared.live.set(self.shared.live.get() - 1);
+        self.shared.pending.borrow_mut().push(self.index);
+        self.shared.release_pending();
+    }
+}
+
+impl<T> PartialEq for Strong<T> {
+    /// Handles are equal if they point to the same item.
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && Rc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<T> Eq for Strong<T> {}
+
+impl<T> fmt::Debug for Strong<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Strong").field(&self.index).finish()
+    }
+}
+
+/// Handle to an item of an [`RcArena`], which doesn't keep it alive.
+pub struct Weak<T> {
+    shared: Rc<Shared<T>>,
+    index: Index,
+}
+
+impl<T> Weak<T> {
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    /// Create a strong handle to the item, if it still has one.
+    pub fn upgrade(&self) -> Option<Strong<T>> {
+        self.shared.strong_count(self.index)?;
+        self.shared.add_strong(self.index);
+        Some(Strong {
+            shared: self.shared.clone(),
+            index: self.index,
+        })
+    }
+}
+
+impl<T> Clone for Weak<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            index: self.index,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Weak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Weak").field(&self.index).finish()
+    }
+}
@@ -0,0 +1,92 @@
+//! Read-only handles to an arena, for sharing between threads.
+use crate::{Arena, Index, Iter, IterIndexed, Keys};
+
+/// Read-only handle to an [`Arena`], created by [`Arena::freeze`].
+///
+/// The handle is `Copy`, and `Send` and `Sync` whenever `T` is `Sync`, so
+/// it can be handed to any number of threads that read the arena at the
+/// same time without locking. The arena can't be modified until every
+/// handle is gone.
+///
+/// Reads through the handle skip [watchpoints](Arena::watch) and the
+/// [stale access handler](Arena::set_stale_handler), which are meant for
+/// debugging the owning thread. Tags are still checked.
+pub struct ArenaReader<'a, T> {
+    arena: &'a Arena<T>,
+}
+
+impl<T> Arena<T> {
+    /// Freeze the arena into a read-only handle that can be shared with
+    /// other threads.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let indices: Vec<_> = (0..8).map(|n| arena.insert(n)).collect();
+    ///
+    /// let reader = arena.freeze();
+    /// let sums: Vec<i32> = std::thread::scope(|scope| {
+    ///     let workers: Vec<_> = indices
+    ///         .chunks(4)
+    ///         .map(|chunk| scope.spawn(move || chunk.iter().map(|&index| reader.get(index).unwrap()).sum()))
+    ///         .collect();
+    ///     workers.into_iter().map(|worker| worker.join().unwrap()).collect()
+    /// });
+    /// assert_eq!(sums, [6, 22]);
+    /// ```
+    pub fn freeze(&self) -> ArenaReader<'_, T> {
+        ArenaReader { arena: self }
+    }
+}
+
+impl<'a, T> ArenaReader<'a, T> {
+    pub fn get(&self, index: Index) -> Option<&'a T> {
+        self.arena.lookup(index)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Iterate the items in slot order.
+    pub fn iter(&self) -> Iter<'a, T> {
+        self.arena.iter()
+    }
+
+    /// Iterate the items in slot order, together with their indices.
+    pub fn iter_indexed(&self) -> IterIndexed<'a, T> {
+        self.arena.iter_indexed()
+    }
+
+    /// Iterate the indices of the items in slot order.
+    pub fn keys(&self) -> Keys<'a, T> {
+        self.arena.keys()
+    }
+
+    /// The frozen arena.
+    pub fn as_arena(&self) -> &'a Arena<T> {
+        self.arena
+    }
+}
+
+impl<T> Clone for ArenaReader<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaReader<'_, T> {}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for ArenaReader<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ArenaReader").field(self.arena).finish()
+    }
+}
@@ -0,0 +1,214 @@
+//! Tracking of the most recent insertions.
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::iter::Enumerate;
+use core::slice::Iter as SliceIter;
+
+use crate::checks::Stamp;
+use crate::{Arena, Entry, Index};
+
+/// Most recently inserted indices, oldest first.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Recent {
+    last: Option<Index>,
+    history: VecDeque<Index>,
+    limit: usize,
+    births: Option<Births>,
+}
+
+/// Epoch at which the item in each slot was inserted.
+#[derive(Debug, Default, Clone)]
+struct Births {
+    clock: u64,
+    slots: Vec<u64>,
+}
+
+impl Births {
+    #[inline]
+    fn get(&self, slot: usize) -> u64 {
+        self.slots.get(slot).copied().unwrap_or(0)
+    }
+
+    #[inline]
+    fn set(&mut self, slot: usize, epoch: u64) {
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, 0);
+        }
+        self.slots[slot] = epoch;
+    }
+}
+
+impl Recent {
+    #[inline]
+    pub(crate) fn record(&mut self, index: Index) {
+        self.last = Some(index);
+
+        if self.limit > 0 {
+            if self.history.len() == self.limit {
+                self.history.pop_front();
+            }
+            self.history.push_back(index);
+        }
+
+        self.stamp(index.slot());
+    }
+
+    /// Mark the item in `slot` as inserted in a new epoch.
+    #[inline]
+    pub(crate) fn stamp(&mut self, slot: usize) {
+        if let Some(births) = &mut self.births {
+            births.clock += 1;
+            births.set(slot, births.clock);
+        }
+    }
+
+    /// Carry the epochs of moved items over to their new slots.
+    pub(crate) fn relocate(&mut self, moves: &[(Index, Index)]) {
+        if let Some(births) = &mut self.births {
+            // Read them all first, as items may have moved into each other's slots.
+            let epochs: Vec<u64> = moves.iter().map(|(old, _)| births.get(old.slot())).collect();
+            for ((_, new), epoch) in moves.iter().zip(epochs) {
+                births.set(new.slot(), epoch);
+            }
+        }
+    }
+}
+
+/// Point in an arena's history of insertions, see [`Arena::epoch`].
+///
+/// Epochs of the same arena are ordered, later ones comparing greater.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch(u64);
+
+impl<T> Arena<T> {
+    /// The index of the most recently inserted item.
+    ///
+    /// The index may have gone stale since.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// assert_eq!(arena.last_inserted(), None);
+    /// let index = arena.insert("Foo");
+    /// assert_eq!(arena.last_inserted(), Some(index));
+    /// ```
+    pub fn last_inserted(&self) -> Option<Index> {
+        self.recent.last
+    }
+
+    /// Keep a history of the last `n` inserted indices, available
+    /// through [`Arena::recent_insertions`].
+    ///
+    /// Setting `n` to zero disables the history.
+    pub fn track_recent_insertions(&mut self, n: usize) {
+        let recent = &mut self.recent;
+        recent.limit = n;
+
+        while recent.history.len() > n {
+            recent.history.pop_front();
+        }
+    }
+
+    /// Iterate the recently inserted indices, most recent first.
+    ///
+    /// Indices that have since gone stale are included.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// arena.track_recent_insertions(2);
+    ///
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// let index2 = arena.insert("Baz");
+    ///
+    /// let recent: Vec<_> = arena.recent_insertions().collect();
+    /// assert_eq!(recent, [index2, index1]);
+    /// ```
+    pub fn recent_insertions(&self) -> impl Iterator<Item = Index> + '_ {
+        self.recent.history.iter().rev().copied()
+    }
+
+    /// Start recording the [`Epoch`] each item is inserted in, for
+    /// [`Arena::iter_since`].
+    ///
+    /// Items present when tracking starts count as inserted before any
+    /// epoch. Calling this when tracking is already enabled has no effect.
+    pub fn track_epochs(&mut self) {
+        if self.recent.births.is_none() {
+            self.recent.births = Some(Births::default());
+        }
+    }
+
+    /// Stop recording epochs, and discard the recorded ones.
+    pub fn untrack_epochs(&mut self) {
+        self.recent.births = None;
+    }
+
+    pub fn is_tracking_epochs(&self) -> bool {
+        self.recent.births.is_some()
+    }
+
+    /// The current epoch, a watermark for the items inserted so far.
+    ///
+    /// Without [epoch tracking](Arena::track_epochs), this is always
+    /// the same epoch.
+    pub fn epoch(&self) -> Epoch {
+        Epoch(self.recent.births.as_ref().map_or(0, |births| births.clock))
+    }
+
+    /// Iterate the items inserted after `epoch` that are still present.
+    ///
+    /// Replaced items count as inserted, while items moved by
+    /// [compaction](Arena::compact) keep their epoch. Yields nothing when
+    /// epochs are not tracked.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// arena.track_epochs();
+    /// arena.insert("Foo");
+    ///
+    /// let watermark = arena.epoch();
+    /// let index1 = arena.insert("Bar");
+    /// let index2 = arena.insert("Baz");
+    /// arena.remove(index2);
+    ///
+    /// let spawned: Vec<_> = arena.iter_since(watermark).collect();
+    /// assert_eq!(spawned, [(index1, &"Bar")]);
+    /// ```
+    pub fn iter_since(&self, epoch: Epoch) -> InsertedSince<'_, T> {
+        InsertedSince {
+            inner: self.data.iter().enumerate(),
+            births: self.recent.births.as_ref(),
+            since: epoch.0,
+            stamp: self.stamper(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InsertedSince<'a, T> {
+    inner: Enumerate<SliceIter<'a, Entry<T>>>,
+    births: Option<&'a Births>,
+    since: u64,
+    stamp: Stamp,
+}
+
+impl<'a, T> Iterator for InsertedSince<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let births = self.births?;
+
+        for (slot, entry) in self.inner.by_ref() {
+            if let Entry::Occupied { generation, item } = entry {
+                if births.get(slot) > self.since {
+                    return Some((self.stamp.index(slot, *generation), item));
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,156 @@
+//! Translation of indices to items that moved, between or within arenas.
+use alloc::collections::btree_map::{self, BTreeMap};
+use core::num::NonZeroUsize;
+
+use crate::Index;
+
+/// Mapping from the old indices of moved items to their new ones.
+///
+/// Lookups validate the old index's generation, so an index that was
+/// already stale before the move doesn't translate.
+///
+/// Returned by [`Arena::compact_with_remap`](crate::Arena::compact_with_remap),
+/// [`Arena::append`](crate::Arena::append), [`Arena::split_off_if`](crate::Arena::split_off_if)
+/// and [`Arena::sort_by`](crate::Arena::sort_by), and when deserializing
+/// [`Compacted`](crate::Compacted) with the `serde` feature, under which the
+/// remap itself can be serialized too.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IndexRemap {
+    /// Old generation and new index, by old slot. Kept sparse, since
+    /// the old slots can be far apart, or come from untrusted input.
+    slots: BTreeMap<usize, (NonZeroUsize, Index)>,
+}
+
+impl IndexRemap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The new index of the item that was at `old`.
+    ///
+    /// The new index keeps the [tag](Index::tag) of the old one.
+    pub fn translate(&self, old: Index) -> Option<Index> {
+        match self.slots.get(&old.slot()) {
+            Some((generation, new)) if *generation == old.generation => Some(new.with_tag(old.tag())),
+            _ => None,
+        }
+    }
+
+    /// Translate a stored handle in place.
+    ///
+    /// Returns `false`, leaving the handle untouched, if it has no
+    /// translation.
+    pub fn patch(&self, index: &mut Index) -> bool {
+        match self.translate(*index) {
+            Some(new) => {
+                *index = new;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of translated indices.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Iterate the old and new indices of the moved items, in order of
+    /// their old slots.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.slots.iter(),
+        }
+    }
+
+    /// Combine this remap with `next`, which was produced after it, into
+    /// one that translates handles across both moves at once.
+    ///
+    /// Items moved by this remap and not by `next` keep their translation.
+    /// Indices translated by `next` alone, such as those handed out
+    /// between the two moves, translate too, unless their slot is also a
+    /// source of this remap.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// let index2 = arena.insert("Baz");
+    ///
+    /// arena.remove(index0);
+    /// let first = arena.compact_with_remap();
+    /// arena.remove(first.translate(index1).unwrap());
+    /// let second = arena.compact_with_remap();
+    ///
+    /// let remap = first.then(&second);
+    /// assert_eq!(arena.get(remap.translate(index2).unwrap()), Some(&"Baz"));
+    /// assert_eq!(remap.len(), 2);
+    /// ```
+    pub fn then(&self, next: &IndexRemap) -> IndexRemap {
+        let mut remap = IndexRemap::new();
+        for (old, new) in next {
+            remap.insert(old, new);
+        }
+        for (old, new) in self {
+            remap.insert(old, next.translate(new).unwrap_or(new));
+        }
+        remap
+    }
+
+    /// Record that the item at `old` moved to `new`.
+    ///
+    /// Replaces any translation of an index in the same slot.
+    pub fn insert(&mut self, old: Index, new: Index) {
+        self.slots.insert(old.slot(), (old.generation, new));
+    }
+}
+
+impl<'a> IntoIterator for &'a IndexRemap {
+    type Item = (Index, Index);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<(Index, Index)> for IndexRemap {
+    fn from_iter<I: IntoIterator<Item = (Index, Index)>>(iter: I) -> Self {
+        let mut remap = IndexRemap::new();
+        remap.extend(iter);
+        remap
+    }
+}
+
+impl Extend<(Index, Index)> for IndexRemap {
+    fn extend<I: IntoIterator<Item = (Index, Index)>>(&mut self, iter: I) {
+        for (old, new) in iter {
+            self.insert(old, new);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    inner: btree_map::Iter<'a, usize, (NonZeroUsize, Index)>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = (Index, Index);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (slot, (generation, new)) = self.inner.next()?;
+        Some((Index::new(*slot, *generation), *new))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
@@ -0,0 +1,106 @@
+//! Two-phase insertion, for handles that are needed before their items exist.
+use crate::{Arena, ChangeKind, Entry, Index};
+
+impl<T> Arena<T> {
+    /// Reserve a slot for an item that will be provided later with [`Arena::fill`].
+    ///
+    /// Until it's filled, the returned index doesn't resolve to anything, and
+    /// the slot isn't handed out by insertions. Useful for building cyclic
+    /// structures, where items must refer to each other.
+    ///
+    /// ```
+    /// # use arena::{Arena, Index};
+    /// struct Node {
+    ///     next: Index,
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.reserve_index();
+    /// let b = arena.insert(Node { next: a });
+    /// assert!(arena.get(a).is_none());
+    ///
+    /// arena.fill(a, Node { next: b });
+    /// assert_eq!(arena.get(a).unwrap().next, b);
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if the slot would exceed the arena's [memory budget](Arena::set_memory_budget).
+    pub fn reserve_index(&mut self) -> Index {
+        assert!(
+            self.reserve_budget_bytes(0, self.free_head.is_none()),
+            "arena memory budget exceeded"
+        );
+
+        let index = self.next_index();
+        let slot = index.slot();
+        let reserved = Entry::Reserved {
+            generation: index.generation,
+        };
+
+        if slot == self.data.len() {
+            assert!(slot < Index::SLOT_MASK, "arena exceeded the maximum number of slots");
+            let capacity = self.data.capacity();
+            self.data.push(reserved);
+            self.metric_grew(capacity);
+        } else {
+            self.unlink_free(slot);
+            self.data[slot] = reserved;
+        }
+
+        index
+    }
+
+    /// Check whether `index` is a reservation waiting to be filled.
+    pub fn is_reserved(&self, index: Index) -> bool {
+        matches!(
+            self.data.get(index.slot()),
+            Some(Entry::Reserved { generation }) if *generation == index.generation
+        )
+    }
+
+    /// Store the item in a slot reserved by [`Arena::reserve_index`].
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is not a pending reservation, or the item would
+    /// exceed the arena's [memory budget](Arena::set_memory_budget) or
+    /// [limit](Arena::with_limit).
+    pub fn fill(&mut self, index: Index, item: T) {
+        assert!(self.is_reserved(index), "index is not a pending reservation");
+        self.assert_below_limit();
+        assert!(self.reserve_budget(&item, false), "arena memory budget exceeded");
+        assert!(self.is_reserved(index), "budget handler released the reservation");
+
+        self.occupy_reserved(index, item);
+    }
+
+    /// Place the item in the reserved slot of `index`.
+    pub(crate) fn occupy_reserved(&mut self, index: Index, item: T) {
+        let slot = index.slot();
+        self.account_added(&item);
+        self.data[slot] = Entry::Occupied {
+            generation: index.generation,
+            item,
+        };
+        self.count += 1;
+        self.touch(slot);
+        let index = self.stamp(Index::new(slot, index.generation));
+        self.recent.record(index);
+        self.record_change(index, ChangeKind::Inserted);
+        self.metric_inserted(index);
+    }
+
+    /// Release a reservation without filling it, making its slot free again.
+    ///
+    /// Returns `false` if `index` is not a pending reservation.
+    pub fn unreserve(&mut self, index: Index) -> bool {
+        if !self.is_reserved(index) {
+            return false;
+        }
+
+        let generation = self.next_generation(index.slot(), index.generation);
+        self.vacate(index.slot(), generation);
+        true
+    }
+}
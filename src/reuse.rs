@@ -0,0 +1,100 @@
+//! Order in which vacant slots are reused.
+use core::num::NonZeroUsize;
+
+use crate::{Arena, Entry};
+
+/// Order in which freed slots are handed out again, chosen with
+/// [`Arena::with_reuse_policy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReusePolicy {
+    /// Reuse the most recently freed slot first. Keeps the slots in use
+    /// warm in the cache, and makes stale index bugs surface quickly.
+    #[default]
+    Lifo,
+    /// Reuse the least recently freed slot first. A slot sits in the free
+    /// list for as long as possible before being recycled, so a stale index
+    /// is less likely to meet the slot's next item during its lifetime.
+    Fifo,
+}
+
+impl<T> Arena<T> {
+    /// Create a new arena with the given slot reuse policy.
+    ///
+    /// ```
+    /// # use arena::{Arena, ReusePolicy};
+    /// let mut arena = Arena::with_reuse_policy(ReusePolicy::Fifo);
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    /// arena.remove(index1);
+    ///
+    /// // Items are iterated in slot order.
+    /// arena.insert("Baz");
+    /// arena.insert("Qux");
+    /// assert!(arena.iter().eq(&["Baz", "Qux"]));
+    /// ```
+    pub fn with_reuse_policy(policy: ReusePolicy) -> Self {
+        let mut arena = Self::new();
+        arena.reuse = policy;
+        arena
+    }
+
+    pub fn reuse_policy(&self) -> ReusePolicy {
+        self.reuse
+    }
+
+    /// Change the slot reuse policy. Only affects slots freed from now on.
+    pub fn set_reuse_policy(&mut self, policy: ReusePolicy) {
+        self.reuse = policy;
+    }
+
+    /// Turn the slot into a vacant one with `generation`, and add it
+    /// to the free list unless it's retired.
+    pub(crate) fn vacate(&mut self, slot: usize, generation: NonZeroUsize) -> Entry<T> {
+        let vacant = Entry::Vacant { generation, next: None };
+        let original = core::mem::replace(&mut self.data[slot], vacant);
+        if self.is_retired(&self.data[slot]) {
+            return original;
+        }
+
+        match self.reuse {
+            ReusePolicy::Lifo => {
+                self.data[slot].set_next_free(self.free_head);
+                self.free_head = Some(slot);
+            }
+            ReusePolicy::Fifo => {
+                match self.free_tail(slot) {
+                    Some(tail) => self.data[tail].set_next_free(Some(slot)),
+                    None => self.free_head = Some(slot),
+                }
+                self.free_tail = Some(slot);
+            }
+        }
+        original
+    }
+
+    /// Last slot of the free list, other than the newly vacated `slot`.
+    ///
+    /// Every vacant slot that isn't retired is on the free list, so a
+    /// vacant slot without a successor must be its tail. The remembered
+    /// tail is checked for that, and the list only walked if it's outdated.
+    fn free_tail(&self, slot: usize) -> Option<usize> {
+        let is_tail = |tail: usize| {
+            tail != slot
+                && matches!(&self.data[tail], entry @ Entry::Vacant { next: None, .. } if !self.is_retired(entry))
+        };
+
+        match self.free_tail {
+            Some(tail) if tail < self.data.len() && is_tail(tail) => Some(tail),
+            _ => {
+                let mut tail = None;
+                let mut cursor = self.free_head;
+                while let Some(current) = cursor {
+                    tail = Some(current);
+                    cursor = self.data[current].next_free();
+                }
+                tail
+            }
+        }
+    }
+}
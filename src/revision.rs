@@ -0,0 +1,138 @@
+//! Per-slot revision counters for change detection.
+use alloc::vec::Vec;
+use core::iter::Enumerate;
+use core::slice::Iter as SliceIter;
+
+use crate::checks::Stamp;
+use crate::{Arena, Entry, Index};
+
+/// Revision of each slot, stamped from a clock that advances on every mutation.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Revisions {
+    clock: u64,
+    slots: Vec<u64>,
+}
+
+impl Revisions {
+    #[inline]
+    pub(crate) fn touch(&mut self, slot: usize) {
+        self.clock += 1;
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, 0);
+        }
+        self.slots[slot] = self.clock;
+    }
+
+    #[inline]
+    fn get(&self, slot: usize) -> u64 {
+        self.slots.get(slot).copied().unwrap_or(0)
+    }
+}
+
+impl<T> Arena<T> {
+    /// Start recording a revision for every slot.
+    ///
+    /// Once enabled, every insertion, replacement and mutable access through
+    /// the arena's APIs stamps the affected slot with a new, increasing
+    /// revision. Items present when tracking starts have revision `0`.
+    ///
+    /// Calling this when tracking is already enabled has no effect.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(1);
+    /// let index1 = arena.insert(2);
+    /// arena.track_revisions();
+    ///
+    /// let last_seen = arena.current_revision();
+    /// *arena.get_mut(index1).unwrap() += 1;
+    ///
+    /// let changed: Vec<_> = arena.iter_changed_since(last_seen).map(|(index, _)| index).collect();
+    /// assert_eq!(changed, [index1]);
+    /// ```
+    pub fn track_revisions(&mut self) {
+        if self.revisions.is_none() {
+            self.revisions = Some(Revisions::default());
+        }
+    }
+
+    /// Stop recording revisions, and discard the recorded ones.
+    pub fn untrack_revisions(&mut self) {
+        self.revisions = None;
+    }
+
+    pub fn is_tracking_revisions(&self) -> bool {
+        self.revisions.is_some()
+    }
+
+    /// The revision of the most recent mutation, or `0` when tracking is disabled.
+    pub fn current_revision(&self) -> u64 {
+        self.revisions.as_ref().map_or(0, |revisions| revisions.clock)
+    }
+
+    /// The revision of the item at the given `index`.
+    ///
+    /// Returns `None` if the index is stale, or revisions are not tracked.
+    pub fn revision(&self, index: Index) -> Option<u64> {
+        let revisions = self.revisions.as_ref()?;
+        self.lookup(index).map(|_| revisions.get(index.slot()))
+    }
+
+    /// Iterate the items that were inserted or mutated after `revision`.
+    ///
+    /// Yields nothing when revisions are not tracked.
+    pub fn iter_changed_since(&self, revision: u64) -> ChangedSince<'_, T> {
+        ChangedSince {
+            inner: self.data.iter().enumerate(),
+            revisions: self.revisions.as_ref(),
+            since: revision,
+            stamp: self.stamper(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn touch(&mut self, slot: usize) {
+        if let Some(revisions) = &mut self.revisions {
+            revisions.touch(slot);
+        }
+    }
+
+    /// Stamp every occupied slot, ahead of handing out mutable
+    /// access to all items at once.
+    pub(crate) fn touch_occupied(&mut self) {
+        if let Some(revisions) = &mut self.revisions {
+            for (slot, entry) in self.data.iter().enumerate() {
+                if entry.is_occupied() {
+                    revisions.touch(slot);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ChangedSince<'a, T> {
+    inner: Enumerate<SliceIter<'a, Entry<T>>>,
+    revisions: Option<&'a Revisions>,
+    since: u64,
+    stamp: Stamp,
+}
+
+impl<'a, T> Iterator for ChangedSince<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let revisions = self.revisions?;
+
+        for (slot, entry) in self.inner.by_ref() {
+            if let Entry::Occupied { generation, item } = entry {
+                if revisions.get(slot) > self.since {
+                    return Some((self.stamp.index(slot, *generation), item));
+                }
+            }
+        }
+
+        None
+    }
+}
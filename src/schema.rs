@@ -0,0 +1,109 @@
+//! JSON Schema generation, behind the `schemars` feature.
+//!
+//! Describes the serialized form of [`Index`] and [`Arena`]:
+//!
+//! ```json
+//! { "slot": 0, "generation": 1, "tag": 0 }
+//! ```
+//!
+//! ```json
+//! {
+//!   "generation": 1,
+//!   "entries": [null, { "generation": 1, "item": "Foo" }],
+//!   "free": [{ "slot": 0, "generation": 2 }]
+//! }
+//! ```
+//!
+//! Vacant slots are `null` entries, and `free` lists the vacant slots in
+//! the order they will be reused, with the generation of their next item.
+//! Slots handed out by [`Arena::reserve_index`] are listed in the optional
+//! `reserved` array instead. The top-level `generation` is given to slots
+//! appended to storage.
+use std::borrow::Cow;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::{Arena, Index};
+
+impl JsonSchema for Index {
+    fn schema_name() -> Cow<'static, str> {
+        "Index".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        concat!(module_path!(), "::Index").into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "slot": { "type": "integer", "format": "uint", "minimum": 0 },
+                "generation": { "type": "integer", "format": "uint", "minimum": 1 },
+                "tag": { "type": "integer", "format": "uint8", "minimum": 0, "maximum": 255, "default": 0 }
+            },
+            "required": ["slot", "generation"]
+        })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Arena<T> {
+    fn schema_name() -> Cow<'static, str> {
+        format!("Arena_for_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        format!("{}::Arena<{}>", module_path!(), T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let item = generator.subschema_for::<T>();
+
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "generation": { "type": "integer", "format": "uint", "minimum": 1 },
+                "entries": {
+                    "type": "array",
+                    "items": {
+                        "anyOf": [
+                            { "type": "null" },
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "generation": { "type": "integer", "format": "uint", "minimum": 1 },
+                                    "item": item
+                                },
+                                "required": ["generation", "item"]
+                            }
+                        ]
+                    }
+                },
+                "free": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "slot": { "type": "integer", "format": "uint", "minimum": 0 },
+                            "generation": { "type": "integer", "format": "uint", "minimum": 1 }
+                        },
+                        "required": ["slot", "generation"]
+                    }
+                },
+                "reserved": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "slot": { "type": "integer", "format": "uint", "minimum": 0 },
+                            "generation": { "type": "integer", "format": "uint", "minimum": 1 }
+                        },
+                        "required": ["slot", "generation"]
+                    },
+                    "default": []
+                }
+            },
+            "required": ["generation", "entries", "free"]
+        })
+    }
+}
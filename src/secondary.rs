@@ -0,0 +1,485 @@
+//! Secondary storage keyed by the indices of a primary arena.
+use alloc::sync::{Arc, Weak};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::Enumerate;
+use core::num::NonZeroUsize;
+use core::slice::{Iter as SliceIter, IterMut as SliceIterMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::sync::Mutex;
+use crate::{Arena, Index};
+
+/// Map attaching extra data to the items of an [`Arena`], keyed by [`Index`].
+///
+/// Values are stored densely by slot, and lookups validate the
+/// index's generation, so a stale index never resolves to a value
+/// inserted for a newer item in the same slot.
+///
+/// A map can be [linked](Arena::link) to its primary arena, after which
+/// values are dropped automatically when their index is removed from
+/// the arena, and follow their items when they move.
+#[derive(Debug)]
+pub struct SecondaryMap<V> {
+    slots: Vec<Option<(NonZeroUsize, V)>>,
+    count: usize,
+    link: Option<Arc<Link>>,
+}
+
+/// State shared between a linked map and its arena.
+///
+/// The arena keeps the map's keys up to date as items are removed and
+/// moved, while the values stay where they were until the map is next
+/// [flushed](SecondaryMap::flush).
+#[derive(Debug)]
+pub(crate) struct Link {
+    /// Set once the arena removed or moved a key since the last flush.
+    dirty: AtomicBool,
+    keys: Mutex<Keys>,
+}
+
+#[derive(Debug, Default)]
+struct Keys {
+    /// The current index of each key, by slot, with the index it was
+    /// stored under at the last flush.
+    slots: Vec<Option<(NonZeroUsize, Index)>>,
+    count: usize,
+}
+
+impl Keys {
+    fn take(&mut self, index: Index) -> Option<Index> {
+        let entry = self.slots.get_mut(index.slot())?;
+
+        match *entry {
+            Some((generation, stored)) if generation == index.generation => {
+                *entry = None;
+                self.count -= 1;
+                Some(stored)
+            }
+            _ => None,
+        }
+    }
+
+    fn put(&mut self, index: Index, stored: Index) {
+        let slot = index.slot();
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, None);
+        }
+        if self.slots[slot].replace((index.generation, stored)).is_none() {
+            self.count += 1;
+        }
+    }
+
+    /// Apply an event from the arena, and return whether any key changed.
+    fn apply(&mut self, event: &LinkEvent) -> bool {
+        match event {
+            LinkEvent::Removed(index) => self.take(*index).is_some(),
+            LinkEvent::Relocated(old, new) => self.relocate(&[(*old, *new)]),
+            LinkEvent::Swapped(moves) => self.relocate(moves),
+            LinkEvent::Permuted(moves) => self.relocate(moves),
+        }
+    }
+
+    /// Move the keys of items that moved together.
+    fn relocate(&mut self, moves: &[(Index, Index)]) -> bool {
+        // Take all keys out first, so none overwrites another.
+        let stored: Vec<_> = moves.iter().map(|(old, _)| self.take(*old)).collect();
+        let mut changed = false;
+        for ((_, new), stored) in moves.iter().zip(stored) {
+            if let Some(stored) = stored {
+                self.put(*new, stored);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// The index a key currently stored under `index` was stored under at
+    /// the last flush.
+    fn stored(&self, index: Index) -> Option<Index> {
+        match self.slots.get(index.slot()) {
+            Some(Some((generation, stored))) if *generation == index.generation => Some(*stored),
+            _ => None,
+        }
+    }
+}
+
+impl<V> SecondaryMap<V> {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            count: 0,
+            link: None,
+        }
+    }
+
+    /// Insert a value for the given `index`, returning the previous
+    /// value stored for the same index, if any.
+    ///
+    /// A value stored for an older generation of the slot is replaced
+    /// and dropped.
+    pub fn insert(&mut self, index: Index, value: V) -> Option<V> {
+        self.flush();
+
+        let slot = index.slot();
+        if slot >= self.slots.len() {
+            self.slots.resize_with(slot + 1, || None);
+        }
+        if let Some(link) = &self.link {
+            let stored = Index::new(slot, index.generation);
+            link.keys.lock().put(stored, stored);
+        }
+
+        match self.slots[slot].replace((index.generation, value)) {
+            Some((generation, previous)) if generation == index.generation => Some(previous),
+            Some(_) => None,
+            None => {
+                self.count += 1;
+                None
+            }
+        }
+    }
+
+    /// Remove and return the value stored for `index`.
+    pub fn remove(&mut self, index: Index) -> Option<V> {
+        self.flush();
+
+        let value = self.remove_entry(index)?;
+        if let Some(link) = &self.link {
+            link.keys.lock().take(index);
+        }
+        Some(value)
+    }
+
+    pub fn get(&self, index: Index) -> Option<&V> {
+        let index = self.stored(index)?;
+
+        match self.slots.get(index.slot()) {
+            Some(Some((generation, value))) if *generation == index.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut V> {
+        self.flush();
+
+        match self.slots.get_mut(index.slot()) {
+            Some(Some((generation, value))) if *generation == index.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn contains_key(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Number of values in the map.
+    pub fn len(&self) -> usize {
+        match &self.link {
+            Some(link) if link.dirty.load(Ordering::Acquire) => link.keys.lock().count,
+            _ => self.count,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate the stored values together with their indices, in slot order.
+    pub fn iter(&self) -> Iter<'_, V> {
+        let moved = match &self.link {
+            Some(link) if link.dirty.load(Ordering::Acquire) => link.keys.lock().slots.clone(),
+            _ => {
+                return Iter {
+                    inner: IterInner::Stored(self.slots.iter().enumerate()),
+                }
+            }
+        };
+
+        Iter {
+            inner: IterInner::Moved {
+                slots: &self.slots,
+                keys: moved.into_iter().enumerate(),
+            },
+        }
+    }
+
+    /// Mutably iterate the stored values together with their indices.
+    pub fn iter_mut(&mut self) -> IterMut<'_, V> {
+        self.flush();
+
+        IterMut {
+            inner: self.slots.iter_mut().enumerate(),
+        }
+    }
+
+    /// Iterate the items of `arena` that have a value in this map,
+    /// together with their indices and values.
+    ///
+    /// Values whose index no longer resolves in `arena` are skipped.
+    ///
+    /// ```
+    /// # use arena::{Arena, SecondaryMap};
+    /// let mut arena = Arena::new();
+    /// let mut names = SecondaryMap::new();
+    ///
+    /// let index0 = arena.insert([0.0, 0.0]);
+    /// let index1 = arena.insert([1.0, 1.0]);
+    /// names.insert(index1, "player");
+    ///
+    /// let joined: Vec<_> = names.join(&arena).collect();
+    /// assert_eq!(joined, [(index1, &[1.0, 1.0], &"player")]);
+    /// ```
+    pub fn join<'a, T>(&'a self, arena: &'a Arena<T>) -> Join<'a, T, V> {
+        Join {
+            arena,
+            inner: self.iter(),
+        }
+    }
+
+    /// Move the values of a linked map to where the arena moved their
+    /// items, and drop those of removed items.
+    ///
+    /// Reads already see the arena's changes, flushing only reclaims the
+    /// memory of dropped values and makes later reads cheaper. Mutable
+    /// access flushes automatically.
+    pub fn flush(&mut self) {
+        let Some(link) = &self.link else { return };
+        if !link.dirty.load(Ordering::Acquire) {
+            return;
+        }
+
+        let mut keys = link.keys.lock();
+        let mut stored = core::mem::take(&mut self.slots);
+        self.slots.resize_with(keys.slots.len(), || None);
+        self.count = 0;
+        for (slot, key) in keys.slots.iter_mut().enumerate() {
+            let Some((generation, from)) = key else { continue };
+            match stored.get_mut(from.slot()).and_then(Option::take) {
+                Some((_, value)) => {
+                    self.slots[slot] = Some((*generation, value));
+                    self.count += 1;
+                    *from = Index::new(slot, *generation);
+                }
+                None => *key = None,
+            }
+        }
+        keys.count = self.count;
+        link.dirty.store(false, Ordering::Release);
+    }
+
+    fn remove_entry(&mut self, index: Index) -> Option<V> {
+        let entry = self.slots.get_mut(index.slot())?;
+
+        match entry {
+            Some((generation, _)) if *generation == index.generation => {
+                self.count -= 1;
+                entry.take().map(|(_, value)| value)
+            }
+            _ => None,
+        }
+    }
+
+    /// The index the value for `index` is stored under, which differs
+    /// while the arena's changes haven't been flushed.
+    fn stored(&self, index: Index) -> Option<Index> {
+        match &self.link {
+            Some(link) if link.dirty.load(Ordering::Acquire) => link.keys.lock().stored(index),
+            _ => Some(index),
+        }
+    }
+}
+
+impl<V> Default for SecondaryMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> core::ops::Index<Index> for SecondaryMap<V> {
+    type Output = V;
+
+    /// # Panic
+    ///
+    /// Panics if the map holds no value for `index`.
+    fn index(&self, index: Index) -> &V {
+        self.get(index).expect("no value for index")
+    }
+}
+
+impl<V> core::ops::IndexMut<Index> for SecondaryMap<V> {
+    fn index_mut(&mut self, index: Index) -> &mut V {
+        self.get_mut(index).expect("no value for index")
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a, V> {
+    inner: IterInner<'a, V>,
+}
+
+#[derive(Debug)]
+enum IterInner<'a, V> {
+    /// Values in place, as nothing moved since the last flush.
+    Stored(Enumerate<SliceIter<'a, Option<(NonZeroUsize, V)>>>),
+    /// Values looked up through a copy of the keys moved by the arena.
+    Moved {
+        slots: &'a [Option<(NonZeroUsize, V)>],
+        keys: Enumerate<vec::IntoIter<Option<(NonZeroUsize, Index)>>>,
+    },
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Index, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            IterInner::Stored(inner) => {
+                for (slot, entry) in inner.by_ref() {
+                    if let Some((generation, value)) = entry {
+                        return Some((Index::new(slot, *generation), value));
+                    }
+                }
+            }
+            IterInner::Moved { slots, keys } => {
+                for (slot, key) in keys.by_ref() {
+                    let Some((generation, from)) = key else { continue };
+                    if let Some(Some((stored, value))) = slots.get(from.slot()) {
+                        if *stored == from.generation {
+                            return Some((Index::new(slot, generation), value));
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Debug)]
+pub struct IterMut<'a, V> {
+    inner: Enumerate<SliceIterMut<'a, Option<(NonZeroUsize, V)>>>,
+}
+
+impl<'a, V> Iterator for IterMut<'a, V> {
+    type Item = (Index, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (slot, entry) in self.inner.by_ref() {
+            if let Some((generation, value)) = entry {
+                return Some((Index::new(slot, *generation), value));
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Debug)]
+pub struct Join<'a, T, V> {
+    arena: &'a Arena<T>,
+    inner: Iter<'a, V>,
+}
+
+impl<'a, T, V> Iterator for Join<'a, T, V> {
+    type Item = (Index, &'a T, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, value) in self.inner.by_ref() {
+            if let Some(item) = self.arena.lookup(index) {
+                return Some((index, item, value));
+            }
+        }
+
+        None
+    }
+}
+
+impl<T> Arena<T> {
+    /// Link a secondary map to this arena, so values in the map are
+    /// dropped when their index is removed from the arena.
+    ///
+    /// Items relocated by compaction keep their values under the new index,
+    /// which reads of the map see right away. A map can only be linked to
+    /// one arena at a time; linking it again replaces the previous link.
+    /// Clones of the arena are not linked.
+    ///
+    /// ```
+    /// # use arena::{Arena, SecondaryMap};
+    /// let mut arena = Arena::new();
+    /// let mut names = SecondaryMap::new();
+    /// arena.link(&mut names);
+    ///
+    /// let index = arena.insert([0.0, 0.0]);
+    /// names.insert(index, "player");
+    ///
+    /// arena.remove(index);
+    /// assert_eq!(names.get(index), None);
+    /// ```
+    pub fn link<V>(&mut self, map: &mut SecondaryMap<V>) {
+        map.flush();
+
+        let mut keys = Keys::default();
+        for (slot, entry) in map.slots.iter().enumerate() {
+            if let Some((generation, _)) = entry {
+                let index = Index::new(slot, *generation);
+                keys.put(index, index);
+            }
+        }
+
+        let link = Arc::new(Link {
+            dirty: AtomicBool::new(false),
+            keys: Mutex::new(keys),
+        });
+        self.links.inner.push(Arc::downgrade(&link));
+        map.link = Some(link);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum LinkEvent {
+    Removed(Index),
+    Relocated(Index, Index),
+    /// Two items exchanged slots, each given as its old and new index.
+    Swapped([(Index, Index); 2]),
+    /// Items were rearranged at once, each given as its old and new index.
+    Permuted(Vec<(Index, Index)>),
+}
+
+/// The arena's side of its linked secondary maps.
+#[derive(Debug, Default)]
+pub(crate) struct Links {
+    inner: Vec<Weak<Link>>,
+}
+
+impl Links {
+    pub(crate) fn new() -> Self {
+        Self { inner: Vec::new() }
+    }
+
+    #[inline]
+    pub(crate) fn notify(&mut self, event: LinkEvent) {
+        // Fast path, most arenas have no linked maps.
+        if self.inner.is_empty() {
+            return;
+        }
+
+        // Links to dropped maps are pruned as they are found.
+        self.inner.retain(|link| match link.upgrade() {
+            Some(link) => {
+                if link.keys.lock().apply(&event) {
+                    link.dirty.store(true, Ordering::Release);
+                }
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+impl Clone for Links {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
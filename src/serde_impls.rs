@@ -0,0 +1,282 @@
+//! Serialization, behind the `serde` feature.
+//!
+//! The format is described by the JSON Schema in the `schemars` feature.
+//! Slot layout, generations and the order of the free list round-trip
+//! exactly, so indices saved alongside an arena stay valid after loading.
+//!
+//! Registered callbacks, links and tracking state are not serialized.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+
+use serde::de::Error as _;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Arena, Entry, Index, IndexRemap, SlotKey};
+
+#[derive(Serialize, Deserialize)]
+struct IndexRepr {
+    slot: usize,
+    generation: NonZeroUsize,
+    #[serde(default)]
+    tag: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EntryRepr<T> {
+    generation: NonZeroUsize,
+    item: T,
+}
+
+/// A vacant or reserved slot, with the generation its next item will be given.
+#[derive(Serialize, Deserialize)]
+struct SlotRepr {
+    slot: usize,
+    generation: NonZeroUsize,
+}
+
+#[derive(Deserialize)]
+struct ArenaRepr<T> {
+    generation: NonZeroUsize,
+    entries: Vec<Option<EntryRepr<T>>>,
+    free: Vec<SlotRepr>,
+    #[serde(default)]
+    reserved: Vec<SlotRepr>,
+}
+
+/// Arena deserialized with its vacant slots dropped, and the translation
+/// of the saved indices to the packed ones.
+///
+/// Reads the same format as an [`Arena`], but packs the items into
+/// consecutive slots in slot order, so fragmentation isn't carried over
+/// from one save to the next. Reservations are dropped. Indices saved
+/// alongside the arena must be [translated](IndexRemap::translate),
+/// as they may resolve to another item otherwise.
+///
+/// ```
+/// # use arena::{Arena, Compacted};
+/// # let mut arena = Arena::new();
+/// let index0 = arena.insert("Foo");
+/// let index1 = arena.insert("Bar");
+/// arena.remove(index0);
+///
+/// let json = serde_json::to_string(&arena).unwrap();
+/// let Compacted { arena, remap } = serde_json::from_str(&json).unwrap();
+///
+/// assert_eq!(arena.fragmentation(), 0.0);
+/// assert_eq!(arena.get(remap.translate(index1).unwrap()), Some(&"Bar"));
+/// ```
+#[derive(Debug)]
+pub struct Compacted<T> {
+    pub arena: Arena<T>,
+    pub remap: IndexRemap,
+}
+
+impl Serialize for Index {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        IndexRepr {
+            slot: self.slot(),
+            generation: self.generation,
+            tag: self.tag(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Index {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = IndexRepr::deserialize(deserializer)?;
+        if repr.slot > Index::SLOT_MASK {
+            return Err(D::Error::custom(format_args!("slot {} is out of range", repr.slot)));
+        }
+
+        Ok(Index::new(repr.slot, repr.generation).with_tag(repr.tag))
+    }
+}
+
+impl<T: Serialize> Serialize for Arena<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let reserved: Vec<_> = self
+            .data
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Entry::Reserved { generation } => Some(SlotRepr {
+                    slot,
+                    generation: *generation,
+                }),
+                _ => None,
+            })
+            .collect();
+
+        let mut state = serializer.serialize_struct("Arena", 4)?;
+        state.serialize_field("generation", &self.generation)?;
+        state.serialize_field("entries", &Entries(&self.data))?;
+        state.serialize_field("free", &FreeList(self))?;
+        if reserved.is_empty() {
+            state.skip_field("reserved")?;
+        } else {
+            state.serialize_field("reserved", &reserved)?;
+        }
+        state.end()
+    }
+}
+
+struct Entries<'a, T>(&'a [Entry<T>]);
+
+impl<T: Serialize> Serialize for Entries<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|entry| match entry {
+            Entry::Vacant { .. } | Entry::Reserved { .. } => None,
+            Entry::Occupied { generation, item } => Some(EntryRepr {
+                generation: *generation,
+                item,
+            }),
+        }))
+    }
+}
+
+struct FreeList<'a, T>(&'a Arena<T>);
+
+impl<T> Serialize for FreeList<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let data = &self.0.data;
+        let slots = core::iter::successors(self.0.free_head, |&slot| data[slot].next_free());
+        serializer.collect_seq(slots.map(|slot| SlotRepr {
+            slot,
+            generation: data[slot].generation(),
+        }))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Arena<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ArenaRepr::<T>::deserialize(deserializer)?;
+        if repr.entries.len() > Index::SLOT_MASK {
+            return Err(D::Error::custom("too many entries"));
+        }
+
+        let mut count = 0;
+        let mut data = Vec::with_capacity(repr.entries.len());
+
+        for entry in repr.entries {
+            data.push(match entry {
+                None => Entry::Vacant {
+                    generation: repr.generation,
+                    next: None,
+                },
+                Some(EntryRepr { generation, item }) => {
+                    count += 1;
+                    Entry::Occupied { generation, item }
+                }
+            });
+        }
+
+        if repr.free.len() + repr.reserved.len() != data.len() - count {
+            return Err(D::Error::custom("free list does not cover every vacant slot"));
+        }
+
+        let mut linked = vec![false; data.len()];
+        for (i, free) in repr.free.iter().enumerate() {
+            let slot = free.slot;
+            match data.get_mut(slot) {
+                Some(Entry::Vacant { generation, next }) if !linked[slot] => {
+                    *generation = free.generation;
+                    *next = repr.free.get(i + 1).map(|free| free.slot);
+                    linked[slot] = true;
+                }
+                _ => {
+                    return Err(D::Error::custom(format_args!(
+                        "free list entry {} is not a distinct vacant slot",
+                        slot
+                    )))
+                }
+            }
+        }
+
+        for reserved in &repr.reserved {
+            match data.get_mut(reserved.slot) {
+                Some(entry @ Entry::Vacant { .. }) if !linked[reserved.slot] => {
+                    *entry = Entry::Reserved {
+                        generation: reserved.generation,
+                    };
+                }
+                _ => {
+                    return Err(D::Error::custom(format_args!(
+                        "reserved slot {} is not a distinct vacant slot",
+                        reserved.slot
+                    )))
+                }
+            }
+        }
+
+        let mut arena = Arena::new();
+        arena.data = data;
+        arena.generation = repr.generation;
+        arena.free_head = repr.free.first().map(|free| free.slot);
+        arena.count = count;
+        // Indices saved alongside the arena were stamped by the original.
+        #[cfg(feature = "debug-checks")]
+        {
+            arena.id = 0;
+        }
+        Ok(arena)
+    }
+}
+
+/// Serialized as the slot number.
+impl Serialize for SlotKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.slot() as u64).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SlotKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let slot = u64::deserialize(deserializer)?;
+        match usize::try_from(slot) {
+            Ok(slot) if slot <= Index::SLOT_MASK => Ok(SlotKey::new(slot)),
+            _ => Err(D::Error::custom(format_args!("slot {} is out of range", slot))),
+        }
+    }
+}
+
+/// Serialized as a sequence of `[old, new]` index pairs.
+impl Serialize for IndexRemap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for IndexRemap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pairs = Vec::<(Index, Index)>::deserialize(deserializer)?;
+        let len = pairs.len();
+        let remap: IndexRemap = pairs.into_iter().collect();
+        if remap.len() != len {
+            return Err(D::Error::custom("slot is translated more than once"));
+        }
+        Ok(remap)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Compacted<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ArenaRepr::<T>::deserialize(deserializer)?;
+        if repr.entries.len() > Index::SLOT_MASK {
+            return Err(D::Error::custom("too many entries"));
+        }
+
+        let mut arena = Arena::new();
+        let mut remap = IndexRemap::new();
+        for (slot, entry) in repr.entries.into_iter().enumerate() {
+            if let Some(EntryRepr { generation, item }) = entry {
+                let index = arena.push(item);
+                remap.insert(Index::new(slot, generation), index);
+            }
+        }
+
+        Ok(Compacted { arena, remap })
+    }
+}
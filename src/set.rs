@@ -0,0 +1,97 @@
+//! Set of arena indices.
+use alloc::collections::btree_set::{self, BTreeSet};
+use core::iter::FromIterator;
+
+use crate::Index;
+
+/// A set of [`Index`] handles, used to select items in an [`Arena`](crate::Arena).
+///
+/// The set only stores handles and knows nothing about the arena they
+/// came from. Handles that go stale remain in the set until removed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IndexSet {
+    inner: BTreeSet<Index>,
+}
+
+impl IndexSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an index to the set.
+    ///
+    /// Returns `false` if the index was already present.
+    pub fn insert(&mut self, index: Index) -> bool {
+        self.inner.insert(index)
+    }
+
+    /// Remove an index from the set.
+    ///
+    /// Returns `false` if the index was not present.
+    pub fn remove(&mut self, index: Index) -> bool {
+        self.inner.remove(&index)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.inner.contains(&index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.inner.iter(),
+        }
+    }
+}
+
+impl FromIterator<Index> for IndexSet {
+    fn from_iter<I: IntoIterator<Item = Index>>(iter: I) -> Self {
+        Self {
+            inner: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Index> for IndexSet {
+    fn extend<I: IntoIterator<Item = Index>>(&mut self, iter: I) {
+        self.inner.extend(iter)
+    }
+}
+
+impl<'a> IntoIterator for &'a IndexSet {
+    type Item = Index;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    inner: btree_set::Iter<'a, Index>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
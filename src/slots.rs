@@ -0,0 +1,104 @@
+//! Walking the raw slot layout, for debuggers and serializers.
+use core::iter::Enumerate;
+use core::slice::Iter as SliceIter;
+
+use crate::checks::Stamp;
+use crate::{Arena, Entry, Index};
+
+/// State of a single physical slot, yielded by [`Arena::iter_slots`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotState<'a, T> {
+    /// The slot holds the item at `index`.
+    Occupied { index: Index, item: &'a T },
+    /// The slot is held for `index` by [`Arena::reserve_index`], but not filled yet.
+    Reserved { index: Index },
+    /// The slot is free.
+    Vacant { slot: usize },
+}
+
+impl<T> SlotState<'_, T> {
+    /// Position of the slot in the arena's storage.
+    pub fn slot(&self) -> usize {
+        match self {
+            SlotState::Occupied { index, .. } | SlotState::Reserved { index } => index.slot(),
+            SlotState::Vacant { slot } => *slot,
+        }
+    }
+}
+
+impl<T> Arena<T> {
+    /// Iterate every physical slot in order, including vacant ones.
+    ///
+    /// Unlike [`Arena::iter`], the arena's layout is exposed, with one
+    /// [`SlotState`] for each of the [`slot_count`](Arena::slot_count) slots.
+    ///
+    /// ```
+    /// # use arena::{Arena, SlotState};
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    ///
+    /// let slots: Vec<_> = arena.iter_slots().collect();
+    /// assert_eq!(
+    ///     slots,
+    ///     [
+    ///         SlotState::Vacant { slot: 0 },
+    ///         SlotState::Occupied { index: index1, item: &"Bar" },
+    ///     ]
+    /// );
+    /// ```
+    pub fn iter_slots(&self) -> IterSlots<'_, T> {
+        IterSlots {
+            inner: self.data.iter().enumerate(),
+            stamp: self.stamper(),
+        }
+    }
+
+    /// Number of physical slots, occupied or not.
+    ///
+    /// This is the storage in use, not its [capacity](Arena::capacity).
+    pub fn slot_count(&self) -> usize {
+        self.data.len()
+    }
+}
+
+#[derive(Debug)]
+pub struct IterSlots<'a, T> {
+    inner: Enumerate<SliceIter<'a, Entry<T>>>,
+    stamp: Stamp,
+}
+
+#[inline]
+fn slot_state<T>(stamp: Stamp, (slot, entry): (usize, &Entry<T>)) -> SlotState<'_, T> {
+    match entry {
+        Entry::Occupied { generation, item } => SlotState::Occupied {
+            index: stamp.index(slot, *generation),
+            item,
+        },
+        Entry::Reserved { generation } => SlotState::Reserved {
+            index: stamp.index(slot, *generation),
+        },
+        Entry::Vacant { .. } => SlotState::Vacant { slot },
+    }
+}
+
+impl<'a, T> Iterator for IterSlots<'a, T> {
+    type Item = SlotState<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| slot_state(self.stamp, entry))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterSlots<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|entry| slot_state(self.stamp, entry))
+    }
+}
+
+impl<T> ExactSizeIterator for IterSlots<'_, T> {}
@@ -0,0 +1,198 @@
+//! Arena storing a few items inline, spilling to the heap when it grows.
+use alloc::boxed::Box;
+use core::mem;
+
+use crate::{Arena, FixedArena, Index, Iter, IterIndexed, IterIndexedMut, IterMut};
+
+/// Arena holding up to `N` items inline, and moving them to a heap
+/// allocated [`Arena`] once more are inserted.
+///
+/// Small arenas, like per-entity attachment lists, then don't allocate at
+/// all. Indices stay valid when the items spill to the heap.
+///
+/// ```
+/// # use arena::SmallArena;
+/// let mut arena = SmallArena::<_, 2>::new();
+/// let index0 = arena.insert("Foo");
+/// arena.insert("Bar");
+/// assert!(!arena.spilled());
+///
+/// arena.insert("Baz");
+/// assert!(arena.spilled());
+/// assert_eq!(arena.get(index0), Some(&"Foo"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SmallArena<T, const N: usize> {
+    storage: Storage<T, N>,
+}
+
+#[derive(Debug, Clone)]
+enum Storage<T, const N: usize> {
+    Inline(FixedArena<T, N>),
+    /// Boxed, so the spilled arena's fields don't take up room inline.
+    Heap(Box<Arena<T>>),
+}
+
+impl<T, const N: usize> SmallArena<T, N> {
+    pub fn new() -> Self {
+        Self {
+            storage: Storage::Inline(FixedArena::new()),
+        }
+    }
+
+    /// Check whether the items were moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.storage, Storage::Heap(_))
+    }
+
+    /// Insert the item, moving all items to the heap if there's
+    /// no room left inline.
+    pub fn insert(&mut self, item: T) -> Index {
+        let item = match &mut self.storage {
+            Storage::Inline(fixed) => match fixed.try_insert(item) {
+                Ok(index) => return index,
+                Err(full) => full.into_inner(),
+            },
+            Storage::Heap(arena) => return arena.insert(item),
+        };
+
+        let arena = self.spill();
+        arena.insert(item)
+    }
+
+    /// Remove the item at the given `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: Index) {
+        self.take(index);
+    }
+
+    /// Remove and return the item at the given `index`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn take(&mut self, index: Index) -> Option<T> {
+        match &mut self.storage {
+            Storage::Inline(fixed) => fixed.take(index),
+            Storage::Heap(arena) => arena.take(index),
+        }
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        match &self.storage {
+            Storage::Inline(fixed) => fixed.get(index),
+            Storage::Heap(arena) => arena.get(index),
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        match &mut self.storage {
+            Storage::Inline(fixed) => fixed.get_mut(index),
+            Storage::Heap(arena) => arena.get_mut(index),
+        }
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Inline(fixed) => fixed.len(),
+            Storage::Heap(arena) => arena.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every item. Indices handed out before are invalidated.
+    ///
+    /// Spilled items stay on the heap, keeping its allocation.
+    pub fn clear(&mut self) {
+        match &mut self.storage {
+            Storage::Inline(fixed) => fixed.clear(),
+            Storage::Heap(arena) => arena.clear(),
+        }
+    }
+
+    /// Iterate the items in slot order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        match &self.storage {
+            Storage::Inline(fixed) => fixed.iter(),
+            Storage::Heap(arena) => arena.iter(),
+        }
+    }
+
+    /// Mutably iterate the items in slot order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        match &mut self.storage {
+            Storage::Inline(fixed) => fixed.iter_mut(),
+            Storage::Heap(arena) => arena.iter_mut(),
+        }
+    }
+
+    /// Iterate the items in slot order, together with their indices.
+    pub fn iter_indexed(&self) -> IterIndexed<'_, T> {
+        match &self.storage {
+            Storage::Inline(fixed) => fixed.iter_indexed(),
+            Storage::Heap(arena) => arena.iter_indexed(),
+        }
+    }
+
+    /// Mutably iterate the items in slot order, together with their indices.
+    pub fn iter_indexed_mut(&mut self) -> IterIndexedMut<'_, T> {
+        match &mut self.storage {
+            Storage::Inline(fixed) => fixed.iter_indexed_mut(),
+            Storage::Heap(arena) => arena.iter_indexed_mut(),
+        }
+    }
+
+    /// Convert into a heap allocated arena, where all indices stay valid.
+    pub fn into_arena(self) -> Arena<T> {
+        match self.storage {
+            Storage::Inline(fixed) => fixed.into_arena(),
+            Storage::Heap(arena) => *arena,
+        }
+    }
+
+    /// Move the items to the heap, if they aren't already.
+    fn spill(&mut self) -> &mut Arena<T> {
+        if let Storage::Inline(fixed) = &mut self.storage {
+            let fixed = mem::take(fixed);
+            self.storage = Storage::Heap(Box::new(fixed.into_arena()));
+        }
+
+        match &mut self.storage {
+            Storage::Heap(arena) => arena,
+            Storage::Inline(_) => unreachable!(),
+        }
+    }
+}
+
+impl<T, const N: usize> Default for SmallArena<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> core::ops::Index<Index> for SmallArena<T, N> {
+    type Output = T;
+
+    /// # Panic
+    ///
+    /// Panics if `index` is stale or out of bounds.
+    fn index(&self, index: Index) -> &T {
+        self.get(index).expect("index is stale or out of bounds")
+    }
+}
+
+impl<T, const N: usize> core::ops::IndexMut<Index> for SmallArena<T, N> {
+    fn index_mut(&mut self, index: Index) -> &mut T {
+        self.get_mut(index).expect("index is stale or out of bounds")
+    }
+}
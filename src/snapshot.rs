@@ -0,0 +1,141 @@
+//! Saving and restoring the state of an arena, for undo.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+
+use crate::secondary::LinkEvent;
+use crate::{Arena, ChangeKind, Entry, Index};
+
+/// Saved state of an [`Arena`], created by [`Arena::snapshot`].
+#[derive(Debug, Clone)]
+pub struct Snapshot<T> {
+    data: Vec<Entry<T>>,
+    generation: NonZeroUsize,
+    free_head: Option<usize>,
+    count: usize,
+}
+
+impl<T> Snapshot<T> {
+    /// Number of items in the saved state.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl<T: Clone> Arena<T> {
+    /// Save a copy of the arena's items and slot layout.
+    ///
+    /// Registered callbacks and tracking state are not part of the snapshot.
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot {
+            data: self.data.clone(),
+            generation: self.generation,
+            free_head: self.free_head,
+            count: self.count,
+        }
+    }
+
+    /// Roll the arena back to the state saved in `snapshot`.
+    ///
+    /// Indices to items present when the snapshot was taken resolve to
+    /// them again. Slots freed by the rollback keep advancing their
+    /// generations, so indices handed out after the snapshot don't resolve
+    /// to later items in the same slot. That can't be ensured for a slot
+    /// whose item is brought back, so indices handed out after the snapshot
+    /// should be dropped.
+    ///
+    /// Linked [secondary maps](crate::SecondaryMap) drop the values of
+    /// items removed by the rollback, the [journal](Arena::track_changes)
+    /// records the items removed and brought back, and with
+    /// [revision tracking](Arena::track_revisions) every restored item
+    /// counts as changed.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let snapshot = arena.snapshot();
+    ///
+    /// arena.remove(index0);
+    /// let index1 = arena.insert("Bar");
+    ///
+    /// arena.restore(&snapshot);
+    /// assert_eq!(arena.get(index0), Some(&"Foo"));
+    /// assert_eq!(arena.get(index1), None);
+    /// ```
+    pub fn restore(&mut self, snapshot: &Snapshot<T>) {
+        let mut generation = self.generation.max(snapshot.generation);
+        let mut data = snapshot.data.clone();
+        let mut kept = vec![false; data.len()];
+        let mut removed = Vec::new();
+
+        // Slots past the current storage were truncated since the
+        // snapshot, and handed out at most the arena's generation.
+        for entry in data.iter_mut().skip(self.data.len()) {
+            if let Entry::Vacant { generation, .. } = entry {
+                *generation = (*generation).max(self.generation);
+            }
+        }
+
+        for (slot, entry) in core::mem::take(&mut self.data).into_iter().enumerate() {
+            // The first generation the slot hasn't handed out yet.
+            let floor = match &entry {
+                Entry::Vacant { generation, .. } => *generation,
+                Entry::Reserved { generation } | Entry::Occupied { generation, .. } => generation.saturating_add(1),
+            };
+
+            if let Entry::Occupied { generation, item } = entry {
+                self.account_removed(&item);
+                if matches!(data.get(slot), Some(Entry::Occupied { generation: saved, .. }) if *saved == generation) {
+                    kept[slot] = true;
+                } else {
+                    let index = self.stamp(Index::new(slot, generation));
+                    self.links.notify(LinkEvent::Removed(index));
+                    self.record_change(index, ChangeKind::Removed);
+                    if self.remove_hook.is_some() {
+                        removed.push((index, item));
+                    }
+                }
+            }
+
+            match data.get_mut(slot) {
+                Some(Entry::Vacant { generation, .. }) => *generation = (*generation).max(floor),
+                Some(Entry::Reserved { .. } | Entry::Occupied { .. }) => {}
+                None => generation = generation.max(floor),
+            }
+        }
+
+        self.data = data;
+        self.generation = generation;
+        self.free_head = snapshot.free_head;
+        self.count = snapshot.count;
+
+        // Raising the generations may have retired slots on the saved free list.
+        for slot in 0..self.data.len() {
+            if self.is_retired(&self.data[slot]) && self.unlink_free(slot) {
+                self.data[slot].set_next_free(None);
+            }
+        }
+
+        for (slot, kept) in kept.into_iter().enumerate() {
+            if let Entry::Occupied { generation, item } = &self.data[slot] {
+                let index = self.stamp(Index::new(slot, *generation));
+                let size = self.item_size(item);
+                self.add_item_bytes(size);
+                if !kept {
+                    self.recent.stamp(slot);
+                    self.record_change(index, ChangeKind::Inserted);
+                }
+            }
+        }
+        self.touch_occupied();
+
+        for (index, item) in removed {
+            self.notify_removed(index, &item);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Reordering the items in storage.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::num::NonZeroUsize;
+
+use crate::secondary::LinkEvent;
+use crate::{Arena, ChangeKind, Entry, Index, IndexRemap};
+
+impl<T> Arena<T> {
+    /// Sort the items in storage with the comparator function `compare`,
+    /// packing them into the front slots, so iteration visits them in order.
+    ///
+    /// The sort is stable. Pending [reservations](Arena::reserve_index) are
+    /// kept after the items. Every moved item gets a new index in its new
+    /// slot, and the returned remap translates the old indices. Items that
+    /// were already in place keep their index, and have no translation.
+    ///
+    /// Linked [secondary maps](crate::SecondaryMap) move their values along,
+    /// and the [journal](Arena::track_changes) records every move.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let mut sprite = arena.insert(("Foo", 3));
+    /// arena.insert(("Bar", 1));
+    /// arena.insert(("Baz", 2));
+    ///
+    /// let remap = arena.sort_by(|a, b| a.1.cmp(&b.1));
+    /// remap.patch(&mut sprite);
+    ///
+    /// let names: Vec<_> = arena.iter().map(|(name, _)| *name).collect();
+    /// assert_eq!(names, ["Bar", "Baz", "Foo"]);
+    /// assert_eq!(arena.get(sprite), Some(&("Foo", 3)));
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if a slot's generation overflows under the
+    /// [`Panic`](crate::GenerationOverflow::Panic) or
+    /// [`Retire`](crate::GenerationOverflow::Retire) policy. The arena is
+    /// left untouched.
+    pub fn sort_by<F>(&mut self, mut compare: F) -> IndexRemap
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let len = self.data.len();
+        let mut order: Vec<usize> = (0..len).filter(|&slot| self.data[slot].is_occupied()).collect();
+        order.sort_by(|&a, &b| match (&self.data[a], &self.data[b]) {
+            (Entry::Occupied { item: a, .. }, Entry::Occupied { item: b, .. }) => compare(a, b),
+            _ => unreachable!(),
+        });
+        order.extend((0..len).filter(|&slot| matches!(self.data[slot], Entry::Reserved { .. })));
+
+        // Retired slots are vacant, so there are always enough others.
+        let targets = (0..len).filter(|&slot| !self.is_retired(&self.data[slot]));
+        let mut incoming = vec![false; len];
+        let placement: Vec<(usize, usize)> = order.into_iter().zip(targets).collect();
+        for &(_, to) in &placement {
+            incoming[to] = true;
+        }
+
+        // Work out every generation up front, so an overflow panic leaves the arena untouched.
+        let mut moves = Vec::new();
+        let mut vacated = Vec::new();
+        for (from, to) in placement {
+            if from == to {
+                continue;
+            }
+            let generation = match &self.data[to] {
+                Entry::Vacant { generation, .. } => *generation,
+                entry => self.regenerate(to, entry.generation()),
+            };
+            moves.push((from, to, generation));
+            if !incoming[from] {
+                vacated.push((from, self.next_generation(from, self.data[from].generation())));
+            }
+        }
+
+        // Every moved-from slot is refilled below, by an item or as vacant.
+        let entries: Vec<Entry<T>> = moves
+            .iter()
+            .map(|&(from, ..)| {
+                let placeholder = Entry::Vacant {
+                    generation: NonZeroUsize::MIN,
+                    next: None,
+                };
+                core::mem::replace(&mut self.data[from], placeholder)
+            })
+            .collect();
+        for (slot, generation) in vacated {
+            self.data[slot] = Entry::Vacant { generation, next: None };
+        }
+
+        let mut remap = IndexRemap::new();
+        let mut relocations = Vec::with_capacity(moves.len());
+        for ((from, to, next), mut entry) in moves.into_iter().zip(entries) {
+            let old = match &mut entry {
+                Entry::Occupied { generation, .. } | Entry::Reserved { generation } => {
+                    self.stamp(Index::new(from, core::mem::replace(generation, next)))
+                }
+                Entry::Vacant { .. } => unreachable!(),
+            };
+            self.data[to] = entry;
+
+            let new = self.stamp(Index::new(to, next));
+            remap.insert(old, new);
+            relocations.push((old, new));
+        }
+        self.rebuild_free_list();
+        self.recent.relocate(&relocations);
+
+        for &(old, new) in &relocations {
+            self.touch(new.slot());
+            self.record_change(new, ChangeKind::Relocated { from: old });
+        }
+        if !relocations.is_empty() {
+            self.links.notify(LinkEvent::Permuted(relocations));
+        }
+
+        remap
+    }
+
+    /// Sort the items in storage by the key extracted with `f`.
+    ///
+    /// See [`Arena::sort_by`].
+    pub fn sort_by_key<K, F>(&mut self, mut f: F) -> IndexRemap
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)))
+    }
+}
@@ -0,0 +1,99 @@
+//! Writing uninitialized items into a staging buffer, before inserting them.
+use alloc::vec::Vec;
+use core::mem::MaybeUninit;
+
+use crate::{Arena, Index};
+
+/// Buffer of uninitialized items handed out by [`Arena::spare_slots_mut`].
+///
+/// The buffer is a separate allocation, not the arena's storage. Items
+/// written into it are moved into the arena when
+/// [committed](SpareSlots::commit). Dropping the buffer without
+/// committing leaves the arena untouched, and does not drop any items.
+#[derive(Debug)]
+pub struct SpareSlots<'a, T> {
+    arena: &'a mut Arena<T>,
+    buffer: Vec<MaybeUninit<T>>,
+}
+
+impl<'a, T> SpareSlots<'a, T> {
+    /// The uninitialized items, to be written in order.
+    pub fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        &mut self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Insert the first `initialized` items into the arena, returning their indices.
+    ///
+    /// Each item is moved out of the buffer and placed the same way as
+    /// [`Arena::insert`].
+    ///
+    /// # Safety
+    ///
+    /// The first `initialized` elements of the buffer must have been initialized.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `initialized` is greater than the buffer's length, or an
+    /// item would exceed the arena's [memory budget](Arena::set_memory_budget)
+    /// or [limit](Arena::with_limit). Items inserted before that remain.
+    pub unsafe fn commit(mut self, initialized: usize) -> Vec<Index> {
+        assert!(
+            initialized <= self.buffer.len(),
+            "committed more items than were reserved"
+        );
+
+        // Forget the uninitialized tail, so a panic during
+        // insertion can't touch it.
+        self.buffer.truncate(initialized);
+
+        let mut indices = Vec::with_capacity(initialized);
+        self.arena.data.reserve(initialized);
+
+        for item in self.buffer.drain(..) {
+            // SAFETY: The caller guarantees the first `initialized` items
+            //         are initialized, and each is read exactly once.
+            indices.push(self.arena.insert(unsafe { item.assume_init() }));
+        }
+
+        indices
+    }
+}
+
+impl<T> Arena<T> {
+    /// Hand out a buffer of `n` uninitialized items, which can be written
+    /// by code that fills a `MaybeUninit` slice, such as a decoder, and
+    /// later committed to the arena.
+    ///
+    /// Items aren't written into the arena's storage. The buffer is
+    /// allocated separately, and committing moves each item into the arena,
+    /// which costs one extra allocation, and an extra copy of each item,
+    /// over inserting the items directly.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let mut spare = arena.spare_slots_mut(3);
+    /// for (i, slot) in spare.as_mut_slice().iter_mut().take(2).enumerate() {
+    ///     slot.write(i * 10);
+    /// }
+    ///
+    /// // SAFETY: The first two items were written above.
+    /// let indices = unsafe { spare.commit(2) };
+    /// assert_eq!(arena.get(indices[1]), Some(&10));
+    /// # assert_eq!(arena.len(), 2);
+    /// ```
+    pub fn spare_slots_mut(&mut self, n: usize) -> SpareSlots<'_, T> {
+        let mut buffer = Vec::with_capacity(n);
+        buffer.resize_with(n, MaybeUninit::uninit);
+
+        SpareSlots { arena: self, buffer }
+    }
+}
@@ -0,0 +1,79 @@
+//! Memory and fragmentation statistics.
+use crate::{Arena, Entry};
+
+/// Summary of an arena's storage, created by [`Arena::stats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaStats {
+    /// Number of slots the arena can hold without reallocating.
+    pub capacity: usize,
+    /// Number of slots holding an item.
+    pub occupied: usize,
+    /// Number of slots held by a pending reservation.
+    pub reserved: usize,
+    /// Number of slots free for reuse.
+    pub vacant: usize,
+    /// Number of [retired](crate::GenerationOverflow::Retire) slots, which are never reused.
+    pub retired: usize,
+    /// Number of slots reachable from the head of the free list.
+    pub free_list_len: usize,
+    /// Number of slots in storage, which only goes down when
+    /// vacant slots are truncated or the arena is compacted.
+    pub high_water_mark: usize,
+    /// Ratio of vacant slots to the total number of slots, see [`Arena::fragmentation`].
+    pub fragmentation: f32,
+    /// Bytes of memory used, see [`Arena::memory_usage`].
+    pub memory_usage: usize,
+}
+
+impl<T> Arena<T> {
+    /// Gather statistics about the arena's storage.
+    ///
+    /// Walks every slot and the whole free list, so this is meant
+    /// for occasional profiling rather than every frame.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    ///
+    /// let stats = arena.stats();
+    /// assert_eq!(stats.occupied, 1);
+    /// assert_eq!(stats.vacant, 1);
+    /// assert_eq!(stats.high_water_mark, 2);
+    /// assert_eq!(stats.fragmentation, 0.5);
+    /// ```
+    pub fn stats(&self) -> ArenaStats {
+        let mut reserved = 0;
+        let mut vacant = 0;
+        let mut retired = 0;
+        for entry in &self.data {
+            match entry {
+                Entry::Vacant { .. } if self.is_retired(entry) => retired += 1,
+                Entry::Vacant { .. } => vacant += 1,
+                Entry::Reserved { .. } => reserved += 1,
+                Entry::Occupied { .. } => {}
+            }
+        }
+
+        let mut free_list_len = 0;
+        let mut next = self.free_head;
+        while let Some(Entry::Vacant { next: following, .. }) = next.and_then(|slot| self.data.get(slot)) {
+            free_list_len += 1;
+            next = *following;
+        }
+
+        ArenaStats {
+            capacity: self.data.capacity(),
+            occupied: self.count,
+            reserved,
+            vacant,
+            retired,
+            free_list_len,
+            high_water_mark: self.data.len(),
+            fragmentation: self.fragmentation(),
+            memory_usage: self.memory_usage(),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Checking what became of a stored index.
+use crate::{Arena, Entry, Index};
+
+/// What the slot of an index holds, see [`Arena::status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexStatus {
+    /// The index resolves to its item.
+    Live,
+    /// The slot holds the index's item, but the [tag checker](Arena::set_tag_checker)
+    /// rejects the index's tag.
+    Rejected,
+    /// The index is a [reservation](Arena::reserve_index) that isn't filled yet.
+    Pending,
+    /// The item was removed, and the slot now holds a newer item or
+    /// reservation at `current`.
+    Superseded { current: Index },
+    /// The item was removed, and the slot is empty.
+    Vacant,
+}
+
+impl IndexStatus {
+    pub fn is_live(&self) -> bool {
+        matches!(self, IndexStatus::Live)
+    }
+}
+
+impl<T> Arena<T> {
+    /// Check whether `index` is still the live handle of its slot, or was
+    /// superseded by a newer one, or its slot is empty.
+    ///
+    /// Indices beyond the arena's storage are reported as vacant, since
+    /// their slots were dropped, or never handed out.
+    ///
+    /// ```
+    /// # use arena::{Arena, IndexStatus};
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// assert_eq!(arena.status(index0), IndexStatus::Live);
+    ///
+    /// arena.remove(index0);
+    /// assert_eq!(arena.status(index0), IndexStatus::Vacant);
+    ///
+    /// let index1 = arena.insert("Bar");
+    /// assert_eq!(arena.status(index0), IndexStatus::Superseded { current: index1 });
+    /// ```
+    pub fn status(&self, index: Index) -> IndexStatus {
+        self.check_owner(index);
+        if self.is_live(index) {
+            return IndexStatus::Live;
+        }
+
+        let slot = index.slot();
+        match self.data.get(slot) {
+            Some(Entry::Occupied { generation, .. }) if *generation == index.generation => IndexStatus::Rejected,
+            Some(Entry::Reserved { generation }) if *generation == index.generation => IndexStatus::Pending,
+            Some(Entry::Occupied { generation, .. } | Entry::Reserved { generation }) => IndexStatus::Superseded {
+                current: self.stamp(Index::new(slot, *generation)).with_tag(index.tag()),
+            },
+            Some(Entry::Vacant { .. }) | None => IndexStatus::Vacant,
+        }
+    }
+
+    /// Return `index` if it still resolves, or `None` once it's stale.
+    ///
+    /// Unlike [`Arena::contains`], this can be chained with `?`, and keeps
+    /// the index's [tag](Index::tag). Use [`Arena::status`] to tell why an
+    /// index no longer resolves.
+    pub fn upgrade(&self, index: Index) -> Option<Index> {
+        self.status(index).is_live().then_some(index)
+    }
+}
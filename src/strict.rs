@@ -0,0 +1,87 @@
+//! Loud failures on stale index access.
+use core::num::NonZeroUsize;
+
+use crate::{Access, Arena, Entry, Index};
+
+/// Details of an access through a stale index, passed to the
+/// handler registered with [`Arena::set_stale_handler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleAccess {
+    pub access: Access,
+    pub index: Index,
+    /// Generation of the item currently in the slot, or `None` if the slot is vacant.
+    pub current: Option<NonZeroUsize>,
+}
+
+/// Function invoked when a stale index is used in strict mode.
+pub type StaleFn = fn(StaleAccess);
+
+fn panic_on_stale(stale: StaleAccess) {
+    match stale.current {
+        Some(current) => panic!(
+            "{:?} with stale index: slot {} has generation {}, but the index has generation {}",
+            stale.access,
+            stale.index.slot(),
+            current,
+            stale.index.generation,
+        ),
+        None => panic!(
+            "{:?} with stale index: slot {} is vacant, the index has generation {}",
+            stale.access,
+            stale.index.slot(),
+            stale.index.generation,
+        ),
+    }
+}
+
+impl<T> Arena<T> {
+    /// Enable or disable strict mode.
+    ///
+    /// In strict mode, [`Arena::get`], [`Arena::get_mut`], [`Arena::remove`]
+    /// and [`Arena::take`] panic when given an index that is in bounds, but
+    /// stale, instead of quietly returning `None`. The `try_` accessors,
+    /// such as [`Arena::try_get`], return the error instead.
+    ///
+    /// ```should_panic
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// arena.set_strict(true);
+    ///
+    /// let index = arena.insert("Foo");
+    /// arena.remove(index);
+    /// arena.get(index); // panics
+    /// ```
+    pub fn set_strict(&mut self, strict: bool) {
+        self.stale_handler = if strict { Some(panic_on_stale) } else { None };
+    }
+
+    /// Enable strict mode with a custom handler, which is invoked
+    /// instead of panicking.
+    pub fn set_stale_handler(&mut self, handler: StaleFn) {
+        self.stale_handler = Some(handler);
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.stale_handler.is_some()
+    }
+
+    /// Invoke the stale handler if `index` is in bounds, but
+    /// its slot is vacant or holds a different generation.
+    #[inline]
+    pub(crate) fn report_stale(&self, access: Access, index: Index) {
+        let handler = match self.stale_handler {
+            Some(handler) => handler,
+            None => return,
+        };
+
+        let current = match self.data.get(index.slot()) {
+            Some(Entry::Occupied { generation, .. }) if *generation == index.generation => return,
+            Some(Entry::Occupied { generation, .. }) => Some(*generation),
+            Some(Entry::Reserved { generation }) if *generation == index.generation => return,
+            Some(Entry::Vacant { .. } | Entry::Reserved { .. }) => None,
+            None => return,
+        };
+
+        handler(StaleAccess { access, index, current });
+    }
+}
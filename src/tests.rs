@@ -1,4 +1,8 @@
-use crate::{Arena, Index};
+use crate::{
+    join, Arena, ArenaCell, ArenaError, BorrowError, BoxArena, ConcurrentArena, Cursor, DenseArena, IdAllocator, Index,
+    IndexRemap, IndexSet, IndexStatus, MultiArena, PersistentArena, RcArena, SecondaryMap, SlotKey, SlotState,
+    ValidationError,
+};
 
 #[test]
 fn test_push() {
@@ -168,3 +172,4008 @@ fn test_arena_get2_mut() {
     assert_eq!(foo, Some(&mut "Foo"));
     assert_eq!(bar, Some(&mut "Bar"));
 }
+
+#[test]
+fn test_get_disjoint_mut() {
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..4).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[1]);
+
+    let [a, b, c, d] = arena.get_disjoint_mut([indices[3], indices[1], indices[0], indices[2]]);
+    assert_eq!(b, None);
+    *a.unwrap() += 10;
+    *c.unwrap() += 10;
+    *d.unwrap() += 10;
+
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), vec![10, 12, 13]);
+}
+
+#[test]
+fn test_try_get2_mut() {
+    use crate::AliasError;
+
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    let index1 = arena.insert("Bar");
+
+    assert_eq!(
+        arena.try_get2_mut(index0, Index::from_parts(9, 1)),
+        Ok((Some(&mut "Foo"), None))
+    );
+    assert_eq!(arena.try_get2_mut(index1, index1), Err(AliasError { slot: 1 }));
+}
+
+#[test]
+#[should_panic(expected = "indices point to the same slot")]
+fn test_get_disjoint_mut_aliasing() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    arena.get_disjoint_mut([index0, index0.with_tag(1)]);
+}
+
+#[test]
+fn test_index_operator() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(String::from("Foo"));
+
+    arena[index0].push_str("Bar");
+    assert_eq!(arena[index0], "FooBar");
+}
+
+#[test]
+#[should_panic(expected = "index is stale or out of bounds")]
+fn test_index_operator_stale() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    arena.remove(index0);
+    let _ = arena[index0];
+}
+
+#[test]
+fn test_index_tag() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let tagged = index0.with_tag(0xAB);
+
+    assert_eq!(index0.tag(), 0);
+    assert_eq!(tagged.tag(), 0xAB);
+    assert_eq!(tagged.slot(), index0.slot());
+    assert_eq!(tagged.with_tag(0).slot(), index0.slot());
+
+    // without a checker the tag is ignored
+    assert_eq!(arena.get(tagged), Some(&"Foo"));
+}
+
+#[test]
+fn test_index_debug() {
+    let mut arena = Arena::new();
+    arena.push("Foo");
+    let index = arena.push("Bar").with_tag(3);
+
+    assert_eq!(format!("{:?}", index), "Index { slot: 1, generation: 1, tag: 3 }");
+}
+
+#[test]
+fn test_tag_checker() {
+    fn check(tag: u8, item: &(u8, &str)) -> bool {
+        tag == item.0
+    }
+
+    let mut arena = Arena::new();
+    arena.set_tag_checker(check);
+    let index0 = arena.push((1, "Foo")).with_tag(1);
+    let index1 = arena.push((2, "Bar")).with_tag(2);
+
+    assert_eq!(arena.get(index0), Some(&(1, "Foo")));
+    assert_eq!(arena.get(index1.with_tag(1)), None);
+    assert!(arena.get_mut(index0.with_tag(2)).is_none());
+
+    // mismatched tags don't remove
+    assert_eq!(arena.take(index1.with_tag(1)), None);
+    arena.remove(index0.with_tag(3));
+    assert_eq!(arena.len(), 2);
+
+    assert_eq!(arena.take(index1), Some((2, "Bar")));
+    assert_eq!(arena.len(), 1);
+
+    arena.clear_tag_checker();
+    assert_eq!(arena.get(index0.with_tag(7)), Some(&(1, "Foo")));
+}
+
+#[test]
+fn test_iter_resumable() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    let mut cursor = Cursor::new();
+
+    assert_eq!(arena.iter_resumable(&mut cursor).next(), Some((index0, &"Foo")));
+    assert!(!cursor.is_at_start());
+
+    // removed before the cursor reached it
+    arena.remove(index1);
+    let index3 = arena.push("Qux");
+
+    let items: Vec<_> = arena.iter_resumable(&mut cursor).collect();
+    assert_eq!(items, vec![(index2, &"Baz"), (index3, &"Qux")]);
+    assert!(cursor.is_at_start());
+
+    // recycled slot behind the cursor is seen on the next sweep
+    let index4 = arena.insert("Quux");
+    let items: Vec<_> = arena.iter_resumable(&mut cursor).map(|(index, _)| index).collect();
+    assert_eq!(items, vec![index0, index4, index2, index3]);
+}
+
+#[test]
+fn test_iter_resumable_mut() {
+    let mut arena = Arena::new();
+    let index0 = arena.push(1);
+    let index1 = arena.push(2);
+    let mut cursor = Cursor::new();
+
+    for (_, item) in arena.iter_resumable_mut(&mut cursor).take(1) {
+        *item *= 10;
+    }
+    for (_, item) in arena.iter_resumable_mut(&mut cursor).take(1) {
+        *item *= 10;
+    }
+
+    assert_eq!(arena.get(index0), Some(&10));
+    assert_eq!(arena.get(index1), Some(&20));
+    assert!(!cursor.is_at_start());
+    assert_eq!(arena.iter_resumable_mut(&mut cursor).count(), 0);
+    assert!(cursor.is_at_start());
+}
+
+#[test]
+fn test_view_of() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    let selection: IndexSet = [index0, index2].into_iter().collect();
+
+    let view = arena.view_of(&selection);
+    assert_eq!(view.len(), 2);
+    assert_eq!(view.get(index0), Some(&"Foo"));
+    assert_eq!(view.get(index1), None);
+    assert_eq!(view.get(index2), Some(&"Baz"));
+
+    // stale indices in the selection are skipped
+    arena.remove(index0);
+    let view = arena.view_of(&selection);
+    assert_eq!(view.len(), 1);
+    assert_eq!(view.get(index0), None);
+    assert_eq!(view.iter().collect::<Vec<_>>(), vec![(index2, &"Baz")]);
+}
+
+#[test]
+fn test_views_in_strict_mode() {
+    let mut arena = Arena::new();
+    arena.set_strict(true);
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let selection: IndexSet = [index0, index1].into_iter().collect();
+    arena.remove(index0);
+
+    let view = arena.view_of(&selection);
+    assert_eq!(view.get(index0), None);
+    assert!(!view.contains(index0));
+    assert_eq!(view.get(index1), Some(&"Bar"));
+    assert_eq!(view.iter().collect::<Vec<_>>(), vec![(index1, &"Bar")]);
+
+    let filtered = arena.view(|_, item| item.starts_with('B'));
+    assert_eq!(filtered.get(index0), None);
+    assert!(!filtered.contains(index0));
+    assert_eq!(filtered.get(index1), Some(&"Bar"));
+    assert_eq!(filtered.iter().collect::<Vec<_>>(), vec![(index1, &"Bar")]);
+}
+
+#[test]
+fn test_watch() {
+    use crate::Access;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static LOG: RefCell<Vec<(Access, Index)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(access: Access, index: Index) {
+        LOG.with(|log| log.borrow_mut().push((access, index)));
+    }
+
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    arena.watch(index0, record);
+
+    arena.get(index0);
+    arena.get(index1);
+    arena.get_mut(index0);
+    arena.remove(index0);
+    arena.get(index0);
+
+    let log = LOG.with(|log| log.borrow().clone());
+    assert_eq!(
+        log,
+        vec![
+            (Access::Get, index0),
+            (Access::GetMut, index0),
+            (Access::Remove, index0),
+            (Access::Get, index0),
+        ]
+    );
+
+    arena.unwatch(index0);
+    arena.get(index0);
+    assert_eq!(LOG.with(|log| log.borrow().len()), 4);
+}
+
+#[test]
+fn test_resolve_many() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    arena.remove(index1);
+    let index_bad = Index::from_parts(99, 1);
+
+    let items: Vec<_> = arena
+        .resolve_many([None, Some(index0), Some(index1), Some(index_bad)])
+        .collect();
+    assert_eq!(items, vec![None, Some(&"Foo"), None, None]);
+}
+
+#[test]
+fn test_resolve_many_mut() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    arena.remove(index1);
+    let index_bad = Index::from_parts(99, 1);
+
+    let items = arena.resolve_many_mut([Some(index2), None, Some(index_bad), Some(index1), Some(index0)]);
+    assert_eq!(items, vec![Some(&mut "Baz"), None, None, None, Some(&mut "Foo")]);
+}
+
+#[test]
+#[should_panic]
+fn test_resolve_many_mut_alias() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+
+    arena.resolve_many_mut([Some(index0), Some(index0)]);
+}
+
+#[test]
+fn test_from_fn() {
+    let (mut arena, indices) = Arena::from_fn_with_indices(3, |i| i * 10);
+    assert_eq!(arena.len(), 3);
+    assert_eq!(indices.len(), 3);
+    assert_eq!(arena.get(indices[0]), Some(&0));
+    assert_eq!(arena.get(indices[1]), Some(&10));
+    assert_eq!(arena.get(indices[2]), Some(&20));
+
+    // storage behaves normally afterwards
+    arena.remove(indices[1]);
+    let index3 = arena.insert(30);
+    assert_eq!(index3.slot, indices[1].slot);
+    assert_eq!(arena.get(indices[1]), None);
+
+    assert!(Arena::from_fn(0, |i| i).is_empty());
+}
+
+#[test]
+fn test_maintain_compacts() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static MOVES: RefCell<Vec<(Index, Index)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn on_relocate(old: Index, new: Index) {
+        MOVES.with(|moves| moves.borrow_mut().push((old, new)));
+    }
+
+    let mut arena = Arena::new();
+    arena.compact_when(0.4, on_relocate);
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    let index3 = arena.push("Qux");
+
+    arena.remove(index1);
+    assert_eq!(arena.fragmentation(), 0.25);
+    assert!(!arena.maintain());
+
+    arena.remove(index0);
+    assert!(arena.maintain());
+    assert_eq!(arena.fragmentation(), 0.0);
+    assert_eq!(arena.len(), 2);
+
+    let moves = MOVES.with(|moves| moves.borrow().clone());
+    assert_eq!(moves.len(), 2);
+    assert_eq!(moves[0].0, index2);
+    assert_eq!(moves[1].0, index3);
+
+    // old indices are stale, even those pointing at reused slots
+    for (old, new) in &moves {
+        assert_eq!(arena.get(*old), None);
+        assert!(arena.get(*new).is_some());
+    }
+    assert_eq!(arena.get(index0), None);
+    assert_eq!(arena.get(moves[0].1), Some(&"Baz"));
+    assert_eq!(arena.get(moves[1].1), Some(&"Qux"));
+
+    // free list was reset along with the storage
+    let index4 = arena.insert("Quux");
+    assert_eq!(index4.slot, 2);
+}
+
+#[test]
+fn test_get_many() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    arena.remove(index1);
+    let index_bad = Index::from_parts(99, 1);
+
+    assert_eq!(
+        arena.get_many([index0, index1, index_bad, index0]),
+        [Some(&"Foo"), None, None, Some(&"Foo")]
+    );
+    assert_eq!(arena.get_many::<0>([]), []);
+}
+
+#[test]
+fn test_move_slot() {
+    use crate::MoveError;
+
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    let index3 = arena.push("Qux");
+    arena.remove(index0);
+    arena.remove(index1);
+
+    assert_eq!(arena.move_slot(index3, 2), Err(MoveError::Occupied(2)));
+    assert_eq!(arena.move_slot(index3, 9), Err(MoveError::OutOfBounds(9)));
+    assert_eq!(arena.move_slot(index0, 1), Err(MoveError::Stale(index0)));
+    assert_eq!(arena.move_slot(index2, 2), Ok(index2));
+
+    // slot 0 is not at the head of the free list
+    let moved = arena.move_slot(index3, 0).unwrap();
+    assert_eq!(moved.slot, 0);
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.get(index3), None);
+    assert_eq!(arena.get(index0), None);
+    assert_eq!(arena.get(moved), Some(&"Qux"));
+
+    // the vacated slot and the remaining free slot are both reused
+    let index4 = arena.insert("Quux");
+    let index5 = arena.insert("Corge");
+    let mut slots = [index4.slot, index5.slot];
+    slots.sort();
+    assert_eq!(slots, [1, 3]);
+    assert_eq!(arena.get(index3), None);
+    assert_eq!(arena.len(), 4);
+}
+
+#[test]
+fn test_replace_vacant_unlinks_free_slot() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    arena.remove(index0);
+    arena.remove(index1);
+
+    let (index2, _) = arena.replace(index0, "Baz");
+    let index3 = arena.insert("Qux");
+    let index4 = arena.insert("Quux");
+
+    assert_eq!(index3.slot, index1.slot);
+    assert_eq!(index4.slot, 2);
+    assert_eq!(arena.get(index2), Some(&"Baz"));
+    assert_eq!(arena.len(), 3);
+}
+
+#[test]
+fn test_secondary_map() {
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+
+    assert_eq!(map.insert(index0, 10), None);
+    assert_eq!(map.insert(index0, 11), Some(10));
+    assert_eq!(map.insert(index1, 20), None);
+    assert_eq!(map.len(), 2);
+
+    // unlinked maps are not affected by removal
+    arena.remove(index0);
+    assert_eq!(map.get(index0), Some(&11));
+
+    // newer generation replaces the old value
+    let index2 = arena.insert("Baz");
+    assert_eq!(map.get(index2), None);
+    assert_eq!(map.insert(index2, 30), None);
+    assert_eq!(map.get(index0), None);
+    assert_eq!(map.len(), 2);
+
+    *map.get_mut(index1).unwrap() += 1;
+    assert_eq!(map.remove(index1), Some(21));
+    assert_eq!(map.iter().collect::<Vec<_>>(), vec![(index2, &30)]);
+}
+
+#[test]
+fn test_secondary_map_join() {
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    map.insert(index0, 10);
+    map.insert(index2, 30);
+
+    for (_, value) in map.iter_mut() {
+        *value += 1;
+    }
+    map[index2] += 1;
+    assert_eq!(map[index0], 11);
+
+    // unlinked, so the stale value is skipped by the join only
+    arena.remove(index0);
+    let joined: Vec<_> = map.join(&arena).collect();
+    assert_eq!(joined, vec![(index2, &"Baz", &32)]);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(index1), None);
+}
+
+#[test]
+fn test_secondary_map_linked() {
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    arena.link(&mut map);
+
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    map.insert(index0, 10);
+    map.insert(index1, 20);
+    map.insert(index2, 30);
+
+    arena.remove(index0);
+    assert_eq!(map.get(index0), None);
+    assert_eq!(map.get(index1), Some(&20));
+
+    let (index3, _) = arena.replace(index1, "Qux");
+    assert_eq!(map.get(index1), None);
+    assert_eq!(map.get(index3), None);
+
+    map.flush();
+    assert_eq!(map.len(), 1);
+
+    // relocation keeps the value with the item
+    let moved = arena.move_slot(index2, 0).unwrap();
+    map.flush();
+    assert_eq!(map.get(index2), None);
+    assert_eq!(map.get(moved), Some(&30));
+
+    // clones of the arena don't feed the map
+    let mut clone = arena.clone();
+    clone.remove(moved);
+    map.flush();
+    assert_eq!(map.get(moved), Some(&30));
+
+    // dropped maps are unlinked
+    drop(map);
+    arena.remove(moved);
+}
+
+#[test]
+fn test_with_mut() {
+    let mut arena = Arena::new();
+    let index0 = arena.push(1);
+    let index1 = arena.push(2);
+    arena.remove(index1);
+
+    assert_eq!(arena.with_mut(index0, |item| std::mem::replace(item, 10)), Some(1));
+    assert_eq!(arena.with_mut(index1, |item| *item), None);
+    assert_eq!(arena.with(index0, |item| item * 2), Some(20));
+    assert_eq!(arena.with(index1, |item| item * 2), None);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_map() {
+    let mut arena = Arena::new();
+    let index0 = arena.push(1);
+    let index1 = arena.push(2);
+    let index2 = arena.push(3);
+    arena.remove(index1);
+
+    let mut mapped = arena.par_map(|index, item| (index, item * 10));
+    assert_eq!(mapped.len(), 2);
+    assert_eq!(mapped.get(index0), Some(&(index0, 10)));
+    assert_eq!(mapped.get(index1), None);
+    assert_eq!(mapped.get(index2), Some(&(index2, 30)));
+
+    // free list is carried over
+    let index3 = mapped.insert((index0, 0));
+    assert_eq!(index3.slot, index1.slot);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_iter() {
+    use rayon::prelude::*;
+
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..1000).map(|n| arena.insert(n)).collect();
+    for index in indices.iter().step_by(3) {
+        arena.remove(*index);
+    }
+    arena.reserve_index();
+
+    let expected: i32 = arena.iter().sum();
+    assert_eq!(arena.par_iter().sum::<i32>(), expected);
+
+    arena.par_iter_mut().for_each(|item| *item *= 2);
+    assert_eq!(arena.par_iter().sum::<i32>(), expected * 2);
+
+    arena
+        .par_iter_indexed_mut()
+        .for_each(|(index, item)| *item = index.slot as i32);
+    for (index, item) in arena.iter_indexed() {
+        assert_eq!(*item, index.slot as i32);
+    }
+}
+
+#[test]
+fn test_revisions() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    assert_eq!(arena.revision(index0), None);
+
+    arena.track_revisions();
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    assert_eq!(arena.revision(index0), Some(0));
+    let rev1 = arena.revision(index1).unwrap();
+    let rev2 = arena.revision(index2).unwrap();
+    assert!(rev1 < rev2);
+    assert_eq!(arena.current_revision(), rev2);
+
+    let changed: Vec<_> = arena.iter_changed_since(0).map(|(index, _)| index).collect();
+    assert_eq!(changed, vec![index1, index2]);
+
+    // shared access doesn't count as a change
+    let seen = arena.current_revision();
+    arena.get(index0);
+    assert_eq!(arena.iter_changed_since(seen).count(), 0);
+
+    arena.get_mut(index0);
+    let (index3, _) = arena.replace(index2, "Qux");
+    arena.remove(index1);
+    let changed: Vec<_> = arena.iter_changed_since(seen).map(|(index, _)| index).collect();
+    assert_eq!(changed, vec![index0, index3]);
+    assert_eq!(arena.revision(index1), None);
+
+    let seen = arena.current_revision();
+    arena.iter_mut().for_each(drop);
+    assert_eq!(arena.iter_changed_since(seen).count(), 2);
+
+    arena.untrack_revisions();
+    assert_eq!(arena.revision(index0), None);
+    assert_eq!(arena.iter_changed_since(0).count(), 0);
+}
+
+#[test]
+fn test_recent_insertions() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    assert_eq!(arena.last_inserted(), Some(index0));
+    assert_eq!(arena.recent_insertions().count(), 0);
+
+    arena.track_recent_insertions(3);
+    let index1 = arena.push("Bar");
+    arena.remove(index0);
+    let index2 = arena.insert("Baz");
+    let (index3, _) = arena.replace(index1, "Qux");
+    let index4 = arena.push("Quux");
+
+    assert_eq!(arena.last_inserted(), Some(index4));
+    assert_eq!(
+        arena.recent_insertions().collect::<Vec<_>>(),
+        vec![index4, index3, index2]
+    );
+
+    arena.track_recent_insertions(1);
+    assert_eq!(arena.recent_insertions().collect::<Vec<_>>(), vec![index4]);
+
+    arena.track_recent_insertions(0);
+    assert_eq!(arena.recent_insertions().count(), 0);
+    assert_eq!(arena.last_inserted(), Some(index4));
+}
+
+#[test]
+fn test_shrink_to() {
+    let mut arena = Arena::new();
+    arena.data.reserve(100);
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    arena.remove(index0);
+
+    arena.shrink_to(10);
+    assert!(arena.data.capacity() >= 10);
+    assert!(arena.data.capacity() < 100);
+
+    arena.shrink_to(0);
+    assert!(arena.data.capacity() >= 2);
+    assert_eq!(arena.get(index1), Some(&"Bar"));
+    assert_eq!(arena.insert("Baz").slot, index0.slot);
+}
+
+#[test]
+fn test_split_at_slot_mut() {
+    let mut arena = Arena::new();
+    let index0 = arena.push(1);
+    let index1 = arena.push(2);
+    let index2 = arena.push(3);
+    arena.remove(index1);
+
+    let (mut left, mut right) = arena.split_at_slot_mut(2);
+    assert_eq!((left.start_slot(), left.end_slot()), (0, 2));
+    assert_eq!((right.start_slot(), right.end_slot()), (2, 3));
+
+    assert_eq!(left.get(index2), None);
+    assert_eq!(right.get_mut(index0), None);
+    assert_eq!(left.get_mut(index1), None);
+    *left.get_mut(index0).unwrap() += 10;
+    *right.get_mut(index2).unwrap() += 20;
+
+    let items: Vec<_> = left.iter_mut().map(|(index, item)| (index, *item)).collect();
+    assert_eq!(items, vec![(index0, 11)]);
+    let items: Vec<_> = right.into_iter().map(|(index, item)| (index, *item)).collect();
+    assert_eq!(items, vec![(index2, 23)]);
+
+    let (left, right) = arena.split_at_slot_mut(3);
+    assert!(left.contains(index2));
+    assert_eq!(right.end_slot(), 3);
+}
+
+#[test]
+#[should_panic]
+fn test_split_at_slot_mut_out_of_bounds() {
+    let mut arena = Arena::new();
+    arena.push(1);
+
+    arena.split_at_slot_mut(2);
+}
+
+#[test]
+fn test_chunks_mut() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..5).map(|n| arena.push(n)).collect();
+
+    let ranges: Vec<_> = arena
+        .chunks_mut(2)
+        .map(|view| (view.start_slot(), view.end_slot()))
+        .collect();
+    assert_eq!(ranges, vec![(0, 2), (2, 4), (4, 5)]);
+
+    for mut view in arena.chunks_mut(2) {
+        let offset = view.start_slot();
+        for (index, item) in view.iter_mut() {
+            *item += offset * 10;
+            assert_eq!(index.slot() / 2 * 2, offset);
+        }
+    }
+    assert_eq!(arena.get(indices[3]), Some(&23));
+
+    let (left, right) = arena.split_at_slot_mut(1);
+    let (middle, rest) = right.split_at_mut(3);
+    assert_eq!((left.end_slot(), middle.start_slot(), middle.end_slot()), (1, 1, 3));
+    assert!(middle.contains(indices[2]));
+    assert!(!middle.contains(indices[3]));
+    assert_eq!(rest.start_slot(), 3);
+}
+
+#[test]
+fn test_from_vec() {
+    let mut arena = Arena::from(vec!["Foo", "Bar"]);
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.get(Index::from_parts(1, 1)), Some(&"Bar"));
+
+    let index2 = arena.insert("Baz");
+    assert_eq!(index2.slot, 2);
+}
+
+#[test]
+fn test_from_iter_and_extend() {
+    let mut arena: Arena<_> = (0..3).collect();
+    assert_eq!(arena.len(), 3);
+    assert_eq!(arena.get(Index::from_parts(2, 1)), Some(&2));
+
+    arena.remove(Index::from_parts(1, 1));
+    arena.extend(vec![10, 11]);
+    assert_eq!(arena.len(), 4);
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), vec![0, 10, 2, 11]);
+    assert!(arena.capacity() >= 4);
+}
+
+#[cfg(feature = "slab")]
+#[test]
+fn test_slab_round_trip() {
+    let mut slab = slab::Slab::new();
+    let key0 = slab.insert("Foo");
+    let key1 = slab.insert("Bar");
+    let key2 = slab.insert("Baz");
+    let key3 = slab.insert("Qux");
+    slab.remove(key0);
+    slab.remove(key2);
+
+    let mut arena = Arena::from(slab);
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.iter().collect::<Vec<_>>(), vec![&"Bar", &"Qux"]);
+    assert_eq!(arena.get(Index::from_parts(key1, 1)), Some(&"Bar"));
+    assert_eq!(arena.get(Index::from_parts(key3, 1)), Some(&"Qux"));
+
+    // both vacant slots are recycled
+    assert_eq!(arena.insert("Quux").slot, key0);
+    assert_eq!(arena.insert("Corge").slot, key2);
+    assert_eq!(arena.insert("Grault").slot, 4);
+
+    arena.remove(Index::from_parts(key1, 1));
+    let slab = slab::Slab::from(arena);
+    assert_eq!(slab.len(), 4);
+    assert!(!slab.contains(key1));
+    assert_eq!(slab[key3], "Qux");
+    assert_eq!(slab[4], "Grault");
+}
+
+#[test]
+fn test_stale_handler() {
+    use crate::{Access, StaleAccess};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static STALE: RefCell<Vec<StaleAccess>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(stale: StaleAccess) {
+        STALE.with(|log| log.borrow_mut().push(stale));
+    }
+
+    let mut arena = Arena::new();
+    arena.set_stale_handler(record);
+    assert!(arena.is_strict());
+
+    let index0 = arena.push("Foo");
+    arena.remove(index0);
+    let index1 = arena.insert("Bar");
+
+    // out of bounds and live accesses aren't reported
+    assert_eq!(arena.get(Index::from_parts(99, 1)), None);
+    assert_eq!(arena.get(index1), Some(&"Bar"));
+
+    assert_eq!(arena.get(index0), None);
+    arena.remove(index1);
+    assert_eq!(arena.get_mut(index1), None);
+
+    let log = STALE.with(|log| log.borrow().clone());
+    assert_eq!(
+        log,
+        vec![
+            StaleAccess {
+                access: Access::Get,
+                index: index0,
+                current: Some(index1.generation),
+            },
+            StaleAccess {
+                access: Access::GetMut,
+                index: index1,
+                current: None,
+            },
+        ]
+    );
+
+    arena.set_strict(false);
+    assert_eq!(arena.get(index0), None);
+    assert_eq!(STALE.with(|log| log.borrow().len()), 2);
+}
+
+#[test]
+#[should_panic(expected = "stale index")]
+fn test_strict_panics() {
+    let mut arena = Arena::new();
+    arena.set_strict(true);
+    let index0 = arena.push("Foo");
+    arena.remove(index0);
+
+    arena.remove(index0);
+}
+
+#[test]
+fn test_keys_snapshot() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    arena.remove(index1);
+
+    let keys = arena.keys_snapshot();
+    assert_eq!(keys, vec![index0, index2]);
+
+    for index in keys {
+        arena.remove(index);
+        arena.insert("Qux");
+    }
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.get(index0), None);
+    assert_eq!(arena.get(index2), None);
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn test_json_schema() {
+    let schema = schemars::schema_for!(Arena<Index>);
+    let value = schema.as_value();
+
+    assert_eq!(value["type"], "object");
+    assert_eq!(value["required"][0], "generation");
+    assert_eq!(value["required"][1], "entries");
+    assert_eq!(value["required"][2], "free");
+
+    // items refer to the index schema
+    let occupied = &value["properties"]["entries"]["items"]["anyOf"][1];
+    assert_eq!(occupied["properties"]["item"]["$ref"], "#/$defs/Index");
+    assert_eq!(value["$defs"]["Index"]["properties"]["generation"]["minimum"], 1);
+}
+
+#[test]
+fn test_compact_callback_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    arena.link(&mut map);
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    map.insert(index1, 1);
+    map.insert(index2, 2);
+    arena.remove(index0);
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        arena.compact(|_, _| panic!("relocation failed"));
+    }));
+    assert!(result.is_err());
+
+    // the arena is compacted and consistent despite the panic
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.data.len(), 2);
+    assert_eq!(arena.iter().collect::<Vec<_>>(), vec![&"Bar", &"Baz"]);
+    assert_eq!(arena.get(index1), None);
+    let index3 = arena.insert("Qux");
+    assert_eq!(index3.slot, 2);
+    assert_eq!(arena.len(), 3);
+
+    // linked maps saw every relocation
+    map.flush();
+    assert_eq!(map.get(index1), None);
+    assert_eq!(map.iter().map(|(_, value)| *value).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(map.iter().map(|(index, _)| index.slot).collect::<Vec<_>>(), vec![0, 1]);
+}
+
+#[test]
+fn test_with_mut_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut arena = Arena::new();
+    let index0 = arena.push(1);
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        arena.with_mut(index0, |item| {
+            *item = 2;
+            panic!("mutation failed");
+        })
+    }));
+    assert!(result.is_err());
+
+    assert_eq!(arena.len(), 1);
+    assert_eq!(arena.get(index0), Some(&2));
+    arena.remove(index0);
+    assert!(arena.is_empty());
+}
+
+#[test]
+fn test_spare_slots_mut() {
+    let mut arena = Arena::new();
+    let index0 = arena.push(String::from("Foo"));
+    let index1 = arena.push(String::from("Bar"));
+    arena.remove(index0);
+
+    let mut spare = arena.spare_slots_mut(3);
+    assert_eq!(spare.len(), 3);
+    spare.as_mut_slice()[0].write(String::from("Baz"));
+    spare.as_mut_slice()[1].write(String::from("Qux"));
+    let indices = unsafe { spare.commit(2) };
+
+    assert_eq!(indices.len(), 2);
+    assert_eq!(indices[0].slot, index0.slot);
+    assert_eq!(arena.len(), 3);
+    assert_eq!(arena.get(indices[0]).map(String::as_str), Some("Baz"));
+    assert_eq!(arena.get(indices[1]).map(String::as_str), Some("Qux"));
+    assert_eq!(arena.get(index1).map(String::as_str), Some("Bar"));
+
+    // abandoned buffers leave the arena untouched
+    let spare = arena.spare_slots_mut(2);
+    drop(spare);
+    assert_eq!(arena.len(), 3);
+}
+
+#[test]
+fn test_packed_index() {
+    use crate::{PackIndexError, PackedIndex};
+
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+
+    let packed16 = PackedIndex::<u16>::try_from(index0).unwrap();
+    let packed32 = PackedIndex::<u32>::try_from(index0).unwrap();
+    let packed = PackedIndex::<usize>::try_from(index0).unwrap();
+    assert_eq!(std::mem::size_of::<Option<PackedIndex<u16>>>(), 4);
+    assert_eq!(std::mem::size_of::<Option<PackedIndex<u32>>>(), 8);
+    assert_eq!((packed16.slot(), packed16.generation()), (0, 1));
+    assert_eq!(Index::from(packed16), index0);
+    assert_eq!(Index::from(packed32), index0);
+    assert_eq!(Index::from(packed), index0);
+
+    assert_eq!(
+        PackedIndex::<u16>::try_from(Index::from_parts(70_000, 1)),
+        Err(PackIndexError::SlotOverflow)
+    );
+    assert_eq!(
+        PackedIndex::<u16>::try_from(Index::from_parts(0, 70_000)),
+        Err(PackIndexError::GenerationOverflow)
+    );
+    assert_eq!(
+        PackedIndex::<u32>::try_from(index0.with_tag(1)),
+        Err(PackIndexError::Tagged)
+    );
+}
+
+#[test]
+fn test_packed_index_order() {
+    use crate::{CompactIndex, PackedIndex};
+
+    let indices = [
+        Index::from_parts(0, 1),
+        Index::from_parts(0, 3),
+        Index::from_parts(1, 1),
+        Index::from_parts(2, 2),
+    ];
+    let mut packed: Vec<PackedIndex<u16>> = indices.iter().rev().map(|&index| index.try_into().unwrap()).collect();
+    packed.sort();
+    assert_eq!(packed.into_iter().map(Index::from).collect::<Vec<_>>(), indices);
+
+    let mut compact: Vec<CompactIndex> = indices.iter().rev().map(|&index| index.try_into().unwrap()).collect();
+    compact.sort();
+    assert_eq!(compact.into_iter().map(Index::from).collect::<Vec<_>>(), indices);
+    assert!(CompactIndex::try_from(indices[1]).unwrap() < CompactIndex::try_from(indices[2]).unwrap());
+}
+
+#[test]
+fn test_memory_budget() {
+    let entry_size = std::mem::size_of::<crate::Entry<Vec<u8>>>();
+    let mut arena = Arena::<Vec<u8>>::new();
+    arena.set_memory_budget(entry_size * 4 + 100, |item| item.capacity());
+
+    let index0 = arena.try_insert(vec![0; 50]).unwrap();
+    assert_eq!(arena.data.capacity(), 4);
+    assert_eq!(arena.memory_usage(), entry_size * 4 + 50);
+
+    // item bytes over budget
+    let rejected = arena.try_insert(vec![0; 60]).unwrap_err();
+    assert_eq!(rejected.into_inner().len(), 60);
+    assert_eq!(arena.len(), 1);
+
+    arena.try_insert(vec![0; 50]).unwrap();
+    arena.try_insert(Vec::new()).unwrap();
+    arena.try_insert(Vec::new()).unwrap();
+
+    // storage growth over budget
+    assert!(arena.try_insert(Vec::new()).is_err());
+    assert_eq!(arena.data.capacity(), 4);
+
+    // freed slots and bytes can be reused
+    arena.remove(index0);
+    assert_eq!(arena.memory_usage(), entry_size * 4 + 50);
+    arena.try_insert(vec![0; 40]).unwrap();
+
+    arena.clear_memory_budget();
+    arena.insert(Vec::new());
+    assert_eq!(arena.len(), 5);
+}
+
+#[test]
+fn test_memory_budget_handler() {
+    fn evict(arena: &mut Arena<Vec<u8>>, needed: usize) {
+        assert!(needed > 0);
+        if let Some(index) = arena.keys_snapshot().first() {
+            arena.remove(*index);
+        }
+    }
+
+    let entry_size = std::mem::size_of::<crate::Entry<Vec<u8>>>();
+    let mut arena = Arena::<Vec<u8>>::new();
+    arena.set_memory_budget(entry_size * 4 + 100, |item| item.capacity());
+    arena.set_budget_handler(evict);
+
+    let index0 = arena.insert(vec![0; 60]);
+    let index1 = arena.insert(vec![0; 60]);
+    assert_eq!(arena.get(index0), None);
+    assert_eq!(arena.len(), 1);
+    assert_eq!(index1.slot, index0.slot);
+
+    // evicting isn't always enough
+    assert!(arena.try_insert(vec![0; 200]).is_err());
+}
+
+#[test]
+#[should_panic(expected = "memory budget exceeded")]
+fn test_memory_budget_insert_panics() {
+    let mut arena = Arena::<Vec<u8>>::new();
+    arena.set_memory_budget(0, |item| item.capacity());
+
+    arena.push(Vec::new());
+}
+
+#[test]
+fn test_memory_budget_replace_and_apply() {
+    use crate::ArenaEdit;
+
+    let entry_size = std::mem::size_of::<crate::Entry<Vec<u8>>>();
+    let mut arena = Arena::<Vec<u8>>::new();
+    arena.set_memory_budget(entry_size * 4 + 100, |item| item.capacity());
+    let index0 = arena.insert(vec![0; 60]);
+
+    // only the growth over the replaced item counts
+    let (index0, _) = arena.replace(index0, vec![0; 90]);
+    assert_eq!(arena.memory_usage(), entry_size * 4 + 90);
+
+    arena.apply([ArenaEdit::Changed {
+        index: index0,
+        item: vec![0; 100],
+    }]);
+    assert_eq!(arena.memory_usage(), entry_size * 4 + 100);
+
+    let index1 = arena.insert(Vec::new());
+    arena.remove(index1);
+    arena.apply([ArenaEdit::Added {
+        index: index1,
+        item: Vec::new(),
+    }]);
+    assert_eq!(arena.len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "memory budget exceeded")]
+fn test_memory_budget_replace_panics() {
+    let entry_size = std::mem::size_of::<crate::Entry<Vec<u8>>>();
+    let mut arena = Arena::<Vec<u8>>::new();
+    arena.set_memory_budget(entry_size * 4 + 100, |item| item.capacity());
+    let index = arena.insert(vec![0; 60]);
+
+    arena.replace(index, vec![0; 120]);
+}
+
+#[test]
+#[should_panic(expected = "memory budget exceeded")]
+fn test_memory_budget_replace_vacant_panics() {
+    let entry_size = std::mem::size_of::<crate::Entry<Vec<u8>>>();
+    let mut arena = Arena::<Vec<u8>>::new();
+    arena.set_memory_budget(entry_size * 4 + 100, |item| item.capacity());
+    let index = arena.insert(vec![0; 60]);
+    arena.insert(vec![0; 40]);
+    arena.remove(index);
+
+    arena.replace(index, vec![0; 80]);
+}
+
+#[test]
+#[should_panic(expected = "memory budget exceeded")]
+fn test_memory_budget_apply_changed_panics() {
+    use crate::ArenaEdit;
+
+    let entry_size = std::mem::size_of::<crate::Entry<Vec<u8>>>();
+    let mut arena = Arena::<Vec<u8>>::new();
+    arena.set_memory_budget(entry_size * 4 + 100, |item| item.capacity());
+    let index = arena.insert(vec![0; 60]);
+
+    arena.apply([ArenaEdit::Changed {
+        index,
+        item: vec![0; 120],
+    }]);
+}
+
+#[test]
+#[should_panic(expected = "memory budget exceeded")]
+fn test_memory_budget_apply_added_panics() {
+    let entry_size = std::mem::size_of::<crate::Entry<Vec<u8>>>();
+    let mut source = Arena::new();
+    source.insert(vec![0; 120]);
+    let mut arena = Arena::<Vec<u8>>::new();
+    arena.set_memory_budget(entry_size + 100, |item| item.capacity());
+
+    arena.apply(Arena::new().diff(&source));
+}
+
+#[test]
+#[should_panic(expected = "arena is full")]
+fn test_limit_apply_added_panics() {
+    let mut source = Arena::new();
+    source.insert("Foo");
+    source.insert("Bar");
+    let mut arena = Arena::with_limit(1);
+
+    arena.apply(Arena::new().diff(&source));
+}
+
+#[test]
+fn test_iter_mut_skips_vacant() {
+    let mut arena = Arena::new();
+    let index0 = arena.push(1);
+    let index1 = arena.push(2);
+    let index2 = arena.push(3);
+    arena.remove(index1);
+
+    for item in arena.iter_mut() {
+        *item *= 10;
+    }
+
+    assert_eq!(arena.iter_mut().count(), 2);
+    assert_eq!(arena.get(index0), Some(&10));
+    assert_eq!(arena.get(index2), Some(&30));
+    assert_eq!(arena.iter().collect::<Vec<_>>(), vec![&10, &30]);
+}
+
+#[test]
+fn test_iter_indexed() {
+    let mut arena = Arena::new();
+    let index0 = arena.push("Foo");
+    let index1 = arena.push("Bar");
+    let index2 = arena.push("Baz");
+    arena.remove(index1);
+
+    let items: Vec<_> = arena.iter_indexed().collect();
+    assert_eq!(items, vec![(index0, &"Foo"), (index2, &"Baz")]);
+
+    for (index, item) in arena.iter_indexed_mut() {
+        assert_eq!(index.slot, if *item == "Foo" { 0 } else { 2 });
+        *item = "Qux";
+    }
+    assert_eq!(arena.get(index0), Some(&"Qux"));
+    assert_eq!(arena.get(index2), Some(&"Qux"));
+}
+
+#[test]
+fn test_into_iter() {
+    let mut arena = Arena::new();
+    let index0 = arena.push(String::from("Foo"));
+    arena.push(String::from("Bar"));
+    arena.push(String::from("Baz"));
+    arena.remove(index0);
+
+    for item in &mut arena {
+        item.push('!');
+    }
+
+    let mut lengths = Vec::new();
+    for item in &arena {
+        lengths.push(item.len());
+    }
+    assert_eq!(lengths, vec![4, 4]);
+
+    let items: Vec<String> = arena.into_iter().collect();
+    assert_eq!(items, vec![String::from("Bar!"), String::from("Baz!")]);
+}
+
+#[test]
+fn test_retain() {
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..6).map(|i| arena.insert(i)).collect();
+
+    arena.retain(|_, item| item % 2 == 0);
+    assert_eq!(arena.len(), 3);
+    assert_eq!(arena.get(indices[1]), None);
+    assert_eq!(arena.get(indices[2]), Some(&2));
+
+    arena.retain_mut(|_, item| {
+        *item *= 10;
+        *item != 20
+    });
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), vec![0, 40]);
+
+    // Every removed slot must be back in the free list.
+    for i in 0..4 {
+        arena.insert(i);
+    }
+    assert_eq!(arena.len(), 6);
+    assert_eq!(arena.data.len(), 6);
+}
+
+#[test]
+fn test_drain() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(String::from("Foo"));
+    let index1 = arena.insert(String::from("Bar"));
+    let index2 = arena.insert(String::from("Baz"));
+    arena.remove(index1);
+
+    let mut drain = arena.drain();
+    assert_eq!(drain.size_hint(), (2, Some(2)));
+    assert_eq!(drain.next(), Some((index0, String::from("Foo"))));
+    drop(drain);
+
+    assert!(arena.is_empty());
+    assert_eq!(arena.get(index2), None);
+    assert_eq!(arena.data.len(), 3);
+
+    // Vacated slots are reused from the front.
+    let index3 = arena.insert(String::from("Qux"));
+    assert_eq!(index3.slot, 0);
+    assert_ne!(index3, index0);
+}
+
+#[test]
+fn test_extract_if() {
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..5).map(|i| arena.insert(i)).collect();
+
+    {
+        let mut extract = arena.extract_if(|_, item| *item % 2 == 1);
+        assert_eq!(extract.next(), Some((indices[1], 1)));
+    }
+
+    // Dropping the iterator early keeps the unvisited matches.
+    assert_eq!(arena.len(), 4);
+    assert_eq!(arena.get(indices[3]), Some(&3));
+
+    let rest: Vec<_> = arena.extract_if(|_, item| *item % 2 == 1).collect();
+    assert_eq!(rest, vec![(indices[3], 3)]);
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), vec![0, 2, 4]);
+}
+
+#[test]
+fn test_reserve() {
+    let mut arena = Arena::with_capacity(2);
+    assert!(arena.capacity() >= 2);
+
+    let index0 = arena.insert("Foo");
+    arena.insert("Bar");
+    arena.remove(index0);
+
+    // The vacant slot counts towards the reservation.
+    arena.reserve_exact(3);
+    assert!(arena.capacity() >= 4);
+
+    let capacity = arena.capacity();
+    for _ in 0..3 {
+        arena.insert("Baz");
+    }
+    assert_eq!(arena.capacity(), capacity);
+}
+
+#[test]
+fn test_clear() {
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    arena.link(&mut map);
+
+    let indices: Vec<Index> = (0..3).map(|i| arena.insert(i)).collect();
+    map.insert(indices[1], "Foo");
+    let capacity = arena.capacity();
+
+    arena.clear();
+    assert!(arena.is_empty());
+    assert_eq!(arena.capacity(), capacity);
+    assert_eq!(map.get(indices[1]), None);
+
+    for index in &indices {
+        assert_eq!(arena.insert(7).slot, index.slot);
+        assert_eq!(arena.get(*index), None);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(String::from("Foo"));
+    let index1 = arena.insert(String::from("Bar"));
+    let index2 = arena.insert(String::from("Baz"));
+    arena.remove(index0);
+    arena.remove(index2);
+    let tagged = index1.with_tag(5);
+    let reserved = arena.reserve_index();
+
+    let json = serde_json::to_string(&(&arena, tagged)).unwrap();
+    let (mut loaded, tagged): (Arena<String>, Index) = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(tagged.tag(), 5);
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded.get(index0), None);
+    assert_eq!(loaded.get(tagged.with_tag(0)), Some(&String::from("Bar")));
+
+    // The free list order and reservations are preserved.
+    assert!(loaded.is_reserved(reserved));
+    assert_eq!(loaded.insert(String::from("Qux")).slot, 0);
+    assert_eq!(loaded.insert(String::from("Qux")).slot, 3);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_rejects_corrupt_free_list() {
+    let json = r#"{
+        "generation": 1,
+        "entries": [null, { "generation": 1, "item": 7 }],
+        "free": [{ "slot": 1, "generation": 2 }]
+    }"#;
+    assert!(serde_json::from_str::<Arena<i32>>(json).is_err());
+
+    let json = r#"{
+        "generation": 1,
+        "entries": [null, null],
+        "free": [{ "slot": 0, "generation": 2 }, { "slot": 0, "generation": 2 }]
+    }"#;
+    assert!(serde_json::from_str::<Arena<i32>>(json).is_err());
+
+    let json = r#"{
+        "generation": 1,
+        "entries": [null, { "generation": 1, "item": 7 }],
+        "free": [{ "slot": 0, "generation": 2 }]
+    }"#;
+    let arena = serde_json::from_str::<Arena<i32>>(json).unwrap();
+    assert!(matches!(arena.data[0], crate::Entry::Vacant { generation, .. } if generation.get() == 2));
+}
+
+#[test]
+fn test_per_slot_generations() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    let index1 = arena.insert("Bar");
+
+    // Churn in one slot doesn't advance the other.
+    for _ in 0..3 {
+        let index = arena.insert("Baz");
+        arena.remove(index);
+    }
+    arena.remove(index0);
+    assert_eq!(arena.insert("Qux").generation.get(), 2);
+    assert_eq!(arena.get(index1), Some(&"Bar"));
+
+    // Slots dropped by compaction don't repeat their generations.
+    let index2 = arena.insert("Quux");
+    arena.remove(index2);
+    arena.compact(|_, _| {});
+    let index3 = arena.insert("Corge");
+    assert_eq!(index3.slot, index2.slot);
+    assert_eq!(arena.get(index2), None);
+}
+
+#[test]
+fn test_typed_arena() {
+    use crate::{TypedArena, TypedIndex};
+
+    struct Mesh(&'static str);
+
+    let mut meshes = TypedArena::<Mesh>::new();
+    let index0: TypedIndex<Mesh> = meshes.insert(Mesh("Foo"));
+    let index1 = meshes.insert(Mesh("Bar"));
+    assert_eq!(std::mem::size_of_val(&index0), std::mem::size_of::<Index>());
+
+    meshes.get_mut(index1).unwrap().0 = "Baz";
+    assert_eq!(meshes.take(index0).map(|mesh| mesh.0), Some("Foo"));
+    assert_eq!(meshes.get(index0).map(|mesh| mesh.0), None);
+
+    let items: Vec<_> = meshes.iter().map(|(index, mesh)| (index, mesh.0)).collect();
+    assert_eq!(items, vec![(index1, "Baz")]);
+    assert_eq!(
+        meshes.as_untyped().get(index1.untyped()).map(|mesh| mesh.0),
+        Some("Baz")
+    );
+}
+
+#[test]
+fn test_contains() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    assert!(arena.contains(index0));
+    assert!(!arena.contains(Index::from_parts(99, 1)));
+
+    arena.remove(index0);
+    let index1 = arena.insert("Bar");
+    assert!(!arena.contains(index0));
+    assert!(arena.contains(index1));
+}
+
+#[test]
+fn test_insert_with() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert_with(|index| (index, "Foo"));
+    let index1 = arena.insert_with(|index| (index, "Bar"));
+    arena.remove(index0);
+
+    // Reuses the free slot, with the slot's next generation.
+    let index2 = arena.insert_with(|index| (index, "Baz"));
+    assert_eq!(index2.slot, index0.slot);
+    assert_ne!(index2, index0);
+
+    for index in [index1, index2] {
+        assert_eq!(arena.get(index).unwrap().0, index);
+    }
+}
+
+#[test]
+fn test_reserve_index_and_fill() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    arena.remove(index0);
+
+    let reserved0 = arena.reserve_index();
+    let reserved1 = arena.reserve_index();
+    assert_eq!(reserved0.slot, index0.slot);
+    assert!(arena.is_reserved(reserved0));
+    assert!(arena.get(reserved0).is_none());
+    assert!(arena.is_empty());
+
+    // Reserved slots are not handed out by insertions.
+    let index1 = arena.insert("Bar");
+    assert_eq!(index1.slot, 2);
+
+    arena.fill(reserved1, "Baz");
+    assert_eq!(arena.get(reserved1), Some(&"Baz"));
+    assert_eq!(arena.len(), 2);
+
+    assert!(arena.unreserve(reserved0));
+    assert!(!arena.unreserve(reserved0));
+    let index2 = arena.insert("Qux");
+    assert_eq!(index2.slot, reserved0.slot);
+    assert_ne!(index2, reserved0);
+}
+
+#[test]
+#[should_panic(expected = "index is not a pending reservation")]
+fn test_fill_unreserved_slot() {
+    let mut arena = Arena::new();
+    let index = arena.insert("Foo");
+    arena.fill(index, "Bar");
+}
+
+#[test]
+fn test_reservations_across_compaction_and_clear() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(0);
+    let reserved = arena.reserve_index();
+    arena.remove(index0);
+
+    let mut moved = None;
+    arena.compact(|old, new| moved = Some((old, new)));
+    let (old, new) = moved.unwrap();
+    assert_eq!(old, reserved);
+    assert!(arena.is_reserved(new));
+
+    arena.clear();
+    assert!(!arena.is_reserved(new));
+    assert_ne!(arena.insert(1), new);
+}
+
+#[test]
+fn test_try_accessors() {
+    use crate::ArenaError;
+
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    let out_of_bounds = Index::from_parts(9, 1);
+
+    assert_eq!(
+        arena.try_take(out_of_bounds),
+        Err(ArenaError::OutOfBounds(out_of_bounds))
+    );
+    assert_eq!(arena.try_remove(index0), Ok(()));
+    assert_eq!(arena.try_take(index0), Err(ArenaError::Vacant(index0)));
+
+    let index1 = arena.insert("Bar");
+    assert_eq!(
+        arena.try_get_mut(index0),
+        Err(ArenaError::StaleGeneration {
+            index: index0,
+            current: index1.generation
+        })
+    );
+
+    arena.set_tag_checker(|tag, _| tag == 0);
+    assert_eq!(
+        arena.try_get(index1.with_tag(1)),
+        Err(ArenaError::TagMismatch(index1.with_tag(1)))
+    );
+
+    let reserved = arena.reserve_index();
+    assert_eq!(arena.try_get(reserved), Err(ArenaError::Pending(reserved)));
+}
+
+#[test]
+fn test_keys_and_values() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(1);
+    let index1 = arena.insert(2);
+    let index2 = arena.insert(3);
+    arena.remove(index1);
+    arena.reserve_index();
+
+    assert_eq!(arena.keys().collect::<Vec<_>>(), vec![index0, index2]);
+    assert_eq!(arena.values().rev().collect::<Vec<_>>(), vec![&3, &1]);
+    arena.values_mut().for_each(|item| *item *= 10);
+
+    let mut iter = arena.iter_indexed();
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next_back(), Some((index2, &30)));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next(), Some((index0, &10)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    assert_eq!(arena.iter_mut().len(), 2);
+    assert_eq!(
+        arena
+            .iter_indexed_mut()
+            .rev()
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>(),
+        vec![index2, index0]
+    );
+    assert_eq!(arena.clone().into_iter().rev().collect::<Vec<_>>(), vec![30, 10]);
+    assert_eq!(arena.into_iter().len(), 2);
+}
+
+#[test]
+fn test_insert_many() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(0);
+    let index1 = arena.insert(1);
+    arena.remove(index0);
+
+    let indices = arena.insert_many(2..5);
+    assert_eq!(indices.len(), 3);
+    assert_eq!(indices[0].slot, index0.slot);
+    assert_eq!(
+        indices.iter().map(|index| index.slot).collect::<Vec<_>>(),
+        vec![0, 2, 3]
+    );
+    for (index, item) in indices.iter().zip(2..5) {
+        assert_eq!(arena.get(*index), Some(&item));
+    }
+    assert_eq!(arena.get(index1), Some(&1));
+    assert!(arena.insert_many(core::iter::empty()).is_empty());
+}
+
+#[test]
+fn test_entry() {
+    use crate::SlotEntry;
+
+    let mut arena = Arena::new();
+    let index0 = arena.insert(1);
+    let index1 = arena.insert(2);
+    arena.remove(index1);
+
+    // occupied
+    assert_eq!(arena.entry(index0).unwrap().index(), index0);
+    *arena.entry(index0).unwrap().and_modify(|item| *item *= 10).or_insert(0) += 1;
+    assert_eq!(arena.get(index0), Some(&11));
+    match arena.entry(index0).unwrap() {
+        SlotEntry::Occupied(mut entry) => assert_eq!(entry.insert(5), 11),
+        SlotEntry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(arena.get(index0), Some(&5));
+
+    // freed slot gets a new index
+    let entry = arena.entry(index1).unwrap();
+    let index2 = entry.index();
+    assert_eq!(index2.slot, index1.slot);
+    assert_ne!(index2, index1);
+    assert_eq!(*entry.or_insert_with(|| 3), 3);
+    assert_eq!(arena.get(index2), Some(&3));
+    assert_eq!(arena.len(), 2);
+
+    // stale and out of bounds
+    assert!(arena.entry(index1).is_none());
+    assert!(arena.entry(Index::from_parts(9, 1)).is_none());
+
+    // reservation
+    let reserved = arena.reserve_index();
+    assert!(matches!(arena.entry(reserved), Some(SlotEntry::Vacant(_))));
+    assert_eq!(*arena.entry(reserved).unwrap().or_default(), 0);
+    assert_eq!(arena.get(reserved), Some(&0));
+
+    match arena.entry(index2).unwrap() {
+        SlotEntry::Occupied(entry) => assert_eq!(entry.remove(), 3),
+        SlotEntry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.insert(7).slot, index2.slot);
+}
+
+#[test]
+fn test_dense_arena() {
+    let mut arena = DenseArena::new();
+    let index0 = arena.insert(0);
+    let index1 = arena.insert(1);
+    let index2 = arena.insert(2);
+    assert_eq!(arena.len(), 3);
+
+    assert_eq!(arena.take(index0), Some(0));
+    assert_eq!(arena.take(index0), None);
+    assert_eq!(arena.as_slice(), &[2, 1]);
+    assert_eq!(arena.get(index2), Some(&2));
+    assert_eq!(arena.get(index1), Some(&1));
+
+    // the freed slot is reused with a new generation
+    let index3 = arena.insert(3);
+    assert_eq!(index3.slot, index0.slot);
+    assert_eq!(arena.get(index0), None);
+    assert_eq!(arena[index3], 3);
+
+    arena[index1] += 10;
+    let items: Vec<_> = arena.iter_indexed().map(|(index, item)| (index, *item)).collect();
+    assert_eq!(items, vec![(index2, 2), (index1, 11), (index3, 3)]);
+
+    // removing the last packed item moves nothing
+    arena.remove(index3);
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), vec![2, 11]);
+
+    arena.clear();
+    assert!(arena.is_empty());
+    assert!(!arena.contains(index1));
+    let index4 = arena.insert(4);
+    assert_eq!(
+        arena.iter_indexed_mut().map(|(index, _)| index).collect::<Vec<_>>(),
+        vec![index4]
+    );
+}
+
+#[test]
+fn test_compact_and_shrink_to_fit() {
+    let mut arena = Arena::with_capacity(16);
+    let indices: Vec<_> = (0..8).map(|n| arena.insert(n)).collect();
+    for index in &indices[..6] {
+        arena.remove(*index);
+    }
+
+    let mut moved = Vec::new();
+    arena.compact(|old, new| moved.push((old, new)));
+    assert_eq!(moved.len(), 2);
+    assert_eq!(moved[0].0, indices[6]);
+    assert_eq!(moved[1].1.slot, 1);
+    assert_eq!(arena.get(moved[1].1), Some(&7));
+
+    arena.shrink_to_fit();
+    assert!(arena.capacity() < 16);
+}
+
+#[test]
+fn test_truncate_vacant_tail() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..6).map(|n| arena.insert(n)).collect();
+    let reserved = arena.reserve_index();
+    for &slot in &[4, 1, 5, 3] {
+        arena.remove(indices[slot]);
+    }
+
+    // the reservation keeps its slot
+    assert_eq!(arena.truncate_vacant_tail(), 0);
+    arena.unreserve(reserved);
+    assert_eq!(arena.truncate_vacant_tail(), 4);
+    assert_eq!(arena.data.len(), 3);
+    assert_eq!(arena.get(indices[2]), Some(&2));
+
+    // the free list only holds slot 1, and dropped slots aren't reused by old indices
+    assert_eq!(arena.insert(10).slot, 1);
+    let index = arena.insert(11);
+    assert_eq!(index.slot, 3);
+    assert_eq!(arena.get(indices[3]), None);
+
+    arena.remove(index);
+    arena.shrink_to_fit();
+    assert_eq!(arena.data.len(), 3);
+    assert_eq!(arena.truncate_vacant_tail(), 0);
+}
+
+#[cfg(feature = "debug-checks")]
+#[test]
+#[should_panic(expected = "handed out by another arena")]
+fn test_index_from_another_arena() {
+    let mut arena0 = Arena::new();
+    let mut arena1 = Arena::new();
+    let index0 = arena0.insert("Foo");
+    arena1.insert("Bar");
+
+    arena1.get(index0);
+}
+
+#[cfg(feature = "debug-checks")]
+#[test]
+#[should_panic(expected = "handed out by another arena")]
+fn test_iterated_index_from_another_arena() {
+    let mut a = Arena::new();
+    let mut b = Arena::new();
+    a.insert("Foo");
+    b.insert("Bar");
+
+    let (index, _) = a.iter_indexed().next().unwrap();
+    b.get(index);
+}
+
+#[cfg(feature = "debug-checks")]
+#[test]
+fn test_handed_out_indices_are_stamped() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut a = Arena::new();
+    let mut b = Arena::new();
+    let (_, a_indices) = Arena::from_fn_with_indices(2, |n| n);
+    for n in 0..4 {
+        a.insert(n);
+        b.insert(n);
+    }
+
+    let rejected = |index: Index| catch_unwind(AssertUnwindSafe(|| b.contains(index))).is_err();
+    assert!(a_indices.iter().all(|&index| rejected(index)));
+    assert!(a.keys().all(rejected));
+    assert!(a.keys_snapshot().into_iter().all(rejected));
+    assert!(a.iter_indexed_mut().all(|(index, _)| rejected(index)));
+    assert!(a.iter_slots().all(|state| match state {
+        SlotState::Occupied { index, .. } => rejected(index),
+        _ => true,
+    }));
+    assert!(a.extract_if(|_, n| *n == 0).all(|(index, _)| rejected(index)));
+    a.retain(|index, _| {
+        assert!(rejected(index));
+        true
+    });
+    assert!(a.drain().all(|(index, _)| rejected(index)));
+}
+
+#[cfg(feature = "debug-checks")]
+#[test]
+fn test_index_arena_checks() {
+    let mut arena = Arena::new();
+    let index = arena.insert("Foo");
+
+    // clones share the slot layout, so their indices are interchangeable
+    let clone = arena.clone();
+    assert_eq!(clone.get(index), Some(&"Foo"));
+
+    // indices that weren't handed out by an arena are never rejected
+    let mut other = Arena::new();
+    other.insert("Bar");
+    assert_eq!(other.get(Index::from_parts(0, 1)), Some(&"Bar"));
+    assert_eq!(Index::from_parts(0, 1), index);
+}
+
+#[test]
+fn test_branded_scope() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(1);
+    let index1 = arena.insert(2);
+    arena.remove(index1);
+
+    let index2 = arena.scope(|mut arena| {
+        assert!(arena.brand(index1).is_none());
+        let branded0 = arena.brand(index0).unwrap();
+        let branded2 = arena.insert(3);
+        *arena.get_mut(branded0) += *arena.get(branded2);
+
+        let items: Vec<_> = arena.iter().map(|(index, item)| (index, *item)).collect();
+        assert_eq!(items, vec![(branded0, 4), (branded2, 3)]);
+        assert_eq!(arena.len(), 2);
+        branded2.index()
+    });
+
+    assert_eq!(index2.slot, index1.slot);
+    assert_eq!(arena.get(index0), Some(&4));
+    assert_eq!(arena.get(index2), Some(&3));
+}
+
+#[test]
+fn test_get_unchecked() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(1);
+    let index1 = arena.insert(2);
+
+    unsafe {
+        *arena.get_unchecked_mut(index1) += 10;
+        assert_eq!(*arena.get_unchecked(index0), 1);
+        assert_eq!(*arena.get_unchecked(index1), 12);
+    }
+}
+
+#[test]
+fn test_snapshot_restore() {
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    arena.link(&mut map);
+
+    let index0 = arena.insert("Foo");
+    let index1 = arena.insert("Bar");
+    let index2 = arena.insert("Baz");
+    arena.remove(index1);
+    let snapshot = arena.snapshot();
+    assert_eq!(snapshot.len(), 2);
+
+    arena.remove(index0);
+    let index3 = arena.insert("Qux");
+    let index4 = arena.insert("Quux");
+    map.insert(index2, 20);
+    map.insert(index4, 40);
+    *arena.get_mut(index2).unwrap() = "Corge";
+
+    arena.restore(&snapshot);
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.get(index0), Some(&"Foo"));
+    assert_eq!(arena.get(index2), Some(&"Baz"));
+    assert_eq!(arena.get(index3), None);
+    assert_eq!(arena.get(index4), None);
+    assert_eq!(map.get(index2), Some(&20));
+    assert_eq!(map.get(index4), None);
+
+    // the freed slot doesn't hand out the generation used after the snapshot
+    let index5 = arena.insert("Grault");
+    assert_eq!(index5.slot, index4.slot);
+    assert_ne!(index5, index4);
+
+    // slots truncated after the snapshot don't repeat their generations either
+    let snapshot = arena.snapshot();
+    arena.remove(index5);
+    arena.compact(|_, _| {});
+    arena.restore(&snapshot);
+    arena.remove(index5);
+    assert_ne!(arena.insert("Garply"), index5);
+}
+
+#[test]
+fn test_change_journal() {
+    use crate::ChangeKind;
+
+    let mut arena = Arena::new();
+    assert!(!arena.is_tracking_changes());
+    let index0 = arena.insert("Foo");
+    arena.track_changes();
+
+    let index1 = arena.insert("Bar");
+    let (index2, _) = arena.replace(index0, "Baz");
+    arena.remove(index1);
+    let moved = arena.move_slot(index2, 1).unwrap();
+    assert_eq!(
+        arena.take_changes(),
+        vec![
+            (index1, ChangeKind::Inserted),
+            (index2, ChangeKind::Replaced { previous: index0 }),
+            (index1, ChangeKind::Removed),
+            (moved, ChangeKind::Relocated { from: index2 }),
+        ]
+    );
+
+    let snapshot = arena.snapshot();
+    arena.clear();
+    let reserved = arena.reserve_index();
+    arena.fill(reserved, "Qux");
+    arena.restore(&snapshot);
+    assert_eq!(
+        arena.take_changes(),
+        vec![
+            (moved, ChangeKind::Removed),
+            (reserved, ChangeKind::Inserted),
+            (reserved, ChangeKind::Removed),
+            (moved, ChangeKind::Inserted),
+        ]
+    );
+
+    arena.untrack_changes();
+    arena.insert("Quux");
+    assert!(arena.take_changes().is_empty());
+}
+
+#[test]
+fn test_diff_and_apply() {
+    use crate::ArenaEdit;
+
+    let mut arena = Arena::new();
+    let index0 = arena.insert(0);
+    let index1 = arena.insert(1);
+    let index2 = arena.insert(2);
+
+    let mut other = arena.clone();
+    other.remove(index0);
+    let index3 = other.insert(3);
+    *other.get_mut(index2).unwrap() = 20;
+    let index4 = other.insert(4);
+    let index5 = other.insert(5);
+    other.remove(index4);
+
+    let edits = arena.diff(&other);
+    assert_eq!(
+        edits,
+        vec![
+            ArenaEdit::Removed { index: index0 },
+            ArenaEdit::Added { index: index3, item: 3 },
+            ArenaEdit::Changed {
+                index: index2,
+                item: 20
+            },
+            ArenaEdit::Added { index: index5, item: 5 },
+        ]
+    );
+    assert!(other.diff(&other).is_empty());
+
+    arena.apply(edits);
+    assert!(arena.diff(&other).is_empty());
+    assert_eq!(arena.len(), 4);
+    assert_eq!(arena.get(index1), Some(&1));
+    assert_eq!(arena.get(index5), Some(&5));
+
+    // the slot skipped over to add index5 is free
+    assert_eq!(arena.insert(6).slot, index4.slot);
+}
+
+#[test]
+#[should_panic(expected = "doesn't resolve")]
+fn test_apply_mismatched_edit() {
+    use crate::ArenaEdit;
+
+    let mut arena = Arena::new();
+    let index = arena.insert(0);
+    arena.remove(index);
+
+    arena.apply(vec![ArenaEdit::Changed { index, item: 1 }]);
+}
+
+#[test]
+fn test_remove_hook() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static REMOVED: RefCell<Vec<(Index, i32)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(index: Index, item: &i32) {
+        REMOVED.with(|removed| removed.borrow_mut().push((index, *item)));
+    }
+
+    fn take_removed() -> Vec<(Index, i32)> {
+        REMOVED.with(|removed| removed.take())
+    }
+
+    let mut arena = Arena::new();
+    arena.set_remove_hook(record);
+    let index0 = arena.insert(0);
+    let index1 = arena.insert(1);
+    let index2 = arena.insert(2);
+
+    arena.remove(index0);
+    assert_eq!(arena.take(index1), Some(1));
+    let (index3, _) = arena.replace(index2, 3);
+    assert_eq!(take_removed(), vec![(index0, 0), (index1, 1), (index2, 2)]);
+
+    let snapshot = arena.snapshot();
+    let index4 = arena.insert(4);
+    arena.restore(&snapshot);
+    assert_eq!(take_removed(), vec![(index4, 4)]);
+
+    let index5 = arena.insert(5);
+    arena.retain(|_, item| *item != 5);
+    arena.clear();
+    assert_eq!(take_removed(), vec![(index5, 5), (index3, 3)]);
+
+    arena.clear_remove_hook();
+    arena.insert(6);
+    arena.clear();
+    assert!(take_removed().is_empty());
+}
+
+#[test]
+fn test_invalidate_all() {
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    arena.link(&mut map);
+
+    let index0 = arena.insert("Foo");
+    let index1 = arena.insert("Bar");
+    let index2 = arena.insert("Baz");
+    arena.remove(index1);
+    map.insert(index2, 20);
+
+    let fresh: Vec<_> = arena.invalidate_all().collect();
+    assert_eq!(fresh.len(), 2);
+    assert_eq!(arena.get(index0), None);
+    assert_eq!(arena.get(index2), None);
+    assert_eq!(arena.get(fresh[0]), Some(&"Foo"));
+    assert_eq!(fresh[1].slot, index2.slot);
+    map.flush();
+    assert_eq!(map.get(fresh[1]), Some(&20));
+
+    // removing after invalidation still advances the generation
+    arena.remove(fresh[0]);
+    let index3 = arena.insert("Qux");
+    assert_ne!(index3, index0);
+    assert_ne!(index3, fresh[0]);
+}
+
+#[test]
+fn test_stats() {
+    let mut arena = Arena::with_capacity(8);
+    let stats = arena.stats();
+    assert_eq!(stats.capacity, 8);
+    assert_eq!(stats.high_water_mark, 0);
+    assert_eq!(stats.fragmentation, 0.0);
+
+    let indices: Vec<_> = (0..4).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[0]);
+    arena.remove(indices[2]);
+    let reserved = arena.reserve_index();
+
+    let stats = arena.stats();
+    assert_eq!(stats.occupied, 2);
+    assert_eq!(stats.reserved, 1);
+    assert_eq!(stats.vacant, 1);
+    assert_eq!(stats.free_list_len, 1);
+    assert_eq!(stats.high_water_mark, 4);
+    assert_eq!(stats.memory_usage, arena.memory_usage());
+
+    arena.fill(reserved, 10);
+    arena.remove(indices[3]);
+    arena.shrink_to_fit();
+    let stats = arena.stats();
+    assert_eq!(stats.high_water_mark, 3);
+    assert_eq!(stats.vacant, 1);
+}
+
+#[test]
+fn test_validate() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..4).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[1]);
+    arena.remove(indices[3]);
+    arena.reserve_index();
+    assert_eq!(arena.validate(), Ok(()));
+
+    let mut broken = arena.clone();
+    broken.count += 1;
+    assert_eq!(
+        broken.validate(),
+        Err(ValidationError::CountMismatch { count: 3, occupied: 2 })
+    );
+
+    let mut broken = arena.clone();
+    broken.free_head = Some(0);
+    assert_eq!(broken.validate(), Err(ValidationError::FreeListNotVacant(0)));
+
+    let mut broken = arena.clone();
+    broken.free_head = Some(7);
+    assert_eq!(broken.validate(), Err(ValidationError::FreeListOutOfBounds(7)));
+
+    let mut broken = arena.clone();
+    broken.free_head = None;
+    assert_eq!(broken.validate(), Err(ValidationError::Unlinked(1)));
+
+    let mut broken = arena.clone();
+    let head = broken.free_head.unwrap();
+    if let crate::Entry::Vacant { next, .. } = &mut broken.data[head] {
+        *next = Some(head);
+    }
+    assert_eq!(broken.validate(), Err(ValidationError::FreeListCycle(head)));
+}
+
+#[test]
+fn test_generation_overflow() {
+    use crate::GenerationOverflow;
+    use core::num::NonZeroUsize;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    fn exhausted(policy: GenerationOverflow) -> (Arena<&'static str>, Index) {
+        let mut arena = Arena::with_overflow_policy(policy);
+        let index = arena.insert("Foo");
+        arena.insert("Bar");
+        if let crate::Entry::Occupied { generation, .. } = &mut arena.data[0] {
+            *generation = NonZeroUsize::MAX;
+        }
+        (arena, Index::new(0, NonZeroUsize::MAX).with_tag(index.tag()))
+    }
+
+    let (mut arena, index) = exhausted(GenerationOverflow::Saturate);
+    arena.remove(index);
+    assert_eq!(arena.insert("Baz"), index);
+
+    let (mut arena, index) = exhausted(GenerationOverflow::Wrap);
+    arena.remove(index);
+    assert_eq!(arena.insert("Baz").generation, NonZeroUsize::MIN);
+
+    let (mut arena, index) = exhausted(GenerationOverflow::Retire);
+    arena.remove(index);
+    let index2 = arena.insert("Baz");
+    assert_eq!(index2.slot(), 2);
+    assert_eq!(arena.get(index), None);
+    assert_eq!(arena.stats().retired, 1);
+    assert_eq!(arena.validate(), Ok(()));
+
+    // retired slots survive truncation and compaction
+    arena.remove(index2);
+    arena.truncate_vacant_tail();
+    arena.compact(|_, _| {});
+    assert_eq!(arena.stats().retired, 1);
+    assert_eq!(arena.validate(), Ok(()));
+    let index3 = arena.insert("Qux");
+    assert_ne!(index3.slot(), 0);
+    assert_eq!(arena.validate(), Ok(()));
+
+    let (mut arena, index) = exhausted(GenerationOverflow::Panic);
+    let result = catch_unwind(AssertUnwindSafe(|| arena.remove(index)));
+    assert!(result.is_err());
+    assert_eq!(arena.get(index), Some(&"Foo"));
+    assert_eq!(arena.validate(), Ok(()));
+}
+
+#[test]
+fn test_reuse_policy() {
+    use crate::ReusePolicy;
+
+    let mut lifo = Arena::new();
+    let mut fifo = Arena::with_reuse_policy(ReusePolicy::Fifo);
+    for arena in [&mut lifo, &mut fifo] {
+        let indices: Vec<_> = (0..4).map(|n| arena.insert(n)).collect();
+        for &index in &indices[..3] {
+            arena.remove(index);
+        }
+    }
+
+    let slots = |arena: &mut Arena<i32>| (0..3).map(|n| arena.insert(n).slot()).collect::<Vec<_>>();
+    assert_eq!(slots(&mut lifo), [2, 1, 0]);
+    assert_eq!(slots(&mut fifo), [0, 1, 2]);
+
+    // the remembered tail is recovered after the free list is rebuilt
+    let indices: Vec<_> = fifo.iter_indexed().map(|(index, _)| index).collect();
+    fifo.remove(indices[3]);
+    fifo.remove(indices[1]);
+    fifo.compact(|_, _| {});
+    fifo.remove(fifo.keys().next().unwrap());
+    let index = fifo.keys().next().unwrap();
+    fifo.remove(index);
+    assert_eq!(fifo.validate(), Ok(()));
+    assert_eq!(fifo.insert(10).slot(), 0);
+    assert_eq!(fifo.insert(11).slot(), 1);
+    assert_eq!(fifo.validate(), Ok(()));
+}
+
+#[test]
+fn test_fixed_arena() {
+    use crate::FixedArena;
+
+    let mut arena = FixedArena::<i32, 3>::new();
+    assert_eq!(arena.capacity(), 3);
+    let index0 = arena.insert(1);
+    let index1 = arena.insert(2);
+    let index2 = arena.insert(3);
+    assert!(arena.is_full());
+    assert_eq!(arena.try_insert(4).unwrap_err().into_inner(), 4);
+
+    assert_eq!(arena.take(index1), Some(2));
+    assert_eq!(arena.take(index1), None);
+    let index3 = arena.insert(5);
+    assert_eq!(index3.slot(), index1.slot());
+    assert_ne!(index3, index1);
+    assert_eq!(arena.get(index1), None);
+
+    *arena.get_mut(index0).unwrap() += 10;
+    assert!(arena.iter().eq(&[11, 5, 3]));
+    assert_eq!(arena.iter().rev().count(), 3);
+    assert_eq!(arena.keys().collect::<Vec<_>>(), [index0, index3, index2]);
+    arena[index2] = 7;
+    assert_eq!(arena.iter_indexed().next_back(), Some((index2, &7)));
+
+    arena.clear();
+    assert!(arena.is_empty());
+    assert_eq!(arena.get(index0), None);
+    let index4 = arena.insert(8);
+    assert_eq!(index4.slot(), 0);
+    assert_ne!(index4, index0);
+
+    let empty = FixedArena::<i32, 0>::new();
+    assert!(empty.is_full());
+}
+
+#[test]
+fn test_limit() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut arena = Arena::with_limit(2);
+    let index0 = arena.insert(1);
+    let reserved = arena.reserve_index();
+    arena.push(2);
+    assert!(arena.is_full());
+    assert_eq!(arena.try_insert(3).map_err(|full| full.into_inner()), Err(3));
+
+    let result = catch_unwind(AssertUnwindSafe(|| arena.insert(3)));
+    assert!(result.is_err());
+    let result = catch_unwind(AssertUnwindSafe(|| arena.fill(reserved, 3)));
+    assert!(result.is_err());
+    assert_eq!(arena.len(), 2);
+
+    // replacing an item doesn't add one
+    let (index0, _) = arena.replace(index0, 10);
+    arena.remove(index0);
+    arena.fill(reserved, 3);
+    assert!(arena.is_full());
+
+    arena.set_limit(Some(1));
+    assert!(arena.is_full());
+    arena.set_limit(None);
+    assert!(!arena.is_full());
+    arena.insert(4);
+    assert_eq!(arena.len(), 3);
+}
+
+#[test]
+fn test_cache_arena() {
+    use crate::CacheArena;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static EVICTED: RefCell<Vec<(Index, i32)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn on_evict(index: Index, item: i32) {
+        EVICTED.with(|evicted| evicted.borrow_mut().push((index, item)));
+    }
+
+    let mut cache = CacheArena::new(3);
+    cache.set_eviction_handler(on_evict);
+    let index0 = cache.insert(0);
+    let index1 = cache.insert(1);
+    let index2 = cache.insert(2);
+    assert_eq!(cache.oldest(), Some(index0));
+
+    *cache.get_mut(index0).unwrap() += 10;
+    assert_eq!(cache.peek(index1), Some(&1));
+    assert_eq!(cache.oldest(), Some(index1));
+
+    let index3 = cache.insert(3);
+    assert_eq!(cache.peek(index1), None);
+    assert_eq!(cache.newest(), Some(index3));
+    assert_eq!(cache.oldest(), Some(index2));
+    EVICTED.with(|evicted| assert_eq!(*evicted.borrow(), [(index1, 1)]));
+
+    // removed items aren't reported
+    cache.remove(index2);
+    assert_eq!(cache.oldest(), Some(index0));
+    cache.insert(4);
+    assert_eq!(cache.len(), 3);
+    EVICTED.with(|evicted| assert_eq!(evicted.borrow().len(), 1));
+
+    cache.set_capacity(1);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.iter().collect::<Vec<_>>(), [&4]);
+    EVICTED.with(|evicted| assert_eq!(evicted.borrow()[1..], [(index0, 10), (index3, 3)]));
+}
+
+#[test]
+fn test_pin_arena() {
+    use crate::PinArena;
+    use core::marker::PhantomPinned;
+    use core::pin::Pin;
+
+    struct SelfRef {
+        value: i32,
+        this: *const SelfRef,
+        _pinned: PhantomPinned,
+    }
+
+    let mut arena: PinArena<_, 16> = PinArena::new();
+    let index0 = arena.insert(SelfRef {
+        value: 1,
+        this: core::ptr::null(),
+        _pinned: PhantomPinned,
+    });
+    let mut node = arena.get_pin_mut(index0).unwrap();
+    let this: *const SelfRef = &*node;
+    unsafe { Pin::get_unchecked_mut(node.as_mut()).this = this };
+
+    let indices: Vec<_> = (0..200)
+        .map(|value| {
+            arena.insert(SelfRef {
+                value,
+                this: core::ptr::null(),
+                _pinned: PhantomPinned,
+            })
+        })
+        .collect();
+    assert_eq!(arena.capacity(), 208);
+    let node = arena.get(index0).unwrap();
+    assert_eq!(node.this, node as *const SelfRef);
+
+    assert!(arena.remove(indices[10]));
+    assert!(!arena.remove(indices[10]));
+    let index1 = arena.insert(SelfRef {
+        value: 99,
+        this: core::ptr::null(),
+        _pinned: PhantomPinned,
+    });
+    assert_eq!(index1.slot(), indices[10].slot());
+    assert_eq!(arena.len(), 201);
+    assert_eq!(arena.iter().map(|node| node.value).nth(11), Some(99));
+    assert_eq!(arena.iter_indexed().nth(11).map(|(index, _)| index), Some(index1));
+
+    arena.clear();
+    assert!(arena.is_empty());
+    assert!(arena.get(index0).is_none());
+
+    let mut values: PinArena<_> = PinArena::new();
+    let index = values.insert(1);
+    *values.get_mut(index).unwrap() += 1;
+    assert_eq!(values.take(index), Some(2));
+    assert_eq!(values.get(index), None);
+}
+
+#[test]
+fn test_chunked_arena() {
+    use crate::ChunkedArena;
+    use core::marker::PhantomPinned;
+
+    struct Node {
+        value: i32,
+        _pinned: PhantomPinned,
+    }
+
+    let node = |value| Node {
+        value,
+        _pinned: PhantomPinned,
+    };
+
+    let mut arena: ChunkedArena<_, 4> = ChunkedArena::new();
+    let indices: Vec<_> = (0..10).map(|n| arena.insert(node(n))).collect();
+    assert_eq!(arena.capacity(), 12);
+
+    // items aren't pinned, so they can be mutated and moved out
+    arena.get_mut(indices[1]).unwrap().value = 10;
+    assert_eq!(arena.take(indices[2]).map(|node| node.value), Some(2));
+    assert!(arena.take(indices[2]).is_none());
+
+    arena.retain(|_, node| node.value % 2 == 0);
+    for node in arena.iter_mut() {
+        node.value += 1;
+    }
+    let kept: Vec<_> = arena.iter_indexed_mut().map(|(index, _)| index).collect();
+    assert_eq!(kept, [indices[0], indices[1], indices[4], indices[6], indices[8]]);
+    assert_eq!(
+        arena.iter().map(|node| node.value).collect::<Vec<_>>(),
+        [1, 11, 5, 7, 9]
+    );
+    assert_eq!(arena.keys().count(), arena.len());
+
+    arena.remove(indices[0]);
+    assert!(!arena.contains(indices[0]));
+    arena.clear();
+    assert!(arena.is_empty());
+}
+
+#[cfg(feature = "allocator-api2")]
+#[test]
+fn test_pin_arena_allocator() {
+    use crate::pin::{Allocator, Global};
+    use crate::PinArena;
+    use allocator_api2::alloc::{AllocError, Layout};
+    use core::cell::Cell;
+    use core::ptr::NonNull;
+
+    #[derive(Clone, Copy)]
+    struct Counting<'a>(&'a Cell<usize>);
+
+    unsafe impl Allocator for Counting<'_> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.set(self.0.get() + layout.size());
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.set(self.0.get() - layout.size());
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    let bytes = Cell::new(0);
+    let mut arena: PinArena<u64, 8, _> = PinArena::new_in(Counting(&bytes));
+    assert_eq!(bytes.get(), 0);
+
+    let index = arena.insert(1);
+    let chunk = bytes.get();
+    assert!(chunk >= 8 * core::mem::size_of::<u64>());
+    for n in 0..8 {
+        arena.insert(n);
+    }
+    assert_eq!(bytes.get(), 2 * chunk);
+    assert_eq!(arena.get(index), Some(&1));
+
+    drop(arena);
+    assert_eq!(bytes.get(), 0);
+}
+
+#[test]
+fn test_freeze() {
+    use crate::ArenaReader;
+
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let mut arena = Arena::new();
+    let index0 = arena.insert(1);
+    let index1 = arena.insert(2);
+    arena.remove(index0);
+
+    let reader = arena.freeze();
+    assert_send_sync(&reader);
+    let copy: ArenaReader<'_, i32> = reader;
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            assert_eq!(copy.get(index0), None);
+            assert_eq!(copy.get(index1), Some(&2));
+        });
+        scope.spawn(move || assert_eq!(reader.iter_indexed().collect::<Vec<_>>(), [(index1, &2)]));
+    });
+    assert_eq!(reader.len(), 1);
+}
+
+#[test]
+fn test_concurrent_arena() {
+    let arena = ConcurrentArena::with_shards(4);
+
+    let indices: Vec<Index> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..8)
+            .map(|n| {
+                let arena = &arena;
+                scope.spawn(move || (0..50).map(|m| arena.insert(n * 50 + m)).collect::<Vec<_>>())
+            })
+            .collect();
+        workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+    });
+    assert_eq!(arena.len(), 400);
+    for (n, index) in indices.iter().enumerate() {
+        assert_eq!(arena.with(*index, |item| *item), Some(n));
+    }
+
+    std::thread::scope(|scope| {
+        for chunk in indices.chunks(100) {
+            let arena = &arena;
+            scope.spawn(move || {
+                for index in chunk.iter().step_by(2) {
+                    arena.remove(*index);
+                }
+            });
+        }
+    });
+    assert_eq!(arena.len(), 200);
+    assert!(!arena.contains(indices[0]));
+    assert_eq!(arena.take(indices[1]), Some(1));
+
+    // Reused slots hand out fresh generations within their shard.
+    let index = arena.insert(1000);
+    assert!(indices.iter().all(|old| arena.with(*old, |item| *item) != Some(1000)));
+    assert_eq!(arena.with_mut(index, |item| std::mem::replace(item, 2000)), Some(1000));
+
+    let mut arena = arena;
+    *arena.get_mut(index).unwrap() += 1;
+    let mut sum = 0;
+    arena.for_each(|index, item| {
+        assert!(usize::from(index.tag()) < arena.shard_count());
+        sum += item;
+    });
+    assert_eq!(sum, (0..400).filter(|n| n % 2 == 1 && *n != 1).sum::<usize>() + 2001);
+}
+
+#[test]
+fn test_rc_arena() {
+    use crate::rc::Strong;
+
+    struct Node {
+        value: u32,
+        children: Vec<Strong<Node>>,
+    }
+
+    let arena = RcArena::new();
+    let leaf = arena.insert(Node {
+        value: 1,
+        children: vec![],
+    });
+    let root = arena.insert(Node {
+        value: 0,
+        children: vec![leaf.clone(), leaf.clone()],
+    });
+    assert_eq!(leaf.strong_count(), 3);
+    assert_eq!(arena.len(), 2);
+
+    // Handles can be cloned and dropped while items are borrowed.
+    let weak = leaf.downgrade();
+    {
+        let mut node = root.borrow_mut();
+        node.value = 10;
+        node.children.pop();
+        let extra = weak.upgrade().unwrap();
+        assert_eq!(extra.strong_count(), 3);
+    }
+    drop(leaf);
+    assert_eq!(root.borrow().value, 10);
+    assert_eq!(weak.upgrade().unwrap().borrow().value, 1);
+
+    // Removal is deferred while the arena is borrowed.
+    let other = arena.insert(Node {
+        value: 2,
+        children: vec![],
+    });
+    let other_weak = other.downgrade();
+    {
+        let node = root.borrow();
+        drop(other);
+        assert!(other_weak.upgrade().is_none());
+        assert_eq!(node.value, 10);
+    }
+    assert_eq!(arena.len(), 2);
+
+    // Dropping the root releases its children.
+    drop(root);
+    assert!(weak.upgrade().is_none());
+    assert!(arena.is_empty());
+
+    let reused = arena.insert(Node {
+        value: 3,
+        children: vec![],
+    });
+    assert!(weak.upgrade().is_none());
+    assert_eq!(reused.strong_count(), 1);
+}
+
+#[test]
+fn test_arena_list() {
+    use crate::list::{ArenaList, Linked, Links};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[derive(Debug)]
+    struct Item(u32, Links);
+
+    impl Linked for Item {
+        fn links(&self) -> &Links {
+            &self.1
+        }
+        fn links_mut(&mut self) -> &mut Links {
+            &mut self.1
+        }
+    }
+
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..4).map(|n| arena.insert(Item(n, Links::new()))).collect();
+    let values = |list: &ArenaList, arena: &Arena<Item>| list.iter(arena).map(|(_, item)| item.0).collect::<Vec<_>>();
+
+    let mut list = ArenaList::new();
+    list.push_back(&mut arena, indices[1]);
+    list.push_front(&mut arena, indices[0]);
+    list.push_back(&mut arena, indices[3]);
+    list.insert_before(&mut arena, indices[3], indices[2]);
+    assert_eq!(values(&list, &arena), [0, 1, 2, 3]);
+    assert_eq!(list.iter(&arena).rev().map(|(index, _)| index).collect::<Vec<_>>(), {
+        let mut reversed = indices.clone();
+        reversed.reverse();
+        reversed
+    });
+    assert_eq!(list.len(), 4);
+
+    list.move_to_front(&mut arena, indices[2]);
+    list.move_to_back(&mut arena, indices[0]);
+    assert_eq!(values(&list, &arena), [2, 1, 3, 0]);
+    assert!(list.unlink(&mut arena, indices[1]));
+    assert!(!list.unlink(&mut arena, indices[1]));
+    assert!(!arena[indices[1]].links().is_linked());
+    list.insert_after(&mut arena, indices[2], indices[1]);
+    assert_eq!(values(&list, &arena), [2, 1, 3, 0]);
+
+    let result = catch_unwind(AssertUnwindSafe(|| list.push_back(&mut arena, indices[1])));
+    assert!(result.is_err());
+
+    // Unlinking the end of another list is caught.
+    let mut other = ArenaList::new();
+    let lone = arena.insert(Item(4, Links::new()));
+    other.push_back(&mut arena, lone);
+    let result = catch_unwind(AssertUnwindSafe(|| list.unlink(&mut arena, lone)));
+    assert!(result.is_err());
+
+    assert_eq!(list.pop_front(&mut arena), Some(indices[2]));
+    assert_eq!(list.pop_back(&mut arena), Some(indices[0]));
+    list.clear(&mut arena);
+    assert!(list.is_empty());
+    assert_eq!(list.head(), None);
+    assert!(arena.iter().all(|item| item.0 == 4 || !item.links().is_linked()));
+}
+
+#[test]
+fn test_arena_graph() {
+    use crate::graph::{ArenaGraph, Direction};
+
+    let mut graph = ArenaGraph::new();
+    let a = graph.add_node('a');
+    let b = graph.add_node('b');
+    let c = graph.add_node('c');
+    let ab = graph.add_edge(a, b, 1);
+    let bc = graph.add_edge(b, c, 2);
+    let ca = graph.add_edge(c, a, 3);
+    let bb = graph.add_edge(b, b, 4);
+
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.edge_count(), 4);
+    assert_eq!(graph.neighbors(b).collect::<Vec<_>>(), [c, b]);
+    assert_eq!(
+        graph.neighbors_directed(b, Direction::Incoming).collect::<Vec<_>>(),
+        [a, b]
+    );
+    assert_eq!(graph.edges_directed(a, Direction::Incoming).collect::<Vec<_>>(), [ca]);
+    assert_eq!(graph.find_edge(b, c), Some(bc));
+    assert_eq!(graph.find_edge(c, b), None);
+    assert_eq!(graph.edge_endpoints(ab), Some((a, b)));
+    graph[bc] *= 10;
+    graph[a] = 'A';
+    assert_eq!(
+        graph.edges().map(|(_, weight)| *weight).collect::<Vec<_>>(),
+        [1, 20, 3, 4]
+    );
+
+    // Removing a node cleans up its edges, including loops.
+    assert_eq!(graph.remove_node(b), Some('b'));
+    assert!(!graph.contains_node(b));
+    assert!(!graph.contains_edge(ab) && !graph.contains_edge(bc) && !graph.contains_edge(bb));
+    assert_eq!(graph.edge_count(), 1);
+    assert_eq!(graph.neighbors(a).count(), 0);
+    assert_eq!(
+        graph.neighbors_directed(a, Direction::Incoming).collect::<Vec<_>>(),
+        [c]
+    );
+    assert_eq!(graph.neighbors(b).count(), 0);
+
+    assert_eq!(graph.remove_edge(ca), Some(3));
+    assert_eq!(graph.remove_edge(ca), None);
+    assert_eq!(graph.neighbors(c).count(), 0);
+    assert_eq!(graph.nodes().map(|(_, weight)| *weight).collect::<Vec<_>>(), ['A', 'c']);
+}
+
+#[test]
+fn test_join() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(0);
+    let index1 = arena.insert(1);
+    let index2 = arena.insert(2);
+    let index3 = arena.insert(3);
+
+    let mut names = SecondaryMap::new();
+    let mut scales = SecondaryMap::new();
+    for index in [index0, index1, index3] {
+        names.insert(index, "name");
+    }
+    for index in [index1, index2, index3] {
+        scales.insert(index, 10);
+    }
+
+    // A newer item in the same slot doesn't match.
+    arena.remove(index3);
+    let index4 = arena.insert(4);
+    assert_eq!(index4.slot(), index3.slot());
+    scales.insert(index4, 100);
+
+    let joined: Vec<_> = join((&arena, &names)).map(|(index, item, _)| (index, *item)).collect();
+    assert_eq!(joined, [(index0, 0), (index1, 1)]);
+
+    for (_, item, scale) in join((&mut arena, &scales)) {
+        *item *= scale;
+    }
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [0, 10, 20, 400]);
+
+    let joined: Vec<_> = join((&names, &mut scales, &arena))
+        .map(|(index, _, _, item)| (index, *item))
+        .collect();
+    assert_eq!(joined, [(index1, 10)]);
+}
+
+#[test]
+fn test_map() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(1);
+    let index1 = arena.insert(2);
+    let index2 = arena.insert(3);
+    arena.remove(index1);
+
+    let mirror = arena.map_ref(|index, item| (index, item * 10));
+    assert_eq!(mirror.get(index0), Some(&(index0, 10)));
+    assert_eq!(mirror.get(index1), None);
+    assert_eq!(mirror.get(index2), Some(&(index2, 30)));
+    assert_eq!(mirror.len(), 2);
+
+    // Both arenas reuse the same slot next, with the same generation.
+    let mut mapped = arena.clone().map(|_, item| item.to_string());
+    let index3 = arena.insert(4);
+    assert_eq!(mapped.insert("4".to_string()), index3);
+    assert_eq!(mapped.iter().cloned().collect::<Vec<_>>(), ["1", "4", "3"]);
+}
+
+#[test]
+fn test_append() {
+    let mut world = Arena::new();
+    let index0 = world.insert(0);
+    let index1 = world.insert(1);
+    world.remove(index0);
+
+    let mut chunk = Arena::new();
+    let chunk0 = chunk.insert(10);
+    let chunk1 = chunk.insert(11);
+    let chunk2 = chunk.insert(12);
+    chunk.remove(chunk1);
+    let tagged = chunk2.with_tag(7);
+
+    let remap = world.append(chunk);
+    assert_eq!(remap.len(), 2);
+    assert_eq!(world.len(), 3);
+    assert_eq!(world.get(index1), Some(&1));
+
+    let new0 = remap.translate(chunk0).unwrap();
+    assert_eq!(new0.slot(), index0.slot());
+    assert_eq!(world[new0], 10);
+    assert_eq!(remap.translate(chunk1), None);
+    assert_eq!(remap.translate(tagged).unwrap().tag(), 7);
+
+    let mut handle = chunk2;
+    assert!(remap.patch(&mut handle));
+    assert_eq!(world[handle], 12);
+    let mut stale = chunk1;
+    assert!(!remap.patch(&mut stale));
+    assert_eq!(stale, chunk1);
+}
+
+#[test]
+fn test_split_off_if() {
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..6).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[2]);
+
+    let (odd, remap) = arena.split_off_if(|_, item| item % 2 == 1);
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [0, 4]);
+    assert_eq!(odd.iter().copied().collect::<Vec<_>>(), [1, 3, 5]);
+    assert_eq!(remap.len(), 3);
+    for old in [indices[1], indices[3], indices[5]] {
+        assert!(!arena.contains(old));
+        assert_eq!(odd.get(remap.translate(old).unwrap()), Some(&(old.slot())));
+    }
+    assert_eq!(remap.translate(indices[0]), None);
+    assert_eq!(remap.translate(indices[2]), None);
+
+    // Splitting off everything, and merging it back, round trips.
+    let (all, remap) = arena.split_off_if(|_, _| true);
+    assert!(arena.is_empty());
+    let back = arena.append(all);
+    assert_eq!(arena[back.translate(remap.translate(indices[4]).unwrap()).unwrap()], 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_binary_format() {
+    use std::io::{Read, Write};
+
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..5).map(|n| arena.insert(format!("item {}", n))).collect();
+    arena.remove(indices[3]);
+    arena.remove(indices[1]);
+    let reserved = arena.reserve_index();
+    arena.remove(indices[0]);
+
+    let mut bytes = Vec::new();
+    arena.write_to(&mut bytes).unwrap();
+    assert_eq!(&bytes[..5], b"ARNA\x01");
+
+    let mut loaded = Arena::<String>::read_from(bytes.as_slice()).unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.get(indices[2]), Some(&"item 2".to_string()));
+    assert_eq!(loaded.get(indices[0]), None);
+    loaded.fill(reserved, "reserved".to_string());
+    arena.fill(reserved, "reserved".to_string());
+    // The free list is restored in order.
+    assert_eq!(loaded.insert("a".to_string()), arena.insert("a".to_string()));
+    assert_eq!(loaded.insert("b".to_string()), arena.insert("b".to_string()));
+
+    // Items can be encoded by a callback instead.
+    let mut bytes = Vec::new();
+    arena
+        .write_to_with(&mut bytes, |item, writer| writer.write_all(&[item.len() as u8]))
+        .unwrap();
+    let lengths = Arena::read_from_with(bytes.as_slice(), |reader| {
+        let mut len = [0];
+        reader.read_exact(&mut len).map(|_| len[0])
+    })
+    .unwrap();
+    assert_eq!(lengths[indices[2]], 6);
+
+    // Truncated and inconsistent data is rejected.
+    assert!(Arena::<String>::read_from(&bytes[..bytes.len() - 1]).is_err());
+    let mut bytes = Vec::new();
+    Arena::<u8>::new().write_to(&mut bytes).unwrap();
+    bytes[4] = 2;
+    let error = Arena::<u8>::read_from(bytes.as_slice()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut broken = Arena::new();
+    broken.insert(1u8);
+    let mut bytes = Vec::new();
+    broken.write_to(&mut bytes).unwrap();
+    // Point the free list at the occupied slot.
+    bytes[13..21].copy_from_slice(&0u64.to_le_bytes());
+    let error = Arena::<u8>::read_from(bytes.as_slice()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_compacted() {
+    use crate::Compacted;
+
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..5).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[0]);
+    arena.remove(indices[3]);
+    arena.reserve_index();
+
+    let json = serde_json::to_string(&arena).unwrap();
+    let Compacted {
+        arena: mut packed,
+        remap,
+    } = serde_json::from_str::<Compacted<i32>>(&json).unwrap();
+    assert_eq!(packed.len(), 3);
+    assert_eq!(packed.fragmentation(), 0.0);
+    assert_eq!(remap.len(), 3);
+
+    for (slot, old) in [indices[1], indices[2], indices[4]].into_iter().enumerate() {
+        let new = remap.translate(old).unwrap();
+        assert_eq!(new.slot(), slot);
+        assert_eq!(packed[new], old.slot() as i32);
+    }
+    assert_eq!(remap.translate(indices[0]), None);
+    assert_eq!(packed.insert(5).slot(), 3);
+
+    // The exact layout path is unaffected.
+    let exact: Arena<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(exact.get(indices[4]), Some(&4));
+}
+
+#[test]
+fn test_index_bits() {
+    use crate::CompactIndex;
+
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    arena.remove(index0);
+    let index1 = arena.insert("Bar");
+    assert_eq!(index1.slot(), 0);
+    assert_eq!(index1.generation(), 2);
+
+    let bits = index1.to_bits().unwrap();
+    assert_eq!(bits, 2 << 32);
+    assert_eq!(Index::from_bits(bits), Some(index1));
+    assert_eq!(arena.get(Index::from_bits(index0.to_bits().unwrap()).unwrap()), None);
+
+    let compact = CompactIndex::try_from(index1).unwrap();
+    assert_eq!(compact.to_bits(), bits);
+    assert_eq!(CompactIndex::from_bits(bits), Some(compact));
+
+    assert_eq!(Index::from_bits(7), None);
+    assert_eq!(index1.with_tag(1).to_bits(), None);
+}
+
+#[test]
+fn test_c_index() {
+    use crate::CIndex;
+
+    let mut arena = Arena::new();
+    let index = arena.insert("Foo");
+    let handle = CIndex::try_from(index).unwrap();
+    assert_eq!(handle, CIndex { slot: 0, generation: 1 });
+    assert_eq!(handle.to_index(), Some(index));
+    assert!(CIndex::NULL.is_null());
+    assert_eq!(CIndex::NULL.to_index(), None);
+    assert!(CIndex::try_from(index.with_tag(1)).is_err());
+    assert_eq!(
+        CIndex {
+            slot: u64::MAX,
+            generation: 1
+        }
+        .to_index(),
+        None
+    );
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn test_c_api() {
+    use crate::ffi::*;
+    use crate::CIndex;
+
+    let mut items = [10u32, 20u32];
+    unsafe {
+        let arena = arena_new();
+        let index0 = arena_insert(arena, &mut items[0] as *mut u32 as *mut _);
+        let index1 = arena_insert(arena, &mut items[1] as *mut u32 as *mut _);
+        assert_eq!(arena_len(arena), 2);
+        assert_eq!(*(arena_get(arena, index1) as *mut u32), 20);
+
+        assert_eq!(arena_remove(arena, index0) as *mut u32, &mut items[0] as *mut u32);
+        assert!(arena_remove(arena, index0).is_null());
+        assert!(arena_get(arena, index0).is_null());
+        assert!(!arena_contains(arena, index0));
+        assert!(arena_contains(arena, index1));
+        assert!(arena_get(arena, CIndex::NULL).is_null());
+        assert!(arena_get(
+            arena,
+            CIndex {
+                slot: 99,
+                generation: 1
+            }
+        )
+        .is_null());
+
+        arena_free(arena);
+        arena_free(core::ptr::null_mut());
+    }
+}
+
+#[test]
+fn test_arena_eq() {
+    let mut arena0 = Arena::new();
+    let mut arena1 = Arena::new();
+    assert_eq!(arena0, arena1);
+
+    let index0 = arena0.insert(1);
+    let index1 = arena1.insert(1);
+    assert_eq!(arena0, arena1);
+
+    // Different generations in the same slot aren't equal.
+    arena1.remove(index1);
+    let other = arena1.insert(1);
+    assert_ne!(arena0, arena1);
+    assert!(arena0.items_eq(&arena1));
+
+    arena0.remove(index0);
+    assert_eq!(arena0.insert(1), other);
+    assert_eq!(arena0, arena1);
+
+    // Trailing vacant slots and settings are ignored.
+    let extra = arena1.insert(2);
+    arena1.remove(extra);
+    arena1.set_limit(Some(10));
+    assert_eq!(arena0, arena1);
+
+    *arena1.get_mut(other).unwrap() = 3;
+    assert_ne!(arena0, arena1);
+    assert!(!arena0.items_eq(&arena1));
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn test_arbitrary_arena() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let mut state = 0x2545_f491_u32;
+    let bytes: Vec<u8> = (0..1024)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect();
+    let mut u = Unstructured::new(&bytes);
+    let arena = Arena::<u8>::arbitrary(&mut u).unwrap();
+
+    assert!(arena.validate().is_ok());
+    assert!(!arena.is_empty());
+    // Interleaved removals advance the generations of reused slots.
+    assert!(arena.keys().any(|index| index.generation() > 1));
+}
+
+#[test]
+fn test_find() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(3);
+    let index1 = arena.insert(8);
+    let index2 = arena.insert(12);
+
+    assert_eq!(arena.find(|item| *item > 5), Some(index1));
+    arena.remove(index1);
+    assert_eq!(arena.find(|item| *item > 5), Some(index2));
+    assert_eq!(arena.find(|item| *item > 50), None);
+
+    assert_eq!(arena.find_map(|index, item| (*item < 5).then_some(index)), Some(index0));
+    assert_eq!(arena.find_map(|_, item| u32::checked_sub(*item, 20)), None);
+}
+
+#[test]
+fn test_swap() {
+    use crate::ArenaError;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    let index1 = arena.insert("Bar");
+    let index2 = arena.insert("Baz");
+
+    arena.swap(index0, index2);
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), ["Baz", "Bar", "Foo"]);
+    arena.swap(index1, index1);
+    assert_eq!(arena[index1], "Bar");
+
+    arena.remove(index1);
+    assert_eq!(arena.try_swap(index0, index1), Err(ArenaError::Vacant(index1)));
+    assert_eq!(arena.try_swap(index1, index1), Err(ArenaError::Vacant(index1)));
+    let index3 = arena.insert("Qux");
+    assert!(matches!(
+        arena.try_swap(index1, index3),
+        Err(ArenaError::StaleGeneration { index, .. }) if index == index1
+    ));
+    assert_eq!(arena.try_swap(index2, index3), Ok(()));
+    assert_eq!(arena[index2], "Qux");
+
+    let result = catch_unwind(AssertUnwindSafe(|| arena.swap(index0, index1)));
+    assert!(result.is_err());
+    assert_eq!(arena[index0], "Baz");
+}
+
+#[test]
+fn test_swap_slots() {
+    use crate::MoveError;
+
+    let mut arena = Arena::new();
+    let mut names = SecondaryMap::new();
+    arena.link(&mut names);
+
+    let index0 = arena.insert(10);
+    let index1 = arena.insert(11);
+    let index2 = arena.insert(12);
+    names.insert(index0, "zero");
+    names.insert(index2, "two");
+
+    let (new0, new2) = arena.swap_slots(index0, index2).unwrap();
+    assert_eq!((new0.slot(), new2.slot()), (2, 0));
+    assert_eq!(arena.get(index0), None);
+    assert_eq!(arena.get(index2), None);
+    assert_eq!(arena[new0], 10);
+    assert_eq!(arena[new2], 12);
+    assert_eq!(names.get(index0), None);
+
+    names.flush();
+    assert_eq!(names.get(new0), Some(&"zero"));
+    assert_eq!(names.get(new2), Some(&"two"));
+    assert_eq!(names.len(), 2);
+
+    assert_eq!(arena.swap_slots(index1, index1), Ok((index1, index1)));
+    assert_eq!(arena.swap_slots(index0, index1), Err(MoveError::Stale(index0)));
+    arena.remove(index1);
+    assert_eq!(arena.swap_slots(new0, index1), Err(MoveError::Stale(index1)));
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [12, 10]);
+}
+
+#[test]
+fn test_first_last() {
+    let mut arena = Arena::new();
+    assert_eq!(arena.first(), None);
+    assert_eq!(arena.last(), None);
+    assert_eq!(arena.pop_last(), None);
+
+    let indices: Vec<Index> = (0..4).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[0]);
+    arena.remove(indices[3]);
+    assert_eq!(arena.first(), Some((indices[1], &1)));
+    assert_eq!(arena.last(), Some((indices[2], &2)));
+
+    assert_eq!(arena.pop_last(), Some((indices[2], 2)));
+    assert_eq!(arena.pop_last(), Some((indices[1], 1)));
+    assert_eq!(arena.pop_last(), None);
+    assert!(arena.is_empty());
+
+    // Popped slots are reused like removed ones.
+    let index = arena.insert(5);
+    assert_eq!(index.slot(), indices[1].slot());
+    assert_eq!(arena.get(indices[1]), None);
+}
+
+#[test]
+fn test_cursor_mut() {
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..6).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[1]);
+
+    let mut cursor = arena.cursor_mut();
+    assert!(cursor.current().is_none());
+    let mut visited = Vec::new();
+    while let Some((index, item)) = cursor.move_next() {
+        visited.push(index);
+        match *item {
+            2 => assert_eq!(cursor.remove(), Some(2)),
+            3 => {
+                assert_eq!(cursor.replace(30), Some(3));
+                assert_ne!(cursor.index(), Some(index));
+                assert_eq!(cursor.current().map(|(_, item)| *item), Some(30));
+            }
+            _ => *item *= 10,
+        }
+        assert_eq!(cursor.as_arena().get(indices[1]), None);
+    }
+    assert!(cursor.current().is_none());
+    assert_eq!(cursor.remove(), None);
+
+    assert_eq!(visited, [indices[0], indices[2], indices[3], indices[4], indices[5]]);
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [0, 30, 40, 50]);
+    assert_eq!(arena.get(indices[3]), None);
+}
+
+#[test]
+fn test_iter_slots() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(0);
+    let index1 = arena.insert(1);
+    let index2 = arena.insert(2);
+    arena.remove(index1);
+    let reserved = arena.reserve_index();
+    arena.remove(index0);
+    assert_eq!(reserved.slot(), index1.slot());
+
+    assert_eq!(arena.slot_count(), 3);
+    let slots: Vec<_> = arena.iter_slots().collect();
+    assert_eq!(
+        slots,
+        [
+            SlotState::Vacant { slot: 0 },
+            SlotState::Reserved { index: reserved },
+            SlotState::Occupied {
+                index: index2,
+                item: &2
+            },
+        ]
+    );
+    assert_eq!(arena.iter_slots().len(), arena.slot_count());
+    assert_eq!(
+        arena.iter_slots().rev().map(|state| state.slot()).collect::<Vec<_>>(),
+        [2, 1, 0]
+    );
+    assert_eq!(Arena::<u32>::new().iter_slots().next(), None);
+}
+
+#[test]
+fn test_box_arena() {
+    let mut arena = BoxArena::<[u32]>::new();
+    let index0 = arena.insert_boxed(Box::new([1, 2, 3]));
+    let index1 = arena.insert_boxed(vec![4].into_boxed_slice());
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.get(index0), Some(&[1, 2, 3][..]));
+
+    arena[index1][0] = 40;
+    for (_, items) in arena.iter_mut() {
+        items[0] += 1;
+    }
+    let items: Vec<_> = arena
+        .iter()
+        .map(|(index, items)| (index, items.len(), items[0]))
+        .collect();
+    assert_eq!(items, [(index0, 3, 2), (index1, 1, 41)]);
+
+    assert_eq!(arena.take(index0).as_deref(), Some(&[2, 2, 3][..]));
+    assert!(!arena.contains(index0));
+    assert_eq!(arena.get(index0), None);
+    let index2 = arena.insert_boxed(Box::new([]));
+    assert_eq!(index2.slot(), index0.slot());
+    assert_ne!(index2, index0);
+
+    let mut sized = BoxArena::new();
+    let index = sized.insert("Foo");
+    assert_eq!(sized.into_arena().get(index).map(|item| **item), Some("Foo"));
+}
+
+#[test]
+fn test_id_allocator() {
+    let mut ids = IdAllocator::with_capacity(4);
+    let id0 = ids.alloc();
+    let id1 = ids.alloc();
+    let id2 = ids.alloc();
+    assert_eq!(ids.len(), 3);
+
+    assert!(ids.free(id1));
+    assert!(!ids.free(id1));
+    assert!(!ids.contains(id1));
+    assert_eq!(ids.iter().collect::<Vec<_>>(), [id0, id2]);
+
+    let id3 = ids.alloc();
+    assert_eq!(id3.slot(), id1.slot());
+    assert_eq!(id3.generation(), id1.generation() + 1);
+    assert!(!ids.contains(id1));
+    assert!(ids.contains(id3));
+
+    ids.clear();
+    assert!(ids.is_empty());
+    assert_eq!(ids.iter().next(), None);
+    assert!([id0, id2, id3].iter().all(|id| !ids.contains(*id)));
+    let id4 = ids.alloc();
+    assert_eq!(ids.len(), 1);
+    assert!(!ids.contains(Index::new(7, id4.generation)));
+}
+
+#[cfg(feature = "slab-compat")]
+#[test]
+fn test_slab_compat() {
+    use crate::compat::Slab;
+
+    let mut slab = Slab::with_capacity(2);
+    let key0 = slab.insert("Foo");
+    let key1 = slab.insert("Bar");
+    assert_eq!((key0, key1), (0, 1));
+    assert_eq!(slab.vacant_key(), 2);
+    assert_eq!(slab.len(), 2);
+
+    let index1 = slab.index(key1).unwrap();
+    assert_eq!(slab.try_remove(key1), Some("Bar"));
+    assert_eq!(slab.try_remove(key1), None);
+    assert!(!slab.contains(key1));
+    assert_eq!(slab.vacant_key(), key1);
+
+    assert_eq!(slab.insert("Baz"), key1);
+    assert_eq!(slab[key1], "Baz");
+    assert_eq!(slab.as_arena().get(index1), None);
+
+    slab[key0] = "Qux";
+    slab.retain(|key, _| key != key1);
+    assert_eq!(slab.iter().collect::<Vec<_>>(), [(key0, &"Qux")]);
+    assert_eq!(slab.get(7), None);
+}
+
+#[test]
+fn test_vec_option_round_trip() {
+    let mut arena = Arena::from(vec![Some(0), None, Some(2), None]);
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.slot_count(), 4);
+
+    let index = arena.insert(4);
+    assert!(index.slot() == 1 || index.slot() == 3);
+    let slots: Vec<Option<u32>> = arena.into();
+    assert_eq!(slots.iter().flatten().count(), 3);
+    assert_eq!((slots[0], slots[2]), (Some(0), Some(2)));
+    assert_eq!(slots[index.slot()], Some(4));
+
+    let arena = Arena::from_iter_dense(0..3);
+    let index1 = arena.iter_indexed().nth(1).unwrap().0;
+    let (items, indices) = arena.clone().into_vec_with_indices();
+    assert_eq!(items, [0, 1, 2]);
+    assert_eq!(indices[1], index1);
+    assert_eq!(arena.into_vec(), items);
+}
+
+#[test]
+fn test_sort_by() {
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    arena.link(&mut map);
+
+    let mut indices: Vec<Index> = [5, 3, 9, 1, 7].iter().map(|&n| arena.insert(n)).collect();
+    for &index in &indices {
+        map.insert(index, arena[index] * 10);
+    }
+    arena.remove(indices[2]);
+    let stale = indices.remove(2);
+    let reserved = arena.reserve_index();
+    assert_eq!(reserved.slot(), 2);
+
+    let remap = arena.sort_by_key(|&n| n);
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [1, 3, 5, 7]);
+    assert_eq!(remap.translate(stale), None);
+    // 3 stays in slot 1.
+    assert_eq!(remap.translate(indices[1]), None);
+    assert_eq!(arena.get(indices[1]), Some(&3));
+    assert_eq!(remap.len(), 4);
+
+    let new_reserved = remap.translate(reserved).unwrap();
+    assert_eq!(new_reserved.slot(), 4);
+    assert!(arena.is_reserved(new_reserved));
+    assert!(!arena.is_reserved(reserved));
+
+    map.flush();
+    for index in &mut indices {
+        let old = *index;
+        remap.patch(index);
+        assert_eq!(arena.get(old).is_some(), old == *index);
+        assert_eq!(map.get(*index), Some(&(arena[*index] * 10)));
+    }
+    assert_eq!(map.len(), 4);
+    assert!(arena.validate().is_ok());
+
+    let again = arena.sort_by(|a, b| a.cmp(b));
+    assert!(again.is_empty());
+}
+
+#[test]
+fn test_iter_since() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(0);
+    let start = arena.epoch();
+    assert_eq!(arena.iter_since(start).count(), 0);
+
+    arena.track_epochs();
+    assert!(arena.is_tracking_epochs());
+    assert_eq!(arena.epoch(), start);
+    assert_eq!(arena.iter_since(start).count(), 0);
+
+    arena.insert(1);
+    let watermark = arena.epoch();
+    assert!(watermark > start);
+    arena.insert(2);
+    let index3 = arena.insert(3);
+    arena.remove(index0);
+    arena.replace(index3, 30);
+
+    let since = |arena: &Arena<u32>, epoch| arena.iter_since(epoch).map(|(_, item)| *item).collect::<Vec<_>>();
+    assert_eq!(since(&arena, start), [1, 2, 30]);
+    assert_eq!(since(&arena, watermark), [2, 30]);
+
+    // Moved items keep their epoch.
+    arena.compact(|_, _| {});
+    assert_eq!(since(&arena, watermark), [2, 30]);
+    arena.sort_by(|a, b| b.cmp(a));
+    assert_eq!(since(&arena, watermark), [30, 2]);
+    assert_eq!(since(&arena, arena.epoch()), [] as [u32; 0]);
+
+    arena.untrack_epochs();
+    assert_eq!(arena.iter_since(start).count(), 0);
+}
+
+#[test]
+fn test_persistent_arena() {
+    let mut arena = PersistentArena::new();
+    let indices: Vec<Index> = (0..150).map(|n| arena.insert(n)).collect();
+
+    let saved = arena.clone();
+    assert!(arena.ptr_eq(&saved));
+    arena.remove(indices[3]);
+    *arena.get_mut(indices[70]).unwrap() = 700;
+    assert!(!arena.ptr_eq(&saved));
+
+    // Only the modified chunks were copied.
+    assert!(core::ptr::eq(&arena[indices[140]], &saved[indices[140]]));
+    assert!(!core::ptr::eq(&arena[indices[4]], &saved[indices[4]]));
+
+    let reused = arena.insert(300);
+    assert_eq!(reused.slot(), indices[3].slot());
+    assert_eq!(arena.get(indices[3]), None);
+    assert_eq!(saved.get(indices[3]), Some(&3));
+    assert_eq!(saved.get(reused), None);
+    assert_eq!(saved[indices[70]], 70);
+
+    assert_eq!(arena.len(), 150);
+    assert_eq!(arena.iter().len(), 150);
+    assert_eq!(arena.iter().nth(3), Some((reused, &300)));
+    assert_eq!(
+        arena.iter().map(|(_, item)| *item).sum::<u32>(),
+        saved.iter().map(|(_, item)| *item).sum::<u32>() + 297 + 630
+    );
+
+    arena.clear();
+    assert!(arena.is_empty());
+    assert_eq!(arena.iter().next(), None);
+    assert_eq!(saved.len(), 150);
+}
+
+#[test]
+fn test_dangling_index() {
+    let mut arena = Arena::new();
+    arena.insert("Foo");
+    assert_eq!(Index::default(), Index::DANGLING);
+    assert!(Index::DANGLING.with_tag(3).is_dangling());
+    assert!(!arena.insert("Bar").is_dangling());
+
+    assert!(!arena.contains(Index::DANGLING));
+    assert_eq!(arena.get(Index::DANGLING), None);
+    assert!(arena.try_get(Index::DANGLING).is_err());
+    assert_eq!(arena.len(), 2);
+}
+
+#[test]
+fn test_remove_all() {
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    arena.link(&mut map);
+    let indices: Vec<Index> = (0..10).map(|n| arena.insert(n)).collect();
+    for &index in &indices {
+        map.insert(index, ());
+    }
+
+    let stale = indices[0];
+    arena.remove(stale);
+    let out_of_bounds = Index::from_parts(20, 1);
+    let batch = indices
+        .iter()
+        .copied()
+        .step_by(2)
+        .chain([stale, out_of_bounds, Index::DANGLING]);
+    assert_eq!(arena.remove_all(batch), 4);
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [1, 3, 5, 7, 9]);
+    assert_eq!(arena.remove_all([]), 0);
+
+    map.flush();
+    assert_eq!(map.len(), 5);
+    assert!(arena.validate().is_ok());
+}
+
+#[test]
+fn test_get_or_insert_with() {
+    let mut arena = Arena::new();
+    let live = arena.insert(1);
+    let stale = arena.insert(2);
+    arena.remove(stale);
+    let reserved = arena.reserve_index();
+    assert_eq!(reserved.slot(), stale.slot());
+
+    let (index, item) = arena.get_or_insert_with(live, || 10).unwrap();
+    assert_eq!((index, *item), (live, 1));
+
+    // The slot of the stale index now holds a reservation for another index.
+    assert_eq!(arena.get_or_insert_with(stale, || 20), None);
+    let (index, item) = arena.get_or_insert_with(reserved, || 30).unwrap();
+    *item += 1;
+    assert_eq!(index, reserved);
+    assert_eq!(arena.get(reserved), Some(&31));
+
+    arena.remove(live);
+    let (index, _) = arena.get_or_insert_with(live, || 40).unwrap();
+    assert_ne!(index, live);
+    assert_eq!(index.slot(), live.slot());
+    assert_eq!(arena.get(index), Some(&40));
+    assert_eq!(arena.get_or_insert_with(Index::from_parts(9, 1), || 50), None);
+    assert_eq!(arena.len(), 2);
+}
+
+#[test]
+fn test_small_arena() {
+    let mut arena = crate::SmallArena::<u32, 3>::new();
+    let index0 = arena.insert(0);
+    let index1 = arena.insert(1);
+    arena.insert(2);
+    arena.remove(index1);
+    let index3 = arena.insert(3);
+    assert_eq!(index3.slot(), index1.slot());
+    assert!(!arena.spilled());
+
+    let index4 = arena.insert(4);
+    assert!(arena.spilled());
+    assert_eq!(index4.slot(), 3);
+    assert_eq!(arena.get(index1), None);
+    assert_eq!(arena[index0], 0);
+    assert_eq!(arena[index3], 3);
+    assert_eq!(arena.len(), 4);
+
+    arena.iter_mut().for_each(|item| *item *= 10);
+    assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [0, 30, 20, 40]);
+    assert_eq!(arena.take(index3), Some(30));
+
+    let arena = arena.into_arena();
+    assert_eq!(arena.get(index4), Some(&40));
+    assert!(arena.validate().is_ok());
+}
+
+#[test]
+fn test_index_status() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    let index1 = arena.insert("Bar");
+    assert_eq!(arena.upgrade(index0), Some(index0));
+
+    arena.remove(index0);
+    assert_eq!(arena.status(index0), IndexStatus::Vacant);
+    assert_eq!(arena.upgrade(index0), None);
+
+    let reserved = arena.reserve_index();
+    assert_eq!(arena.status(reserved), IndexStatus::Pending);
+    assert_eq!(
+        arena.status(index0.with_tag(2)),
+        IndexStatus::Superseded {
+            current: reserved.with_tag(2)
+        }
+    );
+    assert_eq!(arena.upgrade(reserved), None);
+
+    arena.remove(index1);
+    arena.truncate_vacant_tail();
+    assert_eq!(arena.status(index1), IndexStatus::Vacant);
+    assert_eq!(arena.status(Index::DANGLING), IndexStatus::Vacant);
+    assert!(!IndexStatus::Pending.is_live());
+}
+
+#[test]
+fn test_index_status_checks_tag() {
+    let mut arena = Arena::new();
+    arena.set_tag_checker(|tag, item: &(u8, &str)| item.0 == tag);
+    let index = arena.insert((1, "Foo")).with_tag(1);
+    assert_eq!(arena.status(index), IndexStatus::Live);
+    assert_eq!(arena.upgrade(index), Some(index));
+
+    let wrong_tag = index.with_tag(2);
+    assert_eq!(arena.get(wrong_tag), None);
+    assert_eq!(arena.status(wrong_tag), IndexStatus::Rejected);
+    assert_eq!(arena.upgrade(wrong_tag), None);
+}
+
+#[test]
+fn test_filtered_view() {
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..10).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[4]);
+
+    let threshold = 3;
+    let view = arena.view(|_, item| item % 2 == 0 && *item > threshold);
+    assert_eq!(view.len(), 2);
+    assert!(!view.is_empty());
+    assert_eq!(view.get(indices[6]), Some(&6));
+    assert_eq!(view.get(indices[4]), None);
+    assert!(!view.contains(indices[2]));
+
+    let copy = view;
+    assert_eq!(copy.iter().map(|(_, item)| *item).collect::<Vec<_>>(), [6, 8]);
+    assert_eq!(
+        view.iter().rev().map(|(index, _)| index).collect::<Vec<_>>(),
+        [indices[8], indices[6]]
+    );
+    assert_eq!(view.as_arena().len(), 9);
+    assert!(arena.view(|_, item| *item > 100).is_empty());
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn test_metrics_hook() {
+    use crate::ArenaEvent;
+    use std::cell::RefCell;
+
+    std::thread_local! {
+        static EVENTS: RefCell<Vec<ArenaEvent>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(event: ArenaEvent) {
+        EVENTS.with(|events| events.borrow_mut().push(event));
+    }
+
+    let mut arena = Arena::with_capacity(2);
+    arena.set_metrics_hook(record);
+    let index0 = arena.insert("Foo");
+    let index1 = arena.insert("Bar");
+    arena.remove(index0);
+    arena.compact(|_, _| {});
+    arena.reserve_exact(10);
+    arena.clear_metrics_hook();
+    arena.insert("Baz");
+
+    let events = EVENTS.with(|events| events.take());
+    let moved = arena.iter_indexed().find(|(_, item)| **item == "Bar").unwrap().0;
+    assert_eq!(
+        events,
+        [
+            ArenaEvent::Inserted { index: index0, len: 1 },
+            ArenaEvent::Inserted { index: index1, len: 2 },
+            ArenaEvent::Removed { index: index0, len: 1 },
+            ArenaEvent::Compacted { relocated: 1, slots: 1 },
+            ArenaEvent::Grew {
+                previous: 2,
+                capacity: 11
+            },
+        ]
+    );
+    assert_ne!(moved, index1);
+}
+
+#[test]
+fn test_debug_and_dump_layout() {
+    let mut arena = Arena::new();
+    assert_eq!(format!("{:?}", arena), "{}");
+    assert_eq!(
+        arena.dump_layout().to_string(),
+        "0 slots, 0 occupied, 0 reserved, 0 vacant\nfree list: empty\n"
+    );
+
+    let indices: Vec<Index> = (0..70).map(|n| arena.insert(n)).collect();
+    for &index in &indices[..68] {
+        arena.remove(index);
+    }
+    assert_eq!(
+        format!("{:?}", arena),
+        format!("{{{:?}: 68, {:?}: 69}}", indices[68], indices[69])
+    );
+    assert!(format!("{:#?}", arena).contains(",\n"));
+
+    let dump = arena.dump_layout().to_string();
+    let lines: Vec<&str> = dump.lines().collect();
+    assert_eq!(lines[0], "70 slots, 2 occupied, 0 reserved, 68 vacant");
+    assert_eq!(lines[1], format!("     0 {}", ".".repeat(64)));
+    assert_eq!(lines[2], "    64 ....##");
+    assert!(lines[3].starts_with("free list: 67 -> 66 -> "));
+    assert!(lines[3].ends_with(" -> 1 -> 0"));
+}
+
+#[test]
+fn test_multi_arena() {
+    let mut arena = MultiArena::new();
+    let index0 = arena.insert((1, "Foo", 1.0));
+    let index1 = arena.insert((2, "Bar", 2.0));
+    let index2 = arena.insert((3, "Baz", 3.0));
+    assert_eq!(arena.len(), 3);
+
+    assert_eq!(arena.remove(index1), Some((2, "Bar", 2.0)));
+    assert_eq!(arena.remove(index1), None);
+    assert!(!arena.contains(index1));
+    assert_eq!(arena.get(index1), None);
+
+    let index3 = arena.insert((4, "Qux", 4.0));
+    assert_eq!(index3.slot(), index1.slot());
+    assert_eq!(arena.get(index3), Some((&4, &"Qux", &4.0)));
+
+    for (_, (number, _, float)) in arena.iter_mut() {
+        *number *= 10;
+        *float += 0.5;
+    }
+    *arena.get_mut(index2).unwrap().1 = "Quux";
+
+    let rows: Vec<_> = arena
+        .iter()
+        .map(|(index, (number, name, _))| (index, *number, *name))
+        .collect();
+    assert_eq!(rows, [(index0, 10, "Foo"), (index3, 40, "Qux"), (index2, 30, "Quux")]);
+    assert_eq!(arena.iter().len(), 3);
+
+    arena.clear();
+    assert!(arena.is_empty());
+    assert!(!arena.contains(index0));
+    assert_eq!(arena.iter().count(), 0);
+    let index4 = arena.insert((5, "Corge", 5.0));
+    assert_eq!(arena.get(index4), Some((&5, &"Corge", &5.0)));
+}
+
+#[test]
+fn test_index_remap_iter_and_then() {
+    let mut arena = Arena::new();
+    let indices: Vec<Index> = (0..6).map(|n| arena.insert(n)).collect();
+    for &slot in &[0, 2, 4, 5] {
+        arena.remove(indices[slot]);
+    }
+
+    let first = arena.compact_with_remap();
+    let pairs: Vec<(Index, Index)> = first.iter().collect();
+    assert_eq!(first.iter().len(), 2);
+    assert_eq!(pairs[0].0, indices[1]);
+    assert_eq!(pairs[1].0, indices[3]);
+    assert_eq!(first.iter().collect::<IndexRemap>(), first);
+
+    // Lands in a slot that wasn't moved by the first compaction.
+    let late = arena.insert(6);
+    arena.remove(first.translate(indices[1]).unwrap());
+    let second = arena.compact_with_remap();
+
+    let remap = first.then(&second);
+    assert_eq!(remap.len(), 3);
+    assert_eq!(arena.get(remap.translate(indices[3]).unwrap()), Some(&3));
+    assert_eq!(arena.get(remap.translate(late).unwrap()), Some(&6));
+    // Removed between the moves, so its translation is stale.
+    assert_eq!(arena.get(remap.translate(indices[1]).unwrap()), None);
+
+    assert!(IndexRemap::new().then(&second).iter().eq(second.iter()));
+    assert!(first.then(&IndexRemap::new()).iter().eq(first.iter()));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_index_remap() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    let index1 = arena.insert("Bar");
+    arena.remove(index0);
+    let remap = arena.compact_with_remap();
+
+    let json = serde_json::to_string(&remap).unwrap();
+    let loaded: IndexRemap = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, remap);
+    assert_eq!(loaded.translate(index1), remap.translate(index1));
+
+    let pair = serde_json::to_string(&(index1, index0)).unwrap();
+    let duplicate = format!("[{},{}]", pair, pair);
+    assert!(serde_json::from_str::<IndexRemap>(&duplicate).is_err());
+
+    // a far away slot is stored sparsely, not by growing to it
+    let far = r#"[[{"slot":72057594037927935,"generation":1},{"slot":0,"generation":1}]]"#;
+    let loaded: IndexRemap = serde_json::from_str(far).unwrap();
+    assert_eq!(loaded.len(), 1);
+    let (old, new) = loaded.iter().next().unwrap();
+    assert_eq!(old.slot(), 72057594037927935);
+    assert_eq!(loaded.translate(old), Some(new));
+}
+
+#[test]
+fn test_arena_cell() {
+    let mut arena = Arena::new();
+    let index0 = arena.insert(String::from("Foo"));
+    let index1 = arena.insert(String::from("Bar"));
+    let mut cell = ArenaCell::from_arena(arena);
+    let index2 = cell.insert(String::from("Baz"));
+
+    {
+        let cell = &cell;
+        let mut a = cell.borrow_mut(index0).unwrap();
+        let b = cell.borrow(index1).unwrap();
+        let c = cell.borrow(index1).unwrap();
+        a.push_str(&b);
+        assert_eq!(*c, "Bar");
+
+        assert_eq!(cell.try_borrow(index0).unwrap_err(), BorrowError::Borrowed(index0));
+        assert_eq!(cell.try_borrow_mut(index1).unwrap_err(), BorrowError::Borrowed(index1));
+        assert!(cell.try_borrow_mut(index2).is_ok());
+    }
+
+    cell.get_mut(index2).unwrap().push('!');
+    cell.remove(index1);
+    assert!(!cell.contains(index1));
+    assert!(cell.borrow(index1).is_none());
+    assert!(matches!(
+        cell.try_borrow_mut(index1),
+        Err(BorrowError::Unresolved(ArenaError::Vacant(_)))
+    ));
+    assert_eq!(cell.len(), 2);
+
+    let arena = cell.into_arena();
+    assert_eq!(arena[index0], "FooBar");
+    assert_eq!(arena[index2], "Baz!");
+}
+
+#[test]
+fn test_index_ordering_and_stable_hash() {
+    use core::cmp::Ordering;
+    use core::hash::{Hash, Hasher};
+
+    /// Records the bytes written, as a stand-in for a portable hasher.
+    #[derive(Default)]
+    struct Recorder(Vec<u8>);
+
+    impl Hasher for Recorder {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    let mut arena = Arena::new();
+    let index0 = arena.insert("Foo");
+    let index1 = arena.insert("Bar");
+    arena.remove(index0);
+    let index2 = arena.insert("Baz");
+
+    let mut sorted = vec![index1, index2, index0, index0.with_tag(1)];
+    sorted.sort();
+    assert_eq!(sorted, [index0, index0.with_tag(1), index2, index1]);
+    assert_eq!(index0.cmp_slot_only(&index2), Ordering::Equal);
+    assert_eq!(index2.cmp_slot_only(&index1), Ordering::Less);
+
+    let mut hasher = Recorder::default();
+    index2.with_tag(3).hash(&mut hasher);
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&0u64.to_ne_bytes());
+    expected.extend_from_slice(&2u64.to_ne_bytes());
+    expected.push(3);
+    assert_eq!(hasher.0, expected);
+
+    let mut hasher = Recorder::default();
+    index2.slot_key().hash(&mut hasher);
+    assert_eq!(hasher.0, 0u64.to_ne_bytes());
+    assert_eq!(index0.slot_key(), SlotKey::from(index2));
+    assert!(index2.slot_key() < index1.slot_key());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_slot_key() {
+    let key = SlotKey::new(7);
+    let json = serde_json::to_string(&key).unwrap();
+    assert_eq!(json, "7");
+    assert_eq!(serde_json::from_str::<SlotKey>(&json).unwrap(), key);
+    assert!(serde_json::from_str::<SlotKey>(&u64::MAX.to_string()).is_err());
+}
+
+#[test]
+fn test_clone_from_and_copy_from() {
+    let mut source = Arena::new();
+    let index0 = source.insert(String::from("Foo"));
+    let index1 = source.insert(String::from("Bar"));
+
+    let mut target = Arena::with_capacity(16);
+    let first = target.insert(String::with_capacity(64));
+    let buffer = target[first].as_ptr();
+
+    target.clone_from(&source);
+    assert_eq!(target, source);
+    assert_eq!(target.capacity(), 16);
+    assert_eq!(target[index0].as_ptr(), buffer);
+    assert_eq!(target[index1], "Bar");
+
+    source.remove(index0);
+    let index2 = source.insert(String::from("Baz"));
+    target.clone_from(&source);
+    assert_eq!(target.get(index0), None);
+    assert_eq!(target[index2], "Baz");
+    assert_eq!(target.clone(), source);
+
+    // Settings are kept by copy_from, and the budget is measured again.
+    let mut limited = Arena::with_limit(4);
+    limited.set_memory_budget(usize::MAX, |item: &String| item.capacity());
+    let storage = limited.memory_usage();
+    limited.copy_from(&source);
+    assert_eq!(limited, source);
+    assert_eq!(
+        limited.memory_usage(),
+        limited.data.capacity() * core::mem::size_of::<crate::Entry<String>>() + 6
+    );
+    assert!(storage <= limited.memory_usage());
+
+    limited.insert(String::new());
+    limited.insert(String::new());
+    assert!(limited.try_insert(String::new()).is_err());
+}
+
+#[test]
+fn test_vacant_slots_and_preallocate() {
+    let mut arena = Arena::new();
+    assert_eq!(arena.vacant_len(), 0);
+    assert_eq!(arena.vacant_slots().next(), None);
+
+    arena.preallocate_slots(3);
+    assert_eq!(arena.len(), 0);
+    assert_eq!(arena.vacant_len(), 3);
+    assert!(arena.vacant_slots().eq([0, 1, 2]));
+
+    let indices: Vec<Index> = (0..4).map(|n| arena.insert(n)).collect();
+    assert_eq!(
+        indices.iter().map(|index| index.slot()).collect::<Vec<_>>(),
+        [0, 1, 2, 3]
+    );
+    assert_eq!(arena.vacant_len(), 0);
+
+    arena.remove(indices[2]);
+    let reserved = arena.reserve_index();
+    arena.remove(indices[0]);
+    arena.preallocate_slots(2);
+    assert_eq!(reserved.slot(), 2);
+    assert_eq!(arena.vacant_len(), 3);
+    assert!(arena.vacant_slots().eq([0, 4, 5]));
+    assert!(arena.validate().is_ok());
+
+    // Existing vacant slots are reused first, then the new ones in order.
+    let slots: Vec<usize> = (0..3).map(|n| arena.insert(n).slot()).collect();
+    assert_eq!(slots, [0, 4, 5]);
+    assert_eq!(arena.insert(7).slot(), 6);
+
+    let mut fifo = Arena::with_reuse_policy(crate::ReusePolicy::Fifo);
+    fifo.preallocate_slots(2);
+    let index = fifo.insert("Foo");
+    fifo.remove(index);
+    assert!(fifo.vacant_slots().eq([1, 0]));
+    assert!(fifo.validate().is_ok());
+}
+
+#[test]
+fn test_secondary_map_reads_relocations_without_flush() {
+    let mut arena = Arena::new();
+    let mut map = SecondaryMap::new();
+    arena.link(&mut map);
+
+    let index0 = arena.insert("a");
+    let index1 = arena.insert("b");
+    let index2 = arena.insert("c");
+    map.insert(index1, "B");
+    map.insert(index2, "C");
+    arena.remove(index0);
+
+    let mut moved1 = index1;
+    let mut moved2 = index2;
+    arena.compact(|old, new| {
+        if old == index1 {
+            moved1 = new;
+        } else if old == index2 {
+            moved2 = new;
+        }
+    });
+
+    assert_eq!(map.get(moved1), Some(&"B"));
+    assert_eq!(map.get(index1), None);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.iter().collect::<Vec<_>>(), [(moved1, &"B"), (moved2, &"C")]);
+    let joined: Vec<_> = map.join(&arena).map(|(index, _, value)| (index, *value)).collect();
+    assert_eq!(joined, [(moved1, "B"), (moved2, "C")]);
+    assert_eq!(join((&arena, &map)).count(), 2);
+
+    let (swapped2, swapped1) = arena.swap_slots(moved2, moved1).unwrap();
+    assert_eq!(map.get(swapped1), Some(&"B"));
+    assert_eq!(map.get(swapped2), Some(&"C"));
+
+    arena.remove(swapped2);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(swapped2), None);
+
+    // Flushing moves the values, and changes nothing reads can see.
+    map.flush();
+    assert_eq!(map.get(swapped1), Some(&"B"));
+    assert_eq!(map.iter().collect::<Vec<_>>(), [(swapped1, &"B")]);
+
+    for _ in 0..100 {
+        let index = arena.insert("d");
+        arena.remove(index);
+    }
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_try_accessors_in_strict_mode() {
+    let mut arena = Arena::new();
+    arena.set_strict(true);
+    let stale = arena.insert("Foo");
+    arena.remove(stale);
+    let live = arena.insert("Bar");
+    assert_eq!(live.slot(), stale.slot());
+    let other = arena.insert("Baz");
+
+    let error = Err(arena.try_get(stale).unwrap_err());
+    assert!(matches!(error, Err(ArenaError::StaleGeneration { .. })));
+    assert_eq!(arena.try_get_mut(stale).map(|_| ()), error);
+    assert_eq!(arena.try_take(stale).map(|_| ()), error);
+    assert_eq!(arena.try_remove(stale), error);
+    assert_eq!(arena.try_swap(stale, other), error);
+    assert_eq!(arena.try_swap(other, stale), error);
+    assert_eq!(arena.try_swap(live, stale), error);
+
+    assert_eq!(arena.try_swap(live, other), Ok(()));
+    assert_eq!(arena.try_swap(live, live), Ok(()));
+    assert_eq!(arena[live], "Baz");
+    assert_eq!(arena.try_take(other), Ok("Bar"));
+}
+
+#[test]
+fn test_try_accessors_skip_stale_handler() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static REPORTED: AtomicUsize = AtomicUsize::new(0);
+
+    let mut arena = Arena::new();
+    arena.set_stale_handler(|_| {
+        REPORTED.fetch_add(1, Ordering::Relaxed);
+    });
+    let index = arena.insert(1);
+    let other = arena.insert(2);
+    arena.remove(index);
+
+    assert!(arena.try_get(index).is_err());
+    assert!(arena.try_get_mut(index).is_err());
+    assert!(arena.try_take(index).is_err());
+    assert!(arena.try_remove(index).is_err());
+    assert!(arena.try_swap(index, other).is_err());
+    assert_eq!(REPORTED.load(Ordering::Relaxed), 0);
+
+    arena.get(index);
+    assert_eq!(REPORTED.load(Ordering::Relaxed), 1);
+}
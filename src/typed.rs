@@ -0,0 +1,244 @@
+//! Arenas whose indices are distinct types, to prevent mixing up
+//! handles from different arenas.
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+use crate::{Arena, Index, IterIndexed};
+
+/// An [`Index`] that only resolves in a [`TypedArena`] with marker `M`.
+///
+/// The marker defaults to the item type of the arena. It is only used at
+/// compile time, so a typed index is the same size as an [`Index`].
+pub struct TypedIndex<M> {
+    index: Index,
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<M> TypedIndex<M> {
+    /// Wrap an untyped index.
+    ///
+    /// Nothing checks that the index came from an arena with marker `M`.
+    pub fn from_untyped(index: Index) -> Self {
+        TypedIndex {
+            index,
+            marker: PhantomData,
+        }
+    }
+
+    /// The underlying untyped index.
+    pub fn untyped(self) -> Index {
+        self.index
+    }
+}
+
+impl<M> Clone for TypedIndex<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for TypedIndex<M> {}
+
+impl<M> PartialEq for TypedIndex<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<M> Eq for TypedIndex<M> {}
+
+impl<M> PartialOrd for TypedIndex<M> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<M> Ord for TypedIndex<M> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<M> Hash for TypedIndex<M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state)
+    }
+}
+
+impl<M> fmt::Debug for TypedIndex<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedIndex").field(&self.index).finish()
+    }
+}
+
+/// An [`Arena`] handing out [`TypedIndex`] handles with marker `M`.
+///
+/// Indices from arenas with different markers are different types, so
+/// using one in the wrong arena fails to compile:
+///
+/// ```compile_fail
+/// # use arena::typed::TypedArena;
+/// struct Mesh;
+/// struct Texture;
+///
+/// let mut meshes = TypedArena::<Mesh>::new();
+/// let textures = TypedArena::<Texture>::new();
+///
+/// let mesh = meshes.insert(Mesh);
+/// textures.get(mesh);
+/// ```
+///
+/// Use an explicit marker to tell apart arenas holding the same item type:
+///
+/// ```
+/// # use arena::typed::TypedArena;
+/// enum Players {}
+/// enum Enemies {}
+///
+/// let mut players = TypedArena::<&str, Players>::new();
+/// let mut enemies = TypedArena::<&str, Enemies>::new();
+///
+/// let player = players.insert("Foo");
+/// let enemy = enemies.insert("Bar");
+/// assert_eq!(players.get(player), Some(&"Foo"));
+/// # assert_eq!(enemies.get(enemy), Some(&"Bar"));
+/// ```
+pub struct TypedArena<T, M = T> {
+    arena: Arena<T>,
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<T, M> TypedArena<T, M> {
+    pub fn new() -> Self {
+        Self::from_untyped(Arena::new())
+    }
+
+    /// Wrap an untyped arena. Its existing indices can be converted
+    /// with [`TypedIndex::from_untyped`].
+    pub fn from_untyped(arena: Arena<T>) -> Self {
+        TypedArena {
+            arena,
+            marker: PhantomData,
+        }
+    }
+
+    /// Unwrap the underlying untyped arena.
+    pub fn into_untyped(self) -> Arena<T> {
+        self.arena
+    }
+
+    /// The underlying untyped arena.
+    pub fn as_untyped(&self) -> &Arena<T> {
+        &self.arena
+    }
+
+    /// The underlying untyped arena, for operations not exposed on
+    /// the typed arena.
+    pub fn as_untyped_mut(&mut self) -> &mut Arena<T> {
+        &mut self.arena
+    }
+
+    /// See [`Arena::push`].
+    pub fn push(&mut self, item: T) -> TypedIndex<M> {
+        TypedIndex::from_untyped(self.arena.push(item))
+    }
+
+    /// See [`Arena::insert`].
+    pub fn insert(&mut self, item: T) -> TypedIndex<M> {
+        TypedIndex::from_untyped(self.arena.insert(item))
+    }
+
+    /// See [`Arena::remove`].
+    pub fn remove(&mut self, index: TypedIndex<M>) {
+        self.arena.remove(index.index)
+    }
+
+    /// See [`Arena::take`].
+    pub fn take(&mut self, index: TypedIndex<M>) -> Option<T> {
+        self.arena.take(index.index)
+    }
+
+    /// See [`Arena::get`].
+    pub fn get(&self, index: TypedIndex<M>) -> Option<&T> {
+        self.arena.get(index.index)
+    }
+
+    /// See [`Arena::get_mut`].
+    pub fn get_mut(&mut self, index: TypedIndex<M>) -> Option<&mut T> {
+        self.arena.get_mut(index.index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Iterate the items in the arena together with their typed indices.
+    pub fn iter(&self) -> Iter<'_, T, M> {
+        Iter {
+            inner: self.arena.iter_indexed(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, M> Default for TypedArena<T, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, M> Clone for TypedArena<T, M> {
+    fn clone(&self) -> Self {
+        Self::from_untyped(self.arena.clone())
+    }
+}
+
+impl<T: fmt::Debug, M> fmt::Debug for TypedArena<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedArena").field(&self.arena).finish()
+    }
+}
+
+pub struct Iter<'a, T, M> {
+    inner: IterIndexed<'a, T>,
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<'a, T, M> Iterator for Iter<'a, T, M> {
+    type Item = (TypedIndex<M>, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(index, item)| (TypedIndex::from_untyped(index), item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, M> DoubleEndedIterator for Iter<'_, T, M> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(index, item)| (TypedIndex::from_untyped(index), item))
+    }
+}
+
+impl<T, M> ExactSizeIterator for Iter<'_, T, M> {}
+
+impl<T, M> FusedIterator for Iter<'_, T, M> {}
+
+impl<T: fmt::Debug, M> fmt::Debug for Iter<'_, T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.inner).finish()
+    }
+}
@@ -0,0 +1,132 @@
+//! Inspecting and pre-seeding the vacant slots.
+use core::iter::FusedIterator;
+
+use crate::{Arena, Entry, Index};
+
+impl<T> Arena<T> {
+    /// Number of vacant slots waiting to be reused.
+    ///
+    /// [Retired](crate::GenerationOverflow::Retire) slots and pending
+    /// [reservations](Arena::reserve_index) aren't counted.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// arena.insert("Bar");
+    /// arena.remove(index);
+    /// assert_eq!(arena.vacant_len(), 1);
+    /// ```
+    pub fn vacant_len(&self) -> usize {
+        self.data
+            .iter()
+            .filter(|entry| entry.is_vacant() && !self.is_retired(entry))
+            .count()
+    }
+
+    /// Iterate the vacant slots, in the order they will be reused by
+    /// the next insertions.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert("Foo");
+    /// let index1 = arena.insert("Bar");
+    /// arena.remove(index0);
+    /// arena.remove(index1);
+    ///
+    /// assert!(arena.vacant_slots().eq([1, 0]));
+    /// assert_eq!(arena.insert("Baz").slot(), 1);
+    /// ```
+    pub fn vacant_slots(&self) -> VacantSlots<'_, T> {
+        VacantSlots {
+            data: &self.data,
+            next: self.free_head,
+        }
+    }
+
+    /// Append `n` vacant slots to storage, which are reused in slot order
+    /// after the slots already vacant.
+    ///
+    /// Pre-carving the slot space makes the indices handed out afterwards
+    /// depend only on the sequence of insertions and removals, and not on
+    /// when storage happened to grow. The slots are dropped again by
+    /// [`Arena::truncate_vacant_tail`] and [`Arena::shrink_to_fit`].
+    ///
+    /// # Panic
+    ///
+    /// Panics if the arena would exceed the maximum number of slots.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// arena.remove(index);
+    /// arena.preallocate_slots(2);
+    ///
+    /// assert_eq!(arena.vacant_len(), 3);
+    /// assert!(arena.vacant_slots().eq([0, 1, 2]));
+    /// ```
+    pub fn preallocate_slots(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let first = self.data.len();
+        assert!(
+            first.checked_add(n).is_some_and(|len| len <= Index::SLOT_MASK),
+            "arena exceeded the maximum number of slots"
+        );
+
+        let mut tail = None;
+        let mut cursor = self.free_head;
+        while let Some(slot) = cursor {
+            tail = Some(slot);
+            cursor = self.data[slot].next_free();
+        }
+
+        let capacity = self.data.capacity();
+        self.data.reserve(n);
+        for slot in first..first + n {
+            let next = if slot + 1 < first + n { Some(slot + 1) } else { None };
+            self.data.push(Entry::Vacant {
+                generation: self.generation,
+                next,
+            });
+        }
+        self.metric_grew(capacity);
+
+        match tail {
+            Some(tail) => self.data[tail].set_next_free(Some(first)),
+            None => self.free_head = Some(first),
+        }
+        self.free_tail = Some(first + n - 1);
+    }
+}
+
+/// Iterator over the vacant slots, see [`Arena::vacant_slots`].
+#[derive(Debug)]
+pub struct VacantSlots<'a, T> {
+    data: &'a [Entry<T>],
+    next: Option<usize>,
+}
+
+impl<T> Iterator for VacantSlots<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot = self.next?;
+        self.next = self.data[slot].next_free();
+        Some(slot)
+    }
+}
+
+impl<T> FusedIterator for VacantSlots<'_, T> {}
+
+impl<T> Clone for VacantSlots<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data,
+            next: self.next,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Self-checks of the arena's internal invariants.
+use alloc::vec;
+use core::fmt;
+
+use crate::{Arena, Entry, Index};
+
+/// Broken invariant found by [`Arena::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Storage holds more slots than an index can address.
+    TooManySlots(usize),
+    /// The free list points beyond the arena's storage.
+    FreeListOutOfBounds(usize),
+    /// The free list points to a slot that isn't vacant.
+    FreeListNotVacant(usize),
+    /// The free list points to a [retired](crate::GenerationOverflow::Retire) slot.
+    FreeListRetired(usize),
+    /// The free list visits the slot a second time.
+    FreeListCycle(usize),
+    /// The vacant slot can't be reached from the free list.
+    Unlinked(usize),
+    /// The item count doesn't match the number of occupied slots.
+    CountMismatch { count: usize, occupied: usize },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::TooManySlots(len) => {
+                write!(f, "storage holds {} slots, more than indices can address", len)
+            }
+            ValidationError::FreeListOutOfBounds(slot) => write!(f, "free list points to slot {} out of bounds", slot),
+            ValidationError::FreeListNotVacant(slot) => {
+                write!(f, "free list points to slot {} which isn't vacant", slot)
+            }
+            ValidationError::FreeListRetired(slot) => write!(f, "free list points to retired slot {}", slot),
+            ValidationError::FreeListCycle(slot) => write!(f, "free list visits slot {} twice", slot),
+            ValidationError::Unlinked(slot) => write!(f, "vacant slot {} isn't on the free list", slot),
+            ValidationError::CountMismatch { count, occupied } => {
+                write!(f, "item count is {}, but {} slots are occupied", count, occupied)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+impl<T> Arena<T> {
+    /// Check the arena's internal invariants, and report the first one
+    /// found broken.
+    ///
+    /// Verifies that the free list is acyclic, only links vacant slots and
+    /// reaches all of them except retired ones, that the item count matches the occupied slots,
+    /// and that every slot can be addressed by an index. A correct arena
+    /// always passes, so this is meant for debugging code that manipulates
+    /// it through `unsafe`. Runs in linear time, and allocates a bit per slot.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index = arena.insert("Foo");
+    /// arena.insert("Bar");
+    /// arena.remove(index);
+    ///
+    /// assert_eq!(arena.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.data.len() > Index::SLOT_MASK {
+            return Err(ValidationError::TooManySlots(self.data.len()));
+        }
+
+        let mut linked = vec![false; self.data.len()];
+        let mut next = self.free_head;
+        while let Some(slot) = next {
+            next = match self.data.get(slot) {
+                None => return Err(ValidationError::FreeListOutOfBounds(slot)),
+                Some(_) if linked[slot] => return Err(ValidationError::FreeListCycle(slot)),
+                Some(entry) if self.is_retired(entry) => return Err(ValidationError::FreeListRetired(slot)),
+                Some(Entry::Vacant { next, .. }) => *next,
+                Some(_) => return Err(ValidationError::FreeListNotVacant(slot)),
+            };
+            linked[slot] = true;
+        }
+
+        let mut occupied = 0;
+        for (slot, entry) in self.data.iter().enumerate() {
+            match entry {
+                Entry::Vacant { .. } if !linked[slot] && !self.is_retired(entry) => {
+                    return Err(ValidationError::Unlinked(slot))
+                }
+                Entry::Occupied { .. } => occupied += 1,
+                _ => {}
+            }
+        }
+
+        if occupied != self.count {
+            return Err(ValidationError::CountMismatch {
+                count: self.count,
+                occupied,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,453 @@
+//! Views over a subset of an arena.
+use core::fmt;
+use core::iter::Enumerate;
+use core::slice::{ChunksMut as SliceChunksMut, IterMut as SliceIterMut};
+
+use crate::checks::Stamp;
+use crate::{set, Arena, Entry, Index, IndexSet, IterIndexed};
+
+/// Read-only view of the items in an [`Arena`] selected by an [`IndexSet`].
+///
+/// Stale indices in the selection are ignored.
+#[derive(Debug)]
+pub struct ArenaView<'a, T> {
+    arena: &'a Arena<T>,
+    selection: &'a IndexSet,
+}
+
+impl<'a, T> ArenaView<'a, T> {
+    pub fn new(arena: &'a Arena<T>, selection: &'a IndexSet) -> Self {
+        Self { arena, selection }
+    }
+
+    /// Return a reference to the item at the given `index`, if it
+    /// is part of the selection.
+    pub fn get(&self, index: Index) -> Option<&'a T> {
+        if self.selection.contains(index) {
+            self.arena.lookup(index)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Number of selected items that are still live in the arena.
+    ///
+    /// This walks the whole selection.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Iterate the live selected items, together with their indices.
+    pub fn iter(&self) -> Iter<'a, T> {
+        Iter {
+            arena: self.arena,
+            inner: self.selection.iter(),
+        }
+    }
+}
+
+impl<T> Clone for ArenaView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaView<'_, T> {}
+
+impl<'a, T> IntoIterator for ArenaView<'a, T> {
+    type Item = (Index, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    arena: &'a Arena<T>,
+    inner: set::Iter<'a>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for index in self.inner.by_ref() {
+            if let Some(item) = self.arena.lookup(index) {
+                return Some((index, item));
+            }
+        }
+
+        None
+    }
+}
+
+/// Read-only view of the items in an [`Arena`] matching a predicate,
+/// created by [`Arena::view`].
+///
+/// The predicate is evaluated on every access, so the view stays current
+/// without collecting the matching indices. It can be kept and iterated
+/// repeatedly while the arena is borrowed.
+#[derive(Clone, Copy)]
+pub struct FilteredView<'a, T, F> {
+    arena: &'a Arena<T>,
+    predicate: F,
+}
+
+impl<T> Arena<T> {
+    /// Create a read-only view of the items for which `predicate`
+    /// returns `true`.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let near = arena.insert(("Foo", 1.0));
+    /// let far = arena.insert(("Bar", 100.0));
+    ///
+    /// let visible = arena.view(|_, (_, depth)| *depth < 10.0);
+    /// assert_eq!(visible.len(), 1);
+    /// assert_eq!(visible.get(far), None);
+    /// for (index, (name, _)) in visible.iter() {
+    ///     assert_eq!((index, *name), (near, "Foo"));
+    /// }
+    /// ```
+    pub fn view<F>(&self, predicate: F) -> FilteredView<'_, T, F>
+    where
+        F: Fn(Index, &T) -> bool,
+    {
+        FilteredView { arena: self, predicate }
+    }
+}
+
+impl<'a, T, F> FilteredView<'a, T, F>
+where
+    F: Fn(Index, &T) -> bool,
+{
+    /// Return a reference to the item at the given `index`, if it
+    /// matches the predicate.
+    pub fn get(&self, index: Index) -> Option<&'a T> {
+        self.arena.lookup(index).filter(|item| (self.predicate)(index, item))
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Number of matching items.
+    ///
+    /// This evaluates the predicate for every item.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Iterate the matching items in slot order, together with their indices.
+    pub fn iter(&self) -> FilteredIter<'a, '_, T, F> {
+        FilteredIter {
+            inner: self.arena.iter_indexed(),
+            predicate: &self.predicate,
+        }
+    }
+
+    /// The whole arena, including the items the view leaves out.
+    pub fn as_arena(&self) -> &'a Arena<T> {
+        self.arena
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for FilteredView<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FilteredView").field(&self.arena).finish()
+    }
+}
+
+pub struct FilteredIter<'a, 'f, T, F> {
+    inner: IterIndexed<'a, T>,
+    predicate: &'f F,
+}
+
+impl<'a, T, F> Iterator for FilteredIter<'a, '_, T, F>
+where
+    F: Fn(Index, &T) -> bool,
+{
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let predicate = self.predicate;
+        self.inner.find(|(index, item)| predicate(*index, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+impl<T, F> DoubleEndedIterator for FilteredIter<'_, '_, T, F>
+where
+    F: Fn(Index, &T) -> bool,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let predicate = self.predicate;
+        self.inner.rfind(|(index, item)| predicate(*index, item))
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for FilteredIter<'_, '_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FilteredIter").field(&self.inner).finish()
+    }
+}
+
+/// Mutable view of a contiguous range of slots in an [`Arena`].
+///
+/// Created by [`Arena::split_at_slot_mut`]. Indices pointing
+/// outside of the view's range resolve to `None`.
+#[derive(Debug)]
+pub struct ArenaViewMut<'a, T> {
+    data: &'a mut [Entry<T>],
+    offset: usize,
+    tag_checker: Option<fn(u8, &T) -> bool>,
+    stamp: Stamp,
+}
+
+impl<'a, T> ArenaViewMut<'a, T> {
+    /// The first slot covered by the view.
+    pub fn start_slot(&self) -> usize {
+        self.offset
+    }
+
+    /// One past the last slot covered by the view.
+    pub fn end_slot(&self) -> usize {
+        self.offset + self.data.len()
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        let slot = index.slot().checked_sub(self.offset)?;
+
+        match self.data.get(slot) {
+            Some(Entry::Occupied { generation, item })
+                if index.generation == *generation && self.tag_checker.is_none_or(|check| check(index.tag(), item)) =>
+            {
+                Some(item)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        let slot = index.slot().checked_sub(self.offset)?;
+
+        match self.data.get_mut(slot) {
+            Some(Entry::Occupied { generation, item })
+                if index.generation == *generation && self.tag_checker.is_none_or(|check| check(index.tag(), item)) =>
+            {
+                Some(item)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Split the view at slot `mid` into two views, covering the slots
+    /// `[start_slot, mid)` and `[mid, end_slot)` respectively.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `mid` is outside of the view's range.
+    pub fn split_at_mut(self, mid: usize) -> (ArenaViewMut<'a, T>, ArenaViewMut<'a, T>) {
+        assert!(
+            (self.start_slot()..=self.end_slot()).contains(&mid),
+            "slot {} is outside of the view",
+            mid
+        );
+        let (left, right) = self.data.split_at_mut(mid - self.offset);
+
+        (
+            ArenaViewMut {
+                data: left,
+                offset: self.offset,
+                tag_checker: self.tag_checker,
+                stamp: self.stamp,
+            },
+            ArenaViewMut {
+                data: right,
+                offset: mid,
+                tag_checker: self.tag_checker,
+                stamp: self.stamp,
+            },
+        )
+    }
+
+    /// Mutably iterate the items in the view, together with their indices.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.data.iter_mut().enumerate(),
+            offset: self.offset,
+            stamp: self.stamp,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for ArenaViewMut<'a, T> {
+    type Item = (Index, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IterMut {
+            inner: self.data.iter_mut().enumerate(),
+            offset: self.offset,
+            stamp: self.stamp,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IterMut<'a, T> {
+    inner: Enumerate<SliceIterMut<'a, Entry<T>>>,
+    offset: usize,
+    stamp: Stamp,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (Index, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (slot, entry) in self.inner.by_ref() {
+            if let Entry::Occupied { generation, item } = entry {
+                return Some((self.stamp.index(self.offset + slot, *generation), item));
+            }
+        }
+
+        None
+    }
+}
+
+impl<T> Arena<T> {
+    /// Split the arena's storage at slot `mid` into two mutable views,
+    /// covering the slots `[0, mid)` and `[mid, len)` respectively.
+    ///
+    /// The views can be used independently, for example from two threads.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// let index0 = arena.insert(1);
+    /// let index1 = arena.insert(2);
+    ///
+    /// let (mut left, mut right) = arena.split_at_slot_mut(1);
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| left.iter_mut().for_each(|(_, item)| *item *= 10));
+    ///     scope.spawn(|| right.iter_mut().for_each(|(_, item)| *item *= 100));
+    /// });
+    /// # assert_eq!(arena.get(index0), Some(&10));
+    /// # assert_eq!(arena.get(index1), Some(&200));
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if `mid` is greater than the number of slots.
+    pub fn split_at_slot_mut(&mut self, mid: usize) -> (ArenaViewMut<'_, T>, ArenaViewMut<'_, T>) {
+        // Views don't record revisions, so conservatively
+        // count every item as changed up front.
+        self.touch_occupied();
+
+        let tag_checker = self.tag_checker;
+        let stamp = self.stamper();
+        let (left, right) = self.data.split_at_mut(mid);
+
+        (
+            ArenaViewMut {
+                data: left,
+                offset: 0,
+                tag_checker,
+                stamp,
+            },
+            ArenaViewMut {
+                data: right,
+                offset: mid,
+                tag_checker,
+                stamp,
+            },
+        )
+    }
+
+    /// Split the arena's storage into mutable views of `chunk_size` slots
+    /// each. The last view is shorter if the number of slots isn't a
+    /// multiple of `chunk_size`.
+    ///
+    /// ```
+    /// # use arena::Arena;
+    /// # let mut arena = Arena::new();
+    /// for n in 0..10 {
+    ///     arena.insert(n);
+    /// }
+    ///
+    /// std::thread::scope(|scope| {
+    ///     for chunk in arena.chunks_mut(4) {
+    ///         scope.spawn(|| chunk.into_iter().for_each(|(_, item)| *item *= 2));
+    ///     }
+    /// });
+    /// # assert_eq!(arena.iter().sum::<i32>(), 90);
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn chunks_mut(&mut self, chunk_size: usize) -> ChunksMut<'_, T> {
+        self.touch_occupied();
+
+        ChunksMut {
+            stamp: self.stamper(),
+            inner: self.data.chunks_mut(chunk_size),
+            offset: 0,
+            chunk_size,
+            tag_checker: self.tag_checker,
+        }
+    }
+}
+
+/// Iterator over mutable views of consecutive slot ranges, created by [`Arena::chunks_mut`].
+#[derive(Debug)]
+pub struct ChunksMut<'a, T> {
+    inner: SliceChunksMut<'a, Entry<T>>,
+    offset: usize,
+    chunk_size: usize,
+    tag_checker: Option<fn(u8, &T) -> bool>,
+    stamp: Stamp,
+}
+
+impl<'a, T> Iterator for ChunksMut<'a, T> {
+    type Item = ArenaViewMut<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = self.inner.next()?;
+        let offset = self.offset;
+        self.offset += self.chunk_size;
+
+        Some(ArenaViewMut {
+            data,
+            offset,
+            tag_checker: self.tag_checker,
+            stamp: self.stamp,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
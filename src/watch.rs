@@ -0,0 +1,49 @@
+//! Slot watchpoints for debugging.
+use alloc::vec::Vec;
+
+use crate::Index;
+
+/// Kind of access that triggered a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Access {
+    Get,
+    GetMut,
+    Remove,
+}
+
+/// Function invoked when a watched slot is accessed.
+pub type WatchFn = fn(Access, Index);
+
+/// Registered watchpoints, keyed by slot.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Watchpoints {
+    entries: Vec<(usize, WatchFn)>,
+}
+
+impl Watchpoints {
+    pub(crate) fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    pub(crate) fn insert(&mut self, slot: usize, callback: WatchFn) {
+        self.entries.push((slot, callback));
+    }
+
+    pub(crate) fn remove(&mut self, slot: usize) {
+        self.entries.retain(|(watched, _)| *watched != slot);
+    }
+
+    #[inline]
+    pub(crate) fn notify(&self, slot: usize, access: Access, index: Index) {
+        // Fast path, most arenas have no watchpoints.
+        if self.entries.is_empty() {
+            return;
+        }
+
+        for (watched, callback) in &self.entries {
+            if *watched == slot {
+                callback(access, index);
+            }
+        }
+    }
+}